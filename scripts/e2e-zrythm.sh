@@ -59,8 +59,8 @@ section "Build"
 # ── 2. Unit tests (no JACK/Zrythm needed) ───────────────────────────────────
 section "Unit tests"
 (cd "$(dirname "$0")/../src-tauri" \
-    && cargo test engine::zrythm::tests 2>&1 | tail -10) \
-    && ok "Zrythm unit tests passed" || fail "Zrythm unit tests failed"
+    && cargo test engine:: 2>&1 | tail -10) \
+    && ok "engine unit tests passed" || fail "engine unit tests failed"
 
 # ── 3. MIDI port registration ────────────────────────────────────────────────
 section "JACK MIDI port registration"
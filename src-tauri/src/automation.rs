@@ -0,0 +1,506 @@
+//! Automation recorded from Maestro's own control moves.
+//!
+//! While recording is on and the transport is playing, every fader, pan,
+//! mute, solo, send and custom param move made through Maestro's commands
+//! is stored as a point on that target's [`Lane`], at the song position it
+//! happened.  Lanes are stored in the open workspace when a pass ends and
+//! as the workspace autosaves; lanes recorded before any workspace opened
+//! go into the first one that does.
+//!
+//! Playback follows the song time the engine reports ([`follow`]) and sends
+//! each lane's value at that position through the engine adapter, without
+//! journaling it.  Lanes being recorded are left alone until the transport
+//! stops, so playback doesn't fight the hand on the fader.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::engine::{EngineAdapter, EngineState, StateChange, StateManager};
+use crate::journal::{Edit, Journal};
+use crate::workspace::Workspaces;
+
+/// Points closer than this (in beats) count as the same position.
+const EPSILON: f64 = 1e-6;
+
+/// What a lane automates.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Target {
+    TrackVolume { track: usize },
+    TrackPan { track: usize },
+    TrackMute { track: usize },
+    TrackSolo { track: usize },
+    TrackSend { track: usize, send: usize },
+    CustomParam { id: String },
+}
+
+impl Target {
+    /// The target and value `edit` sets, if it is automatable.
+    pub fn of(edit: &Edit) -> Option<(Target, f64)> {
+        let index = |i: &i32| usize::try_from(*i).ok();
+        let toggle = |on: &bool| if *on { 1.0 } else { 0.0 };
+        Some(match edit {
+            Edit::TrackVolume(i, v) => (Target::TrackVolume { track: index(i)? }, *v),
+            Edit::TrackPan(i, v) => (Target::TrackPan { track: index(i)? }, *v),
+            Edit::TrackMute(i, on) => (Target::TrackMute { track: index(i)? }, toggle(on)),
+            Edit::TrackSolo(i, on) => (Target::TrackSolo { track: index(i)? }, toggle(on)),
+            Edit::TrackSend(i, s, v) => {
+                let target = Target::TrackSend {
+                    track: index(i)?,
+                    send: index(s)?,
+                };
+                (target, *v)
+            }
+            Edit::CustomParam(id, v) => (Target::CustomParam { id: id.clone() }, *v as f64),
+            _ => return None,
+        })
+    }
+
+    /// The edit that sets this target to `value`.
+    pub fn edit(&self, value: f64) -> Edit {
+        match self {
+            Target::TrackVolume { track } => Edit::TrackVolume(*track as i32, value),
+            Target::TrackPan { track } => Edit::TrackPan(*track as i32, value),
+            Target::TrackMute { track } => Edit::TrackMute(*track as i32, value >= 0.5),
+            Target::TrackSolo { track } => Edit::TrackSolo(*track as i32, value >= 0.5),
+            Target::TrackSend { track, send } => {
+                Edit::TrackSend(*track as i32, *send as i32, value)
+            }
+            Target::CustomParam { id } => Edit::CustomParam(id.clone(), value as f32),
+        }
+    }
+
+    /// Toggles jump from point to point instead of gliding.
+    pub fn is_toggle(&self) -> bool {
+        matches!(self, Target::TrackMute { .. } | Target::TrackSolo { .. })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    /// Song position in beats.
+    pub beat: f64,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lane {
+    pub target: Target,
+    /// Sorted by beat.
+    pub points: Vec<Point>,
+}
+
+impl Lane {
+    /// The value at `beat`: interpolated between points, held after the
+    /// last one, and `None` before the first.
+    pub fn value_at(&self, beat: f64) -> Option<f64> {
+        let next = self.points.partition_point(|p| p.beat <= beat);
+        let prev = self.points.get(next.checked_sub(1)?)?;
+        match self.points.get(next) {
+            Some(next) if !self.target.is_toggle() => {
+                let t = (beat - prev.beat) / (next.beat - prev.beat);
+                Some(prev.value + (next.value - prev.value) * t)
+            }
+            _ => Some(prev.value),
+        }
+    }
+
+    /// Add `point`, replacing what was recorded earlier between `since`
+    /// (this pass's previous point) and it.
+    fn record(&mut self, since: Option<f64>, point: Point) {
+        let from = since.filter(|s| *s <= point.beat).unwrap_or(point.beat - EPSILON);
+        self.points
+            .retain(|p| p.beat <= from || p.beat > point.beat + EPSILON);
+        let at = self.points.partition_point(|p| p.beat < point.beat);
+        self.points.insert(at, point);
+    }
+}
+
+/// Where the transport was when the engine last reported it.
+#[derive(Clone, Copy)]
+struct Position {
+    beat: f64,
+    at: Instant,
+}
+
+#[derive(Default)]
+struct Inner {
+    recording: bool,
+    playing: bool,
+    lanes: Vec<Lane>,
+    /// Targets recorded since the transport started, with the beat of
+    /// their latest point.
+    pass: HashMap<Target, f64>,
+    /// Last value playback sent per target.
+    sent: HashMap<Target, f64>,
+    position: Option<Position>,
+    /// Lanes changed since they were last stored in the workspace.
+    dirty: bool,
+}
+
+/// Recording flags, playback flag and the lanes.
+#[derive(Debug, Clone, Serialize)]
+pub struct AutomationStatus {
+    pub recording: bool,
+    pub playing: bool,
+    pub lanes: Vec<Lane>,
+}
+
+/// The automation lanes and their recorder.  Clones share them.
+#[derive(Clone)]
+pub struct Automation {
+    inner: Arc<Mutex<Inner>>,
+    workspaces: Workspaces,
+}
+
+impl Automation {
+    /// Lanes are saved to the workspace open in `workspaces`.
+    pub fn new(workspaces: Workspaces) -> Self {
+        Self {
+            inner: Arc::default(),
+            workspaces,
+        }
+    }
+
+    pub fn status(&self) -> AutomationStatus {
+        let inner = self.inner.lock().unwrap();
+        AutomationStatus {
+            recording: inner.recording,
+            playing: inner.playing,
+            lanes: inner.lanes.clone(),
+        }
+    }
+
+    pub fn set_recording(&self, on: bool) {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            inner.recording = on;
+            inner.pass.clear();
+        }
+        self.store();
+    }
+
+    pub fn set_playing(&self, on: bool) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.playing = on;
+        inner.sent.clear();
+    }
+
+    /// Load the lanes of a workspace that just opened.  Lanes recorded
+    /// before it opened and not stored yet are kept, replacing its lanes
+    /// of the same targets.
+    pub fn load(&self, lanes: Vec<Lane>) {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            let mut loaded = lanes;
+            if inner.dirty {
+                let unstored = std::mem::take(&mut inner.lanes);
+                loaded.retain(|l| !unstored.iter().any(|u| u.target == l.target));
+                loaded.extend(unstored);
+            }
+            inner.lanes = loaded;
+            inner.pass.clear();
+            inner.sent.clear();
+        }
+        self.store();
+    }
+
+    /// Put changed lanes into the open workspace.  Without one they stay
+    /// here until one opens.
+    pub fn store(&self) {
+        if self.workspaces.current().is_none() {
+            return;
+        }
+        let lanes = {
+            let mut inner = self.inner.lock().unwrap();
+            if !std::mem::take(&mut inner.dirty) {
+                return;
+            }
+            inner.lanes.clone()
+        };
+        self.workspaces.update(|w| w.automation = lanes);
+    }
+
+    /// Remove the lane of `target`, or every lane.
+    pub fn clear(&self, target: Option<&Target>) {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            inner.lanes.retain(|l| target.is_some_and(|t| l.target != *t));
+            inner.dirty = true;
+        }
+        self.store();
+    }
+
+    /// Record `edit` if recording is on and the transport is playing.
+    pub fn record(&self, edit: &Edit, state: &EngineState) {
+        if !state.is_playing {
+            return;
+        }
+        let beat = {
+            let inner = self.inner.lock().unwrap();
+            // Song time arrives in steps; estimate where the playhead is now.
+            match inner.position {
+                Some(p) => p.beat + p.at.elapsed().as_secs_f64() * state.tempo / 60.0,
+                None => state.current_time,
+            }
+        };
+        self.record_at(edit, beat);
+    }
+
+    fn record_at(&self, edit: &Edit, beat: f64) {
+        let Some((target, value)) = Target::of(edit) else {
+            return;
+        };
+        let mut inner = self.inner.lock().unwrap();
+        if !inner.recording {
+            return;
+        }
+        inner.dirty = true;
+        let since = inner.pass.insert(target.clone(), beat);
+        let lane = match inner.lanes.iter().position(|l| l.target == target) {
+            Some(i) => &mut inner.lanes[i],
+            None => {
+                inner.lanes.push(Lane {
+                    target,
+                    points: Vec::new(),
+                });
+                inner.lanes.last_mut().unwrap()
+            }
+        };
+        lane.record(since, Point { beat, value });
+    }
+
+    /// The edits that bring each lane's target to its value at `beat`.
+    /// Skips lanes being recorded and values playback already sent.
+    fn frame(&self, beat: f64) -> Vec<Edit> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.position = Some(Position {
+            beat,
+            at: Instant::now(),
+        });
+        if !inner.playing {
+            return Vec::new();
+        }
+        let Inner {
+            lanes, pass, sent, ..
+        } = &mut *inner;
+        lanes
+            .iter()
+            .filter(|lane| !pass.contains_key(&lane.target))
+            .filter_map(|lane| {
+                let value = lane.value_at(beat)?;
+                let previous = sent.insert(lane.target.clone(), value);
+                (previous != Some(value)).then(|| lane.target.edit(value))
+            })
+            .collect()
+    }
+
+    /// The transport stopped: the record pass is over.
+    fn stopped(&self) {
+        {
+            let mut inner = self.inner.lock().unwrap();
+            inner.pass.clear();
+            inner.sent.clear();
+            inner.position = None;
+        }
+        self.store();
+    }
+
+    /// Follow one transport change: the lane values to send as song time
+    /// advances while `state` plays, or the end of the pass on a stop.
+    fn follow_change(&self, change: &StateChange, state: &StateManager) -> Vec<Edit> {
+        match change {
+            StateChange::SongTime(beat) if state.get().is_playing => self.frame(*beat),
+            StateChange::Playing(false) => {
+                self.stopped();
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Follow the transport: play lanes back as song time advances and end
+/// record passes when it stops.  Runs for the life of the app.
+pub async fn follow(app: AppHandle) {
+    let state = app.state::<StateManager>().inner().clone();
+    let automation = app.state::<Automation>().inner().clone();
+    let journal = app.state::<Journal>().inner().clone();
+    let mut changes = state.subscribe();
+    loop {
+        match changes.recv().await {
+            Ok(change) => {
+                for edit in automation.follow_change(&change, &state) {
+                    let engine = app.state::<Arc<dyn EngineAdapter>>();
+                    if let Err(e) = journal.send(&**engine, &state, edit.clone()) {
+                        log::warn!("Automation playback of {:?} failed: {}", edit, e);
+                    }
+                }
+            }
+            // A stop may be among the missed changes.
+            Err(RecvError::Lagged(_)) if !state.get().is_playing => automation.stopped(),
+            Err(RecvError::Lagged(_)) => {}
+            Err(RecvError::Closed) => return,
+        }
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn automation() -> Automation {
+        Automation::new(Workspaces::new(std::env::temp_dir().join("maestro-automation")))
+    }
+
+    #[test]
+    fn lanes_recorded_without_a_workspace_go_into_the_first_one() {
+        let root = std::env::temp_dir().join(format!("maestro-lanes-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let workspaces = Workspaces::new(root.clone());
+        let automation = Automation::new(workspaces.clone());
+        automation.set_recording(true);
+        automation.record_at(&Edit::TrackVolume(0, 0.5), 1.0);
+        automation.stopped();
+
+        let saved = Lane {
+            target: Target::TrackPan { track: 0 },
+            points: Vec::new(),
+        };
+        let mut workspace = workspaces.open("A").unwrap();
+        workspace.automation = vec![saved.clone()];
+        automation.load(workspace.automation);
+        let stored = workspaces.current().unwrap().automation;
+        assert_eq!(stored.len(), 2);
+        assert_eq!(stored[0], saved);
+
+        // Points are stored when the pass ends, not on every move.
+        automation.record_at(&Edit::TrackPan(0, 0.2), 2.0);
+        assert_eq!(workspaces.current().unwrap().automation, stored);
+        automation.stopped();
+        assert_eq!(workspaces.current().unwrap().automation[0].points.len(), 1);
+
+        // Lanes already stored aren't carried into the next workspace.
+        workspaces.open("B").unwrap();
+        automation.load(Vec::new());
+        assert!(automation.status().lanes.is_empty());
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    fn beats(automation: &Automation) -> Vec<(f64, f64)> {
+        automation.status().lanes[0]
+            .points
+            .iter()
+            .map(|p| (p.beat, p.value))
+            .collect()
+    }
+
+    #[test]
+    fn recording_overwrites_what_the_pass_moves_over() {
+        let automation = automation();
+        automation.record_at(&Edit::TrackVolume(0, 0.5), 1.0);
+        assert!(automation.status().lanes.is_empty());
+
+        automation.set_recording(true);
+        automation.record_at(&Edit::TrackName(0, "x".into()), 1.0);
+        for (beat, v) in [(0.0, 0.1), (1.0, 0.2), (2.0, 0.3), (3.0, 0.4)] {
+            automation.record_at(&Edit::TrackVolume(0, v), beat);
+        }
+        assert_eq!(automation.status().lanes.len(), 1);
+
+        // A second pass over beats 1–2 replaces only those points.
+        automation.stopped();
+        automation.record_at(&Edit::TrackVolume(0, 0.9), 0.9);
+        automation.record_at(&Edit::TrackVolume(0, 0.8), 2.0);
+        assert_eq!(
+            beats(&automation),
+            [(0.0, 0.1), (0.9, 0.9), (2.0, 0.8), (3.0, 0.4)]
+        );
+    }
+
+    #[test]
+    fn lanes_interpolate_levels_and_step_toggles() {
+        let points = vec![
+            Point {
+                beat: 1.0,
+                value: 0.0,
+            },
+            Point {
+                beat: 3.0,
+                value: 1.0,
+            },
+        ];
+        let volume = Lane {
+            target: Target::TrackVolume { track: 0 },
+            points: points.clone(),
+        };
+        assert_eq!(volume.value_at(0.5), None);
+        assert_eq!(volume.value_at(2.0), Some(0.5));
+        assert_eq!(volume.value_at(8.0), Some(1.0));
+
+        let mute = Lane {
+            target: Target::TrackMute { track: 0 },
+            points,
+        };
+        assert_eq!(mute.value_at(2.0), Some(0.0));
+        assert_eq!(mute.value_at(3.0), Some(1.0));
+    }
+
+    #[test]
+    fn playback_sends_changed_values_and_skips_recording_lanes() {
+        let automation = automation();
+        automation.set_recording(true);
+        automation.record_at(&Edit::TrackVolume(0, 0.0), 0.0);
+        automation.record_at(&Edit::TrackVolume(0, 1.0), 4.0);
+        automation.record_at(&Edit::TrackMute(1, true), 0.0);
+        automation.set_playing(true);
+
+        // Both lanes are still being recorded.
+        assert!(automation.frame(2.0).is_empty());
+        automation.stopped();
+        assert_eq!(
+            automation.frame(2.0),
+            [Edit::TrackVolume(0, 0.5), Edit::TrackMute(1, true)]
+        );
+        assert_eq!(automation.frame(3.0), [Edit::TrackVolume(0, 0.75)]);
+
+        automation.set_playing(false);
+        assert!(automation.frame(3.5).is_empty());
+    }
+
+    #[test]
+    fn song_time_from_the_engine_places_and_plays_back_moves() {
+        let state = StateManager::new();
+        let mut changes = state.subscribe();
+        let mut follow = |automation: &Automation| {
+            let mut edits = Vec::new();
+            while let Ok(change) = changes.try_recv() {
+                edits.extend(automation.follow_change(&change, &state));
+            }
+            edits
+        };
+        let automation = automation();
+        automation.set_recording(true);
+        state.set_playing(true);
+        state.set_current_time(4.0);
+        follow(&automation);
+        automation.record(&Edit::TrackVolume(0, 0.5), &state.get());
+        let (beat, _) = beats(&automation)[0];
+        assert!((4.0..4.1).contains(&beat));
+
+        // The stop ends the pass, so the lane plays back on the next one.
+        state.set_playing(false);
+        follow(&automation);
+        automation.set_playing(true);
+        state.set_playing(true);
+        state.set_current_time(5.0);
+        assert_eq!(follow(&automation), [Edit::TrackVolume(0, 0.5)]);
+    }
+}
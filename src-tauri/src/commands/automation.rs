@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use tauri::State;
+
+use crate::automation::{Automation, AutomationStatus, Target};
+use crate::engine::EngineAdapter;
+
+#[tauri::command]
+pub fn get_automation(automation: State<'_, Automation>) -> AutomationStatus {
+    automation.status()
+}
+
+/// While on, control moves made during playback are recorded.  Engines that
+/// don't report the transport can't place moves in the song, so recording
+/// can't be turned on with them.
+#[tauri::command]
+pub fn set_automation_recording(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    automation: State<'_, Automation>,
+    enabled: bool,
+) -> Result<(), String> {
+    if enabled && !engine.reports_transport() {
+        return Err(format!("{} doesn't report song time to record automation to", engine.name()));
+    }
+    automation.set_recording(enabled);
+    Ok(())
+}
+
+/// While on, recorded lanes are played back in sync with the transport.
+#[tauri::command]
+pub fn set_automation_playback(automation: State<'_, Automation>, enabled: bool) {
+    automation.set_playing(enabled);
+}
+
+/// Remove the lane of `target`, or every lane if none is given.
+#[tauri::command]
+pub fn clear_automation(automation: State<'_, Automation>, target: Option<Target>) {
+    automation.clear(target.as_ref());
+}
@@ -0,0 +1,22 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::engine::EngineAdapter;
+
+#[tauri::command]
+pub fn fire_clip(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    track_index: i32,
+    scene_index: i32,
+) -> Result<(), String> {
+    engine.fire_clip(track_index, scene_index)
+}
+
+#[tauri::command]
+pub fn stop_clip(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    track_index: i32,
+    scene_index: i32,
+) -> Result<(), String> {
+    engine.stop_clip(track_index, scene_index)
+}
@@ -0,0 +1,34 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::engine::{EngineAdapter, StateManager};
+use crate::journal::Journal;
+
+#[tauri::command]
+pub fn undo(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+) -> Result<(), String> {
+    journal.undo(&**engine, &state)
+}
+
+#[tauri::command]
+pub fn redo(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+) -> Result<(), String> {
+    journal.redo(&**engine, &state)
+}
+
+/// Start grouping edits into one undo step, e.g. when a slider drag begins.
+#[tauri::command]
+pub fn begin_transaction(journal: State<'_, Journal>) {
+    journal.begin();
+}
+
+#[tauri::command]
+pub fn commit_transaction(journal: State<'_, Journal>) {
+    journal.commit();
+}
@@ -0,0 +1,76 @@
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::State;
+
+use crate::engine::{EngineAdapter, StateManager};
+use crate::journal::Journal;
+use crate::keymap::{EffectiveKeymap, KeyPress, Keymap};
+use crate::ramp::Ramps;
+use crate::sidecar::api::SidecarClient;
+use crate::skills::{SkillContext, SkillRegistry, SkillResult};
+
+#[derive(Serialize)]
+pub struct KeyPressResult {
+    #[serde(flatten)]
+    pub press: KeyPress,
+    /// Set when the press completed a binding and its skill ran.
+    pub result: Option<SkillResult>,
+}
+
+/// Feed one chord (e.g. `Ctrl+Shift+Z`) to the keymap and run the skill of
+/// the binding it completes, if any.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn press_key(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    sidecar: State<'_, SidecarClient>,
+    ramps: State<'_, Ramps>,
+    registry: State<'_, SkillRegistry>,
+    keymap: State<'_, Keymap>,
+    key: String,
+) -> Result<KeyPressResult, String> {
+    let press = keymap.press(&key)?;
+    let result = match &press {
+        KeyPress::Run { binding } => {
+            let ctx = SkillContext {
+                engine: &**engine,
+                state: &state,
+                journal: &journal,
+                sidecar: &sidecar,
+                ramps: &ramps,
+            };
+            Some(
+                registry
+                    .execute(&binding.skill, &binding.params, &ctx)
+                    .await
+                    .unwrap_or_else(SkillResult::failed),
+            )
+        }
+        _ => None,
+    };
+    Ok(KeyPressResult { press, result })
+}
+
+/// Re-read `keymap.toml`.  Returns its errors and clashes between user
+/// bindings.
+#[tauri::command]
+pub fn reload_keymap(registry: State<'_, SkillRegistry>, keymap: State<'_, Keymap>) -> Vec<String> {
+    keymap.reload(&registry.list())
+}
+
+/// The effective keymap (defaults plus user bindings).  With `path`, also
+/// write it there in `keymap.toml` format.
+#[tauri::command]
+pub fn export_keymap(
+    keymap: State<'_, Keymap>,
+    path: Option<String>,
+) -> Result<EffectiveKeymap, String> {
+    let effective = keymap.effective();
+    if let Some(path) = path {
+        let content = toml::to_string(&effective.to_file()).map_err(|e| e.to_string())?;
+        std::fs::write(&path, content).map_err(|e| format!("{}: {}", path, e))?;
+    }
+    Ok(effective)
+}
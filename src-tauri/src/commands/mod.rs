@@ -1,4 +1,17 @@
+pub mod automation;
+pub mod clips;
+pub mod journal;
+pub mod keymap;
+pub mod notes;
+pub mod palette;
 pub mod params;
+pub mod ramps;
+pub mod scenes;
+pub mod scripts;
 pub mod search;
+pub mod skills;
+pub mod snapshots;
+pub mod state;
 pub mod tracks;
 pub mod transport;
+pub mod workspace;
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::engine::notes::NoteTransform;
+use crate::engine::smf::{self, MidiFile, TimeSignature};
+use crate::engine::{EngineAdapter, Note, NoteRegion, StateManager};
+use crate::journal::{Edit, Journal};
+
+/// Run `f` with the engine on a blocking thread: reading notes waits for
+/// the engine's reply.
+async fn blocking<T: Send + 'static>(
+    engine: &Arc<dyn EngineAdapter>,
+    f: impl FnOnce(&dyn EngineAdapter) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let engine = Arc::clone(engine);
+    tauri::async_runtime::spawn_blocking(move || f(&*engine))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn get_clip_notes(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    track_index: i32,
+    scene_index: i32,
+) -> Result<Vec<Note>, String> {
+    blocking(&engine, move |engine| engine.get_clip_notes(track_index, scene_index)).await
+}
+
+#[tauri::command]
+pub fn add_notes(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    journal: State<'_, Journal>,
+    track_index: i32,
+    scene_index: i32,
+    notes: Vec<Note>,
+) -> Result<(), String> {
+    engine.add_notes(track_index, scene_index, &notes)?;
+    journal.record_native();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn remove_notes(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    journal: State<'_, Journal>,
+    track_index: i32,
+    scene_index: i32,
+    region: Option<NoteRegion>,
+) -> Result<(), String> {
+    engine.remove_notes(track_index, scene_index, region)?;
+    journal.record_native();
+    Ok(())
+}
+
+/// Apply a transform to every note of a clip.  Returns the notes as written.
+#[tauri::command]
+pub async fn transform_clip_notes(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    journal: State<'_, Journal>,
+    track_index: i32,
+    scene_index: i32,
+    transform: NoteTransform,
+) -> Result<Vec<Note>, String> {
+    let notes = blocking(&engine, move |engine| {
+        transform.rewrite_clip(engine, track_index, scene_index)
+    })
+    .await?;
+    journal.record_native();
+    Ok(notes)
+}
+
+/// Import a `.mid` file into a clip slot: the clip is created (sized to
+/// whole bars) if the slot is empty, otherwise its notes are replaced.
+/// With `use_file_tempo`, the file's first tempo is applied to the song.
+#[tauri::command]
+pub fn import_clip_midi(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    track_index: i32,
+    scene_index: i32,
+    path: String,
+    use_file_tempo: bool,
+) -> Result<MidiFile, String> {
+    let track = usize::try_from(track_index)
+        .map_err(|_| format!("Invalid track index {track_index}"))?;
+    let scene = usize::try_from(scene_index)
+        .map_err(|_| format!("Invalid scene index {scene_index}"))?;
+    let bytes = std::fs::read(&path).map_err(|e| format!("Failed to read '{path}': {e}"))?;
+    let file = smf::parse(&bytes)?;
+
+    let has_clip = state
+        .get()
+        .tracks
+        .get(track)
+        .and_then(|t| t.clips.get(scene))
+        .is_some_and(|c| c.length > 0.0);
+    if has_clip {
+        engine.remove_notes(track_index, scene_index, None)?;
+    } else {
+        engine.create_clip(track_index, scene_index, file.bar_aligned_length())?;
+    }
+    engine.add_notes(track_index, scene_index, &file.notes)?;
+    journal.record_native();
+
+    if let Some(bpm) = file.tempo.filter(|_| use_file_tempo) {
+        journal.apply(&**engine, &state, Edit::Tempo(bpm))?;
+    }
+    Ok(file)
+}
+
+/// Write a clip's notes to a format 1 `.mid` file with the song tempo.
+/// Maestro doesn't track the time signature, so 4/4 is written.
+#[tauri::command]
+pub async fn export_clip_midi(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    track_index: i32,
+    scene_index: i32,
+    path: String,
+) -> Result<(), String> {
+    let notes =
+        blocking(&engine, move |engine| engine.get_clip_notes(track_index, scene_index)).await?;
+    let file = MidiFile {
+        format: 1,
+        notes,
+        tempo: Some(state.get().tempo).filter(|t| *t > 0.0),
+        time_signature: Some(TimeSignature::default()),
+    };
+    std::fs::write(&path, smf::write(&file)?).map_err(|e| format!("Failed to write '{path}': {e}"))
+}
@@ -0,0 +1,41 @@
+use tauri::State;
+
+use crate::engine::StateManager;
+use crate::keymap::Keymap;
+use crate::palette::{Palette, PaletteItem, PaletteSources};
+use crate::scripting;
+use crate::skills::SkillRegistry;
+
+const DEFAULT_LIMIT: usize = 50;
+
+/// Fuzzy-search skills, tracks, clips, scenes, scripts and recent sample
+/// results, best match first.
+#[tauri::command]
+pub fn palette_query(
+    registry: State<'_, SkillRegistry>,
+    keymap: State<'_, Keymap>,
+    state: State<'_, StateManager>,
+    palette: State<'_, Palette>,
+    text: String,
+    limit: Option<usize>,
+) -> Vec<PaletteItem> {
+    let keymap = keymap.effective();
+    let mut skills = registry.list();
+    for skill in &mut skills {
+        skill.keyboard_shortcut = keymap.shortcut_for(&skill.id);
+    }
+    let state = state.get();
+    let scripts = scripting::list();
+    let sources = PaletteSources {
+        skills: &skills,
+        state: &state,
+        scripts: &scripts,
+    };
+    palette.query(&text, &sources, limit.unwrap_or(DEFAULT_LIMIT))
+}
+
+/// Tell the palette the user ran item `id`, so it ranks higher next time.
+#[tauri::command]
+pub fn palette_record_use(palette: State<'_, Palette>, id: String) {
+    palette.record_use(&id);
+}
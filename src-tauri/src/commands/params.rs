@@ -1,7 +1,9 @@
 use std::sync::Arc;
 use tauri::State;
 
-use crate::engine::{EngineAdapter, ParamDef};
+use crate::automation::Automation;
+use crate::engine::{EngineAdapter, ParamDef, StateManager};
+use crate::journal::{Edit, Journal};
 
 #[tauri::command]
 pub fn get_engine_params(engine: State<'_, Arc<dyn EngineAdapter>>) -> Vec<ParamDef> {
@@ -11,8 +13,14 @@ pub fn get_engine_params(engine: State<'_, Arc<dyn EngineAdapter>>) -> Vec<Param
 #[tauri::command]
 pub fn set_engine_param(
     engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    automation: State<'_, Automation>,
     id: String,
     value: f32,
 ) -> Result<(), String> {
-    engine.set_custom_param(&id, value)
+    let edit = Edit::CustomParam(id, value);
+    journal.apply(&**engine, &state, edit.clone())?;
+    automation.record(&edit, &state.get());
+    Ok(())
 }
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::automation::Target;
+use crate::engine::{EngineAdapter, StateManager};
+use crate::journal::Journal;
+use crate::ramp::{Ramp, Ramps};
+
+/// Glide a track or custom param to a value.  Resolves when the ramp ends:
+/// `true` if it got there, `false` if it was cancelled or replaced.
+#[tauri::command]
+pub async fn start_ramp(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    ramps: State<'_, Ramps>,
+    ramp: Ramp,
+) -> Result<bool, String> {
+    ramps.run(&**engine, &state, &journal, &ramp).await
+}
+
+/// Stop the ramp on `target`, or every ramp.  Returns how many stopped.
+#[tauri::command]
+pub fn cancel_ramp(ramps: State<'_, Ramps>, target: Option<Target>) -> usize {
+    ramps.cancel(target.as_ref())
+}
+
+#[tauri::command]
+pub fn list_ramps(ramps: State<'_, Ramps>) -> Vec<Target> {
+    ramps.running()
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::engine::{EngineAdapter, SceneState, StateManager};
+use crate::journal::Journal;
+
+#[tauri::command]
+pub fn get_scenes(state: State<'_, StateManager>) -> Vec<SceneState> {
+    state.get().scenes
+}
+
+#[tauri::command]
+pub fn fire_scene(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    scene_index: i32,
+) -> Result<(), String> {
+    engine.fire_scene(scene_index)
+}
+
+#[tauri::command]
+pub fn stop_scene(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    scene_index: i32,
+) -> Result<(), String> {
+    engine.stop_scene(scene_index)
+}
+
+#[tauri::command]
+pub fn create_scene(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    journal: State<'_, Journal>,
+    scene_index: i32,
+) -> Result<(), String> {
+    engine.create_scene(scene_index)?;
+    journal.record_native();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn duplicate_scene(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    journal: State<'_, Journal>,
+    scene_index: i32,
+) -> Result<(), String> {
+    engine.duplicate_scene(scene_index)?;
+    journal.record_native();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_scene(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    journal: State<'_, Journal>,
+    scene_index: i32,
+) -> Result<(), String> {
+    engine.delete_scene(scene_index)?;
+    journal.record_native();
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::engine::{EngineAdapter, StateManager};
+use crate::journal::Journal;
+use crate::scripting::{self, ScriptHost, ScriptLimits, ScriptOutput};
+use crate::sidecar::api::SidecarClient;
+
+#[tauri::command]
+pub fn list_scripts() -> Vec<String> {
+    scripting::list()
+}
+
+/// Run `~/.config/maestro/scripts/<name>.rhai`.  Script errors are reported
+/// in `ScriptOutput.error`; only a missing script rejects.
+#[tauri::command]
+pub async fn run_script(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    sidecar: State<'_, SidecarClient>,
+    name: String,
+) -> Result<ScriptOutput, String> {
+    let source = scripting::load(&name)?;
+    let host = ScriptHost {
+        engine: Arc::clone(&engine),
+        state: state.inner().clone(),
+        journal: journal.inner().clone(),
+        sidecar: sidecar.inner().clone(),
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        scripting::run(host, &source, &ScriptLimits::default())
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -2,11 +2,15 @@ use std::sync::Arc;
 use tauri::State;
 
 use crate::engine::EngineAdapter;
+use crate::palette::Palette;
 use crate::sidecar::api::{IndexResponse, SearchResultItem, SidecarClient};
+use crate::workspace::Workspaces;
 
 #[tauri::command]
 pub async fn search_by_text(
     sidecar: State<'_, SidecarClient>,
+    palette: State<'_, Palette>,
+    workspaces: State<'_, Workspaces>,
     query: String,
     n_results: usize,
 ) -> Result<Vec<SearchResultItem>, String> {
@@ -14,12 +18,16 @@ pub async fn search_by_text(
         .search_text(&query, n_results)
         .await
         .map_err(|e| e.to_string())?;
+    palette.record_search(&query, &resp.results);
+    workspaces.update(|w| w.record_search(&query, &resp.results));
     Ok(resp.results)
 }
 
 #[tauri::command]
 pub async fn search_by_similarity(
     sidecar: State<'_, SidecarClient>,
+    palette: State<'_, Palette>,
+    workspaces: State<'_, Workspaces>,
     file_path: String,
     n_results: usize,
 ) -> Result<Vec<SearchResultItem>, String> {
@@ -27,6 +35,8 @@ pub async fn search_by_similarity(
         .search_similar(&file_path, n_results)
         .await
         .map_err(|e| e.to_string())?;
+    palette.record_search(&file_path, &resp.results);
+    workspaces.update(|w| w.record_search(&file_path, &resp.results));
     Ok(resp.results)
 }
 
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+use tauri::State;
+
+use crate::engine::{EngineAdapter, StateManager};
+use crate::journal::Journal;
+use crate::keymap::Keymap;
+use crate::ramp::Ramps;
+use crate::sidecar::api::SidecarClient;
+use crate::skills::{Params, SkillContext, SkillDescriptor, SkillRegistry, SkillResult};
+
+#[tauri::command]
+pub fn list_skills(
+    registry: State<'_, SkillRegistry>,
+    keymap: State<'_, Keymap>,
+) -> Vec<SkillDescriptor> {
+    let keymap = keymap.effective();
+    let mut skills = registry.list();
+    for skill in &mut skills {
+        skill.keyboard_shortcut = keymap.shortcut_for(&skill.id);
+    }
+    skills
+}
+
+/// Re-read the macro skill files, then the keymap (which may bind them).
+/// Returns one message per problem found.
+#[tauri::command]
+pub fn reload_skills(registry: State<'_, SkillRegistry>, keymap: State<'_, Keymap>) -> Vec<String> {
+    let mut problems = registry.reload_macros();
+    problems.extend(keymap.reload(&registry.list()));
+    problems
+}
+
+/// Run a skill.  Unknown skills, invalid params and engine errors come back
+/// as a failed `SkillResult` rather than a rejected promise.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_skill(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    sidecar: State<'_, SidecarClient>,
+    ramps: State<'_, Ramps>,
+    registry: State<'_, SkillRegistry>,
+    id: String,
+    params: Option<Params>,
+) -> Result<SkillResult, String> {
+    let ctx = SkillContext {
+        engine: &**engine,
+        state: &state,
+        journal: &journal,
+        sidecar: &sidecar,
+        ramps: &ramps,
+    };
+    let params = params.unwrap_or_default();
+    Ok(registry
+        .execute(&id, &params, &ctx)
+        .await
+        .unwrap_or_else(SkillResult::failed))
+}
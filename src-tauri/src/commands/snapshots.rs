@@ -0,0 +1,105 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::State;
+
+use crate::engine::{EngineAdapter, StateManager};
+use crate::journal::Journal;
+use crate::snapshot::{drop_held_toggles, morph_frame, Snapshot};
+use crate::workspace::Workspaces;
+
+/// Time between morph steps.
+const MORPH_FRAME: Duration = Duration::from_millis(50);
+
+/// Bumped by every recall and morph, so a running morph stops when another
+/// one (or a recall) takes over.
+#[derive(Default)]
+pub struct MorphGeneration(AtomicU64);
+
+#[tauri::command]
+pub fn list_snapshots(workspaces: State<'_, Workspaces>) -> Vec<Snapshot> {
+    Snapshot::list(&workspaces.snapshots_dir())
+}
+
+/// Save the current mixer as snapshot `name`, replacing any of that name.
+#[tauri::command]
+pub fn save_snapshot(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    workspaces: State<'_, Workspaces>,
+    name: String,
+) -> Result<Snapshot, String> {
+    let snapshot = Snapshot::capture(&name, &state.get(), &engine.custom_params());
+    snapshot.save(&workspaces.snapshots_dir())?;
+    Ok(snapshot)
+}
+
+/// Apply the values of snapshot `name` that differ from the current mixer,
+/// as one undo step.  Returns how many values changed.
+#[tauri::command]
+pub fn recall_snapshot(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    morphs: State<'_, MorphGeneration>,
+    workspaces: State<'_, Workspaces>,
+    name: String,
+) -> Result<usize, String> {
+    let snapshot = Snapshot::load(&workspaces.snapshots_dir(), &name)?;
+    morphs.0.fetch_add(1, Ordering::SeqCst);
+    let edits = snapshot.diff(&state.get(), &engine.custom_params());
+    let count = edits.len();
+
+    let recall = journal.child();
+    for edit in edits {
+        if let Err(e) = recall.apply(&**engine, &state, edit) {
+            return Err(match recall.rollback(&**engine, &state) {
+                Ok(_) => format!("Recalling '{name}' failed: {e} (changes rolled back)"),
+                Err(r) => format!("Recalling '{name}' failed: {e}; rolling back also failed: {r}"),
+            });
+        }
+    }
+    journal.adopt(&recall);
+    Ok(count)
+}
+
+/// Move the mixer from snapshot `from` to snapshot `to` over `duration_ms`.
+/// The whole morph is one undo step.  Resolves when the morph finishes or
+/// is interrupted by another recall or morph.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn morph_snapshots(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    morphs: State<'_, MorphGeneration>,
+    workspaces: State<'_, Workspaces>,
+    from: String,
+    to: String,
+    duration_ms: u64,
+) -> Result<(), String> {
+    let dir = workspaces.snapshots_dir();
+    let (from, to) = (Snapshot::load(&dir, &from)?, Snapshot::load(&dir, &to)?);
+    let generation = morphs.0.fetch_add(1, Ordering::SeqCst) + 1;
+    let frames = (duration_ms / MORPH_FRAME.as_millis() as u64).max(1);
+
+    let morph = journal.child();
+    let mut result = Ok(());
+    for frame in 0..=frames {
+        if morphs.0.load(Ordering::SeqCst) != generation {
+            break;
+        }
+        let t = frame as f64 / frames as f64;
+        let mut edits = morph_frame(&from, &to, t);
+        drop_held_toggles(&mut edits, &state.get());
+        result = edits
+            .into_iter()
+            .try_for_each(|edit| morph.apply(&**engine, &state, edit));
+        if result.is_err() || frame == frames {
+            break;
+        }
+        tokio::time::sleep(MORPH_FRAME).await;
+    }
+    journal.adopt(&morph);
+    result
+}
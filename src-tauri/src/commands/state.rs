@@ -0,0 +1,10 @@
+use tauri::State;
+
+use crate::engine::{StateManager, VersionedState};
+
+/// The complete engine state and its version.  The UI rereads it when a
+/// `state-changed` batch shows it missed changes.
+#[tauri::command]
+pub fn get_engine_state(state: State<'_, StateManager>) -> VersionedState {
+    state.snapshot()
+}
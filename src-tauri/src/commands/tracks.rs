@@ -1,40 +1,191 @@
 use std::sync::Arc;
 use tauri::State;
 
-use crate::engine::EngineAdapter;
+use crate::automation::Automation;
+use crate::engine::{EngineAdapter, StateManager, TrackKind};
+use crate::journal::{Edit, Journal};
 
 #[tauri::command]
 pub fn set_track_volume(
     engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    automation: State<'_, Automation>,
     track_index: i32,
     volume: f32,
 ) -> Result<(), String> {
-    engine.set_track_volume(track_index, volume)
+    let edit = Edit::TrackVolume(track_index, volume as f64);
+    journal.apply(&**engine, &state, edit.clone())?;
+    automation.record(&edit, &state.get());
+    Ok(())
 }
 
 #[tauri::command]
 pub fn set_track_mute(
     engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    automation: State<'_, Automation>,
     track_index: i32,
     mute: bool,
 ) -> Result<(), String> {
-    engine.set_track_mute(track_index, mute)
+    let edit = Edit::TrackMute(track_index, mute);
+    journal.apply(&**engine, &state, edit.clone())?;
+    automation.record(&edit, &state.get());
+    Ok(())
 }
 
 #[tauri::command]
 pub fn set_track_solo(
     engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    automation: State<'_, Automation>,
     track_index: i32,
     solo: bool,
 ) -> Result<(), String> {
-    engine.set_track_solo(track_index, solo)
+    let edit = Edit::TrackSolo(track_index, solo);
+    journal.apply(&**engine, &state, edit.clone())?;
+    automation.record(&edit, &state.get());
+    Ok(())
 }
 
 #[tauri::command]
 pub fn set_track_pan(
     engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    automation: State<'_, Automation>,
     track_index: i32,
     pan: f32,
 ) -> Result<(), String> {
-    engine.set_track_pan(track_index, pan)
+    let edit = Edit::TrackPan(track_index, pan as f64);
+    journal.apply(&**engine, &state, edit.clone())?;
+    automation.record(&edit, &state.get());
+    Ok(())
+}
+
+#[tauri::command]
+pub fn create_track(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    journal: State<'_, Journal>,
+    kind: TrackKind,
+    track_index: i32,
+) -> Result<(), String> {
+    engine.create_track(kind, track_index)?;
+    journal.record_native();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_track(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    journal: State<'_, Journal>,
+    track_index: i32,
+) -> Result<(), String> {
+    engine.delete_track(track_index)?;
+    journal.record_native();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn duplicate_track(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    journal: State<'_, Journal>,
+    track_index: i32,
+) -> Result<(), String> {
+    engine.duplicate_track(track_index)?;
+    journal.record_native();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn rename_track(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    track_index: i32,
+    name: String,
+) -> Result<(), String> {
+    journal.apply(&**engine, &state, Edit::TrackName(track_index, name))
+}
+
+#[tauri::command]
+pub fn set_track_color(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    track_index: i32,
+    color: u32,
+) -> Result<(), String> {
+    journal.apply(&**engine, &state, Edit::TrackColor(track_index, color))
+}
+
+#[tauri::command]
+pub fn set_track_arm(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    track_index: i32,
+    arm: bool,
+) -> Result<(), String> {
+    journal.apply(&**engine, &state, Edit::TrackArm(track_index, arm))
+}
+
+#[tauri::command]
+pub fn set_track_send(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    automation: State<'_, Automation>,
+    track_index: i32,
+    send_index: i32,
+    value: f32,
+) -> Result<(), String> {
+    let edit = Edit::TrackSend(track_index, send_index, value as f64);
+    journal.apply(&**engine, &state, edit.clone())?;
+    automation.record(&edit, &state.get());
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_return_track_volume(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    return_index: i32,
+    volume: f32,
+) -> Result<(), String> {
+    journal.apply(&**engine, &state, Edit::ReturnVolume(return_index, volume as f64))
+}
+
+#[tauri::command]
+pub fn set_return_track_pan(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    return_index: i32,
+    pan: f32,
+) -> Result<(), String> {
+    journal.apply(&**engine, &state, Edit::ReturnPan(return_index, pan as f64))
+}
+
+#[tauri::command]
+pub fn set_master_volume(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    volume: f32,
+) -> Result<(), String> {
+    journal.apply(&**engine, &state, Edit::MasterVolume(volume as f64))
+}
+
+#[tauri::command]
+pub fn set_master_pan(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    pan: f32,
+) -> Result<(), String> {
+    journal.apply(&**engine, &state, Edit::MasterPan(pan as f64))
 }
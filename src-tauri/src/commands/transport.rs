@@ -2,6 +2,7 @@ use std::sync::Arc;
 use tauri::State;
 
 use crate::engine::{EngineAdapter, StateManager};
+use crate::journal::{Edit, Journal};
 
 #[tauri::command]
 pub fn play(engine: State<'_, Arc<dyn EngineAdapter>>) -> Result<(), String> {
@@ -19,17 +20,28 @@ pub fn toggle_record(engine: State<'_, Arc<dyn EngineAdapter>>) -> Result<(), St
 }
 
 #[tauri::command]
-pub fn set_tempo(engine: State<'_, Arc<dyn EngineAdapter>>, bpm: f32) -> Result<(), String> {
-    engine.set_tempo(bpm)
+pub fn set_tempo(
+    engine: State<'_, Arc<dyn EngineAdapter>>,
+    state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
+    bpm: f32,
+) -> Result<(), String> {
+    journal.apply(&**engine, &state, Edit::Tempo(bpm as f64))
+}
+
+#[tauri::command]
+pub fn set_song_time(engine: State<'_, Arc<dyn EngineAdapter>>, beats: f64) -> Result<(), String> {
+    engine.set_song_time(beats)
 }
 
 #[tauri::command]
 pub fn toggle_loop(
     engine: State<'_, Arc<dyn EngineAdapter>>,
     state: State<'_, StateManager>,
+    journal: State<'_, Journal>,
 ) -> Result<(), String> {
     let current = state.get();
-    engine.set_loop_enabled(!current.loop_enabled)
+    journal.apply(&**engine, &state, Edit::Loop(!current.loop_enabled))
 }
 
 #[tauri::command]
@@ -0,0 +1,21 @@
+use tauri::{AppHandle, State};
+
+use crate::workspace::{self, Workspace, Workspaces};
+
+/// The open workspace, if any.
+#[tauri::command]
+pub fn get_workspace(workspaces: State<'_, Workspaces>) -> Option<Workspace> {
+    workspaces.current()
+}
+
+/// Open the workspace of `project` and restore its state.  For engines that
+/// don't report their project, this is how the user picks one.
+#[tauri::command]
+pub fn open_workspace(app: AppHandle, project: String) -> Result<Workspace, String> {
+    workspace::activate(&app, &project)
+}
+
+#[tauri::command]
+pub fn set_selected_tracks(workspaces: State<'_, Workspaces>, tracks: Vec<usize>) {
+    workspaces.update(|w| w.selected_tracks = tracks);
+}
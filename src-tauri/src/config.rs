@@ -0,0 +1,14 @@
+use std::path::PathBuf;
+
+/// Maestro's per-user config directory: `$XDG_CONFIG_HOME/maestro`,
+/// falling back to `~/.config/maestro`.
+///
+/// Engine map files, user skills and other persisted state live here.
+pub fn config_dir() -> PathBuf {
+    let base = std::env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".config")
+        });
+    base.join("maestro")
+}
@@ -0,0 +1,591 @@
+//! Ardour engine adapter over Ardour's native OSC control surface.
+//!
+//! - **Send** commands to Ardour's OSC port (default 3819)
+//! - **Receive** feedback on port 3820 (Ardour's manual reply port)
+//!
+//! On start Maestro sends `/set_surface` to register for strip and transport
+//! feedback, with fader positions (0–1) rather than dB so gain maps directly
+//! onto Maestro's normalized volume.
+//!
+//! | Maestro          | Ardour address                      |
+//! |------------------|-------------------------------------|
+//! | play / stop      | `/transport_play`, `/transport_stop`|
+//! | record           | `/rec_enable_toggle`                |
+//! | loop             | `/loop_toggle`                      |
+//! | track volume     | `/strip/fader ssid position`        |
+//! | track pan        | `/strip/pan_stereo_position ssid p` |
+//! | track mute/solo  | `/strip/mute`, `/strip/solo`        |
+//! | track arm        | `/strip/recenable`                  |
+//! | master vol/pan   | `/master/fader`, `/master/pan_stereo_position` |
+//! | track trim (dB)  | `/strip/trimdB ssid dB` (custom param `track/<n>/trim`) |
+//!
+//! `ssid` is the 1-based surface strip id; Maestro asks for audio + MIDI
+//! tracks only, so strip N is Maestro track N−1.  Ardour's OSC surface has
+//! no tempo or absolute-beat locate, so those commands return an error.
+//!
+//! ## Setup
+//!
+//! In Ardour → **Window → Preferences → Control Surfaces**, enable
+//! **Open Sound Control (OSC)**, open its settings and set **Port Mode** to
+//! *Manual* with **Reply Manual Port** 3820.  Then launch Maestro with
+//! `MAESTRO_ENGINE=ardour`.
+//!
+//! References:
+//! * <https://manual.ardour.org/using-control-surfaces/controlling-ardour-with-osc/>
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use rosc::OscType;
+use tauri::{AppHandle, Emitter};
+
+use crate::engine::{EngineAdapter, ParamDef, StateManager};
+use crate::osc::feedback::{self, one_based, Change, TrackValue};
+use crate::osc::{args, client::OscClient, listener};
+
+/// Port Ardour sends feedback to (Port Mode: Manual, Reply Manual Port).
+pub const ARDOUR_OSC_LISTEN_PORT: u16 = 3820;
+
+/// `/set_surface` strip types: audio tracks (1) + MIDI tracks (2).
+const STRIP_TYPES: i32 = 1 | 2;
+
+/// `/set_surface` feedback: strip buttons (1), strip values (2), master and
+/// transport (16), bar/beat position (32), meters (128).
+const FEEDBACK: i32 = 1 | 2 | 16 | 32 | 128;
+
+/// `/set_surface` fader mode: 1 = fader position 0–1 (`/strip/fader`).
+const FADER_MODE_POSITION: i32 = 1;
+
+/// Trim range Ardour accepts for `/strip/trimdB`.
+const TRIM_MIN_DB: f32 = -20.0;
+const TRIM_MAX_DB: f32 = 20.0;
+
+/// Ardour's BBT tick resolution.
+const TICKS_PER_BEAT: f64 = 1920.0;
+
+// ── Message builders ───────────────────────────────────────────────────────
+
+/// Typed OSC message builders for Ardour's OSC surface.
+pub struct ArdourMessages;
+
+impl ArdourMessages {
+    /// Register this surface: unlimited bank, tracks only, feedback on,
+    /// fader-position gain mode, replies to `reply_port`.
+    pub fn set_surface(reply_port: u16) -> (&'static str, Vec<OscType>) {
+        (
+            "/set_surface",
+            vec![
+                OscType::Int(0), // bank size (0 = all strips)
+                OscType::Int(STRIP_TYPES),
+                OscType::Int(FEEDBACK),
+                OscType::Int(FADER_MODE_POSITION),
+                OscType::Int(0), // send page size
+                OscType::Int(0), // plugin page size
+                OscType::Int(reply_port as i32),
+            ],
+        )
+    }
+
+    pub fn play() -> (&'static str, Vec<OscType>) {
+        ("/transport_play", vec![])
+    }
+
+    pub fn stop() -> (&'static str, Vec<OscType>) {
+        ("/transport_stop", vec![])
+    }
+
+    pub fn toggle_record() -> (&'static str, Vec<OscType>) {
+        ("/rec_enable_toggle", vec![])
+    }
+
+    pub fn undo() -> (&'static str, Vec<OscType>) {
+        ("/undo", vec![])
+    }
+
+    pub fn redo() -> (&'static str, Vec<OscType>) {
+        ("/redo", vec![])
+    }
+
+    pub fn toggle_loop() -> (&'static str, Vec<OscType>) {
+        ("/loop_toggle", vec![])
+    }
+
+    pub fn set_strip_fader(
+        track: i32,
+        position: f32,
+    ) -> Result<(&'static str, Vec<OscType>), String> {
+        Ok(("/strip/fader", vec![ssid(track)?, OscType::Float(position)]))
+    }
+
+    /// `pan` is Maestro's −1..1; Ardour's stereo position is 0 (left) .. 1 (right).
+    pub fn set_strip_pan(track: i32, pan: f32) -> Result<(&'static str, Vec<OscType>), String> {
+        let position = (pan.clamp(-1.0, 1.0) + 1.0) / 2.0;
+        Ok(("/strip/pan_stereo_position", vec![ssid(track)?, OscType::Float(position)]))
+    }
+
+    pub fn set_strip_mute(track: i32, mute: bool) -> Result<(&'static str, Vec<OscType>), String> {
+        Ok(("/strip/mute", vec![ssid(track)?, OscType::Int(mute as i32)]))
+    }
+
+    pub fn set_strip_solo(track: i32, solo: bool) -> Result<(&'static str, Vec<OscType>), String> {
+        Ok(("/strip/solo", vec![ssid(track)?, OscType::Int(solo as i32)]))
+    }
+
+    pub fn set_strip_recenable(
+        track: i32,
+        arm: bool,
+    ) -> Result<(&'static str, Vec<OscType>), String> {
+        Ok(("/strip/recenable", vec![ssid(track)?, OscType::Int(arm as i32)]))
+    }
+
+    pub fn set_master_fader(position: f32) -> (&'static str, Vec<OscType>) {
+        ("/master/fader", vec![OscType::Float(position)])
+    }
+
+    pub fn set_master_pan(pan: f32) -> (&'static str, Vec<OscType>) {
+        let position = (pan.clamp(-1.0, 1.0) + 1.0) / 2.0;
+        ("/master/pan_stereo_position", vec![OscType::Float(position)])
+    }
+
+    pub fn set_strip_trim(track: i32, db: f32) -> Result<(&'static str, Vec<OscType>), String> {
+        Ok(("/strip/trimdB", vec![ssid(track)?, OscType::Float(db)]))
+    }
+}
+
+/// Maestro's 0-based track index → Ardour's 1-based strip id.
+fn ssid(track: i32) -> Result<OscType, String> {
+    Ok(OscType::Int(one_based(track, "track")?))
+}
+
+/// Custom param id for a track's trim control.
+fn trim_param_id(track: usize) -> String {
+    format!("track/{track}/trim")
+}
+
+fn parse_trim_param_id(id: &str) -> Option<i32> {
+    id.strip_prefix("track/")?.strip_suffix("/trim")?.parse().ok()
+}
+
+/// Convert an Ardour `bars|beats|ticks` string into beats from the session
+/// start.  Ardour's OSC feedback has no meter, so 4/4 is assumed.
+fn bbt_to_beats(bbt: &str) -> Option<f64> {
+    const BEATS_PER_BAR: f64 = 4.0;
+    let mut parts = bbt.trim().split('|').map(|p| p.trim().parse::<f64>());
+    let bars = parts.next()?.ok()?;
+    let beats = parts.next()?.ok()?;
+    let ticks = parts.next()?.ok()?;
+    Some((bars - 1.0) * BEATS_PER_BAR + (beats - 1.0) + ticks / TICKS_PER_BEAT)
+}
+
+// ── Feedback ───────────────────────────────────────────────────────────────
+
+/// Apply one Ardour feedback message to `state`.
+fn apply_feedback(state: &StateManager, addr: &str, osc_args: &[OscType]) -> Change {
+    match addr {
+        "/transport_play" => match args::first_bool(osc_args) {
+            Some(true) => {
+                state.set_playing(true);
+                Change::Transport
+            }
+            _ => Change::None,
+        },
+        "/transport_stop" => match args::first_bool(osc_args) {
+            Some(true) => {
+                state.set_playing(false);
+                Change::Transport
+            }
+            _ => Change::None,
+        },
+        "/loop_toggle" => match args::first_bool(osc_args) {
+            Some(enabled) => {
+                state.set_loop_enabled(enabled);
+                Change::Transport
+            }
+            None => Change::None,
+        },
+        "/session_name" => match args::first_str(osc_args).filter(|n| !n.is_empty()) {
+            Some(name) => {
+                state.set_project(name.to_string());
+                Change::Project(name.to_string())
+            }
+            None => Change::None,
+        },
+        "/position/bbt" => match args::first_str(osc_args).and_then(bbt_to_beats) {
+            Some(beats) => {
+                state.set_current_time(beats);
+                Change::SongTime(beats)
+            }
+            None => Change::None,
+        },
+        _ => match addr.strip_prefix("/strip/") {
+            Some(param) => apply_strip_feedback(state, param, osc_args),
+            None => Change::None,
+        },
+    }
+}
+
+/// Strip feedback is `/strip/<param> ssid value`.
+fn apply_strip_feedback(state: &StateManager, param: &str, osc_args: &[OscType]) -> Change {
+    let (Some(ssid), Some(arg)) = (osc_args.first().and_then(args::as_i32), osc_args.get(1))
+    else {
+        return Change::None;
+    };
+    let Some(index) = usize::try_from(ssid).ok().and_then(|s| s.checked_sub(1)) else {
+        return Change::None;
+    };
+    let value = match param {
+        "fader" => args::as_f64(arg).map(TrackValue::Volume),
+        "pan_stereo_position" => args::as_f64(arg).map(|p| TrackValue::Pan(p * 2.0 - 1.0)),
+        "mute" => args::as_bool(arg).map(TrackValue::Mute),
+        "solo" => args::as_bool(arg).map(TrackValue::Solo),
+        "recenable" => args::as_bool(arg).map(TrackValue::Arm),
+        "name" => args::as_str(arg).map(TrackValue::Name),
+        "meter" => {
+            // Meters update constantly; don't grow the track list for them.
+            if let Some(level) = args::as_f64(arg) {
+                state.set_track_meter(index, level);
+            }
+            None
+        }
+        _ => None,
+    };
+    match value {
+        Some(v) => feedback::apply_track_value(state, index, v),
+        None => Change::None,
+    }
+}
+
+fn handle_message(state: &StateManager, addr: &str, osc_args: &[OscType]) {
+    if apply_feedback(state, addr, osc_args) == Change::None {
+        log::trace!("Unhandled Ardour OSC: {} {:?}", addr, osc_args);
+    }
+}
+
+// ── Adapter ────────────────────────────────────────────────────────────────
+
+/// Ardour engine adapter using Ardour's native OSC control surface.
+pub struct ArdourEngine {
+    client: OscClient,
+    /// Set in `start`; loop toggling and trim params read from it.
+    state: OnceLock<StateManager>,
+}
+
+impl ArdourEngine {
+    pub fn new(client: OscClient) -> Self {
+        Self {
+            client,
+            state: OnceLock::new(),
+        }
+    }
+
+    fn send(&self, (addr, args): (&str, Vec<OscType>)) -> Result<(), String> {
+        self.client.send(addr, args).map_err(|e| e.to_string())
+    }
+}
+
+impl EngineAdapter for ArdourEngine {
+    fn name(&self) -> &'static str {
+        "Ardour"
+    }
+
+    fn start(&self, app: AppHandle, state_manager: StateManager) {
+        let _ = self.state.set(state_manager.clone());
+
+        // The first feedback message confirms Ardour accepted /set_surface.
+        let connected = AtomicBool::new(false);
+        listener::listen(ARDOUR_OSC_LISTEN_PORT, move |msg| {
+            if !connected.swap(true, Ordering::Relaxed) {
+                log::info!("Ardour OSC feedback received");
+                let _ = app.emit("engine-connection-changed", true);
+            }
+            handle_message(&state_manager, &msg.addr, &msg.args);
+        });
+
+        if let Err(e) = self.send(ArdourMessages::set_surface(ARDOUR_OSC_LISTEN_PORT)) {
+            log::warn!("Failed to register Ardour OSC surface: {}", e);
+        }
+    }
+
+    // ── Transport ───────────────────────────────────────────────────────────
+
+    fn play(&self) -> Result<(), String> {
+        self.send(ArdourMessages::play())
+    }
+
+    fn stop(&self) -> Result<(), String> {
+        self.send(ArdourMessages::stop())
+    }
+
+    fn toggle_record(&self) -> Result<(), String> {
+        self.send(ArdourMessages::toggle_record())
+    }
+
+    fn set_tempo(&self, _bpm: f32) -> Result<(), String> {
+        Err("Ardour's OSC surface cannot change the tempo. Edit the tempo map in Ardour.".into())
+    }
+
+    fn set_loop_enabled(&self, enabled: bool) -> Result<(), String> {
+        // Ardour only exposes a toggle; skip it if feedback says we're there.
+        let current = self.state.get().map(|s| s.get().loop_enabled);
+        if current == Some(enabled) {
+            return Ok(());
+        }
+        self.send(ArdourMessages::toggle_loop())
+    }
+
+    // ── Tracks ──────────────────────────────────────────────────────────────
+
+    fn set_track_volume(&self, track_index: i32, volume: f32) -> Result<(), String> {
+        self.send(ArdourMessages::set_strip_fader(track_index, volume.clamp(0.0, 1.0))?)
+    }
+
+    fn set_track_mute(&self, track_index: i32, mute: bool) -> Result<(), String> {
+        self.send(ArdourMessages::set_strip_mute(track_index, mute)?)
+    }
+
+    fn set_track_solo(&self, track_index: i32, solo: bool) -> Result<(), String> {
+        self.send(ArdourMessages::set_strip_solo(track_index, solo)?)
+    }
+
+    fn set_track_pan(&self, track_index: i32, pan: f32) -> Result<(), String> {
+        self.send(ArdourMessages::set_strip_pan(track_index, pan)?)
+    }
+
+    fn set_track_arm(&self, track_index: i32, arm: bool) -> Result<(), String> {
+        self.send(ArdourMessages::set_strip_recenable(track_index, arm)?)
+    }
+
+    // ── Master ──────────────────────────────────────────────────────────────
+
+    fn set_master_volume(&self, volume: f32) -> Result<(), String> {
+        self.send(ArdourMessages::set_master_fader(volume.clamp(0.0, 1.0)))
+    }
+
+    fn set_master_pan(&self, pan: f32) -> Result<(), String> {
+        self.send(ArdourMessages::set_master_pan(pan))
+    }
+
+    // ── Undo ────────────────────────────────────────────────────────────────
+
+    fn undo(&self) -> Result<(), String> {
+        self.send(ArdourMessages::undo())
+    }
+
+    fn redo(&self) -> Result<(), String> {
+        self.send(ArdourMessages::redo())
+    }
+
+    // ── Media ───────────────────────────────────────────────────────────────
+
+    fn load_sample(
+        &self,
+        _track_index: i32,
+        _scene_index: i32,
+        _file_path: &str,
+    ) -> Result<(), String> {
+        Err(
+            "Sample loading is not exposed by Ardour's OSC surface. \
+             Import audio via Ardour's Import dialog."
+                .into(),
+        )
+    }
+
+    // ── Custom params ───────────────────────────────────────────────────────
+
+    /// One trim control per track Ardour has reported.
+    fn custom_params(&self) -> Vec<ParamDef> {
+        let Some(state) = self.state.get() else {
+            return vec![];
+        };
+        state
+            .get()
+            .tracks
+            .iter()
+            .map(|t| ParamDef {
+                id: trim_param_id(t.index),
+                label: if t.name.is_empty() {
+                    format!("Track {} Trim", t.index + 1)
+                } else {
+                    format!("{} Trim", t.name)
+                },
+                min: TRIM_MIN_DB,
+                max: TRIM_MAX_DB,
+                value: None,
+            })
+            .collect()
+    }
+
+    fn set_custom_param(&self, id: &str, value: f32) -> Result<(), String> {
+        let track = parse_trim_param_id(id)
+            .ok_or_else(|| format!("Unknown custom param id: '{id}'"))?;
+        self.send(ArdourMessages::set_strip_trim(
+            track,
+            value.clamp(TRIM_MIN_DB, TRIM_MAX_DB),
+        )?)
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+//
+// The round-trip tests talk to a fake Ardour: a plain UDP socket on
+// localhost that records what Maestro sends and plays back feedback.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rosc::{OscMessage, OscPacket};
+    use std::net::UdpSocket;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    /// A fake Ardour OSC endpoint plus an engine whose client targets it.
+    fn fake_ardour() -> (UdpSocket, ArdourEngine) {
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        socket.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+        let client = OscClient::new(socket.local_addr().unwrap().port()).unwrap();
+        (socket, ArdourEngine::new(client))
+    }
+
+    fn recv(socket: &UdpSocket) -> OscMessage {
+        let mut buf = [0u8; 4096];
+        let (size, _) = socket.recv_from(&mut buf).expect("no OSC message received");
+        match rosc::decoder::decode_udp(&buf[..size]).unwrap().1 {
+            OscPacket::Message(m) => m,
+            OscPacket::Bundle(_) => panic!("unexpected bundle"),
+        }
+    }
+
+    fn send_to(socket: &UdpSocket, port: u16, addr: &str, args: Vec<OscType>) {
+        let packet = OscPacket::Message(OscMessage {
+            addr: addr.into(),
+            args,
+        });
+        let buf = rosc::encoder::encode(&packet).unwrap();
+        socket.send_to(&buf, ("127.0.0.1", port)).unwrap();
+    }
+
+    #[test]
+    fn commands_reach_fake_ardour() {
+        let (ardour, engine) = fake_ardour();
+
+        engine.set_track_volume(2, 0.5).unwrap();
+        let msg = recv(&ardour);
+        assert_eq!(msg.addr, "/strip/fader");
+        assert_eq!(msg.args, vec![OscType::Int(3), OscType::Float(0.5)]);
+
+        engine.set_track_pan(0, -1.0).unwrap();
+        let msg = recv(&ardour);
+        assert_eq!(msg.addr, "/strip/pan_stereo_position");
+        assert_eq!(msg.args, vec![OscType::Int(1), OscType::Float(0.0)]);
+
+        engine.set_track_mute(1, true).unwrap();
+        assert_eq!(recv(&ardour).args, vec![OscType::Int(2), OscType::Int(1)]);
+
+        engine.set_custom_param("track/4/trim", 30.0).unwrap();
+        let msg = recv(&ardour);
+        assert_eq!(msg.addr, "/strip/trimdB");
+        assert_eq!(msg.args, vec![OscType::Int(5), OscType::Float(TRIM_MAX_DB)]);
+
+        engine.play().unwrap();
+        assert_eq!(recv(&ardour).addr, "/transport_play");
+
+        assert!(engine.set_custom_param("reverb", 0.5).is_err());
+        assert!(engine.set_tempo(120.0).is_err());
+        // Strip 0 doesn't exist, and `i32::MAX` has no strip id.
+        assert!(engine.set_track_volume(-1, 0.5).is_err());
+        assert!(engine.set_track_solo(i32::MAX, true).is_err());
+    }
+
+    #[test]
+    fn set_surface_registers_reply_port() {
+        let (addr, args) = ArdourMessages::set_surface(ARDOUR_OSC_LISTEN_PORT);
+        assert_eq!(addr, "/set_surface");
+        assert_eq!(args[1], OscType::Int(STRIP_TYPES));
+        assert_eq!(args[3], OscType::Int(FADER_MODE_POSITION));
+        assert_eq!(args[6], OscType::Int(ARDOUR_OSC_LISTEN_PORT as i32));
+    }
+
+    #[test]
+    fn feedback_from_fake_ardour_updates_state() {
+        // Reserve a free port for Maestro's listener.
+        let port = UdpSocket::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        let state = StateManager::new();
+        let (tx, rx) = mpsc::channel();
+        let listener_state = state.clone();
+        listener::listen(port, move |msg| {
+            let change = apply_feedback(&listener_state, &msg.addr, &msg.args);
+            let _ = tx.send((msg.addr.clone(), change));
+        });
+
+        let ardour = UdpSocket::bind("127.0.0.1:0").unwrap();
+        // The listener binds asynchronously — retry until it answers.
+        let mut ready = false;
+        for _ in 0..50 {
+            send_to(&ardour, port, "/transport_play", vec![OscType::Int(1)]);
+            if rx.recv_timeout(Duration::from_millis(100)).is_ok() {
+                ready = true;
+                break;
+            }
+        }
+        assert!(ready, "listener never received feedback");
+
+        let feedback = [
+            ("/strip/name", vec![OscType::Int(2), OscType::String("Vox".into())]),
+            ("/strip/fader", vec![OscType::Int(2), OscType::Float(0.75)]),
+            ("/strip/pan_stereo_position", vec![OscType::Int(2), OscType::Float(1.0)]),
+            ("/strip/solo", vec![OscType::Int(2), OscType::Int(1)]),
+            ("/loop_toggle", vec![OscType::Int(1)]),
+            ("/position/bbt", vec![OscType::String("003|02|0960".into())]),
+            ("/session_name", vec![OscType::String("Demo".into())]),
+        ];
+        for (addr, args) in feedback {
+            send_to(&ardour, port, addr, args);
+            let (got, change) = rx.recv_timeout(Duration::from_secs(2)).unwrap();
+            assert_eq!(got, addr);
+            assert_ne!(change, Change::None, "{addr} was not applied");
+        }
+
+        let s = state.get();
+        assert!(s.is_playing);
+        assert!(s.loop_enabled);
+        assert_eq!(s.current_time, 9.5); // bar 3, beat 2, half a beat
+        assert_eq!(s.project.as_deref(), Some("Demo"));
+        assert_eq!(s.num_tracks, 2);
+        assert_eq!(s.tracks[1].name, "Vox");
+        assert_eq!(s.tracks[1].volume, 0.75);
+        assert_eq!(s.tracks[1].panning, 1.0);
+        assert!(s.tracks[1].solo);
+    }
+
+    #[test]
+    fn transport_stop_feedback() {
+        let state = StateManager::new();
+        state.set_playing(true);
+        // `/transport_stop 0` only reports the button is off — no change.
+        assert_eq!(
+            apply_feedback(&state, "/transport_stop", &[OscType::Int(0)]),
+            Change::None
+        );
+        assert!(state.get().is_playing);
+        apply_feedback(&state, "/transport_stop", &[OscType::Int(1)]);
+        assert!(!state.get().is_playing);
+    }
+
+    #[test]
+    fn bbt_parsing() {
+        assert_eq!(bbt_to_beats("001|01|0000"), Some(0.0));
+        assert_eq!(bbt_to_beats("002|01|0000"), Some(4.0));
+        assert_eq!(bbt_to_beats("garbage"), None);
+    }
+
+    #[test]
+    fn trim_param_ids_round_trip() {
+        assert_eq!(parse_trim_param_id(&trim_param_id(7)), Some(7));
+        assert_eq!(parse_trim_param_id("track/x/trim"), None);
+    }
+}
@@ -0,0 +1,743 @@
+//! Bitwig Studio engine adapter via the DrivenByMoss OSC extension.
+//!
+//! - **Send** commands to the extension's receive port (default 8000)
+//! - **Receive** feedback on its send port (default 9000)
+//!
+//! | Maestro          | DrivenByMoss address            | Value                |
+//! |------------------|---------------------------------|----------------------|
+//! | play / stop      | `/play`, `/stop`                | —                    |
+//! | record           | `/record`                       | toggle               |
+//! | tempo            | `/tempo/raw`                    | BPM                  |
+//! | loop             | `/repeat`                       | 0 / 1                |
+//! | song position    | `/position`                     | beats                |
+//! | track volume/pan | `/track/@/volume`, `/pan`       | 0–127                |
+//! | track mute/solo  | `/track/@/mute`, `/solo`        | 0 / 1                |
+//! | track arm        | `/track/@/recarm`               | 0 / 1                |
+//! | track send       | `/track/@/send/@/volume`        | 0–127                |
+//! | master vol/pan   | `/master/volume`, `/master/pan` | 0–127                |
+//! | clip launch/stop | `/track/@/clip/#/launch`, `/track/@/clip/stop` | — |
+//! | scene launch     | `/scene/#/launch`               | —                    |
+//! | device params    | `/device/param/@/value`         | 0–127                |
+//! | device page      | `/device/page/@`                | —                    |
+//!
+//! `@` is the 1-based track (or parameter) number within the extension's
+//! bank and `#` the 1-based scene.  Values use DrivenByMoss's default
+//! "Low (128)" value resolution.
+//!
+//! Clip slot feedback fills each track's `clips`, so Bitwig's clip launcher
+//! shows up in the same `ClipState` grid as Live's session view.  The cursor
+//! device (which follows Bitwig's track selection) is exposed through
+//! `custom_params`: one 0–1 slider per parameter on the selected remote
+//! control page, plus a `device/page` slider to switch pages.
+//!
+//! ## Setup
+//!
+//! Install DrivenByMoss, then in Bitwig → **Settings → Controllers → Add
+//! controller → Open Sound Control → OSC**, keep the default ports (receive
+//! 8000, send 9000, host `127.0.0.1`) and launch Maestro with
+//! `MAESTRO_ENGINE=bitwig`.
+//!
+//! References:
+//! * <https://github.com/git-moss/DrivenByMoss-Documentation/blob/master/Generic-Tools-Protocols/Open-Sound-Control-(OSC).md>
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use rosc::OscType;
+use tauri::{AppHandle, Emitter};
+
+use crate::engine::{EngineAdapter, ParamDef, StateManager};
+use crate::osc::feedback::{self, one_based, parse_track_addr, Change, TrackValue};
+use crate::osc::{args, client::OscClient, listener};
+
+/// Port DrivenByMoss sends feedback to.
+const BITWIG_OSC_LISTEN_PORT: u16 = 9000;
+
+/// Largest value at the "Low (128)" value resolution.
+const VALUE_MAX: f32 = 127.0;
+
+/// Parameters per remote control page, and pages selectable by number.
+const PAGE_SIZE: usize = 8;
+
+// ── Message builders ───────────────────────────────────────────────────────
+
+/// Typed OSC message builders for the DrivenByMoss OSC protocol.
+pub struct BitwigMessages;
+
+impl BitwigMessages {
+    /// Ask the extension to resend its complete state.
+    pub fn refresh() -> (String, Vec<OscType>) {
+        ("/refresh".into(), vec![])
+    }
+
+    pub fn play() -> (String, Vec<OscType>) {
+        ("/play".into(), vec![])
+    }
+
+    pub fn stop() -> (String, Vec<OscType>) {
+        ("/stop".into(), vec![])
+    }
+
+    pub fn toggle_record() -> (String, Vec<OscType>) {
+        ("/record".into(), vec![])
+    }
+
+    pub fn set_tempo(bpm: f32) -> (String, Vec<OscType>) {
+        ("/tempo/raw".into(), vec![OscType::Float(bpm)])
+    }
+
+    pub fn set_repeat(enabled: bool) -> (String, Vec<OscType>) {
+        ("/repeat".into(), vec![OscType::Int(enabled as i32)])
+    }
+
+    pub fn undo() -> (String, Vec<OscType>) {
+        ("/undo".into(), vec![])
+    }
+
+    pub fn redo() -> (String, Vec<OscType>) {
+        ("/redo".into(), vec![])
+    }
+
+    pub fn set_position(beats: f32) -> (String, Vec<OscType>) {
+        ("/position".into(), vec![OscType::Float(beats)])
+    }
+
+    pub fn set_track_volume(track: i32, volume: f32) -> Result<(String, Vec<OscType>), String> {
+        Ok((track_addr(track, "volume")?, vec![to_value(volume)]))
+    }
+
+    /// `pan` is Maestro's −1..1; DrivenByMoss expects 0..127 with 64 ≈ centre.
+    pub fn set_track_pan(track: i32, pan: f32) -> Result<(String, Vec<OscType>), String> {
+        let normalized = (pan.clamp(-1.0, 1.0) + 1.0) / 2.0;
+        Ok((track_addr(track, "pan")?, vec![to_value(normalized)]))
+    }
+
+    pub fn set_track_mute(track: i32, mute: bool) -> Result<(String, Vec<OscType>), String> {
+        Ok((track_addr(track, "mute")?, vec![OscType::Int(mute as i32)]))
+    }
+
+    pub fn set_track_solo(track: i32, solo: bool) -> Result<(String, Vec<OscType>), String> {
+        Ok((track_addr(track, "solo")?, vec![OscType::Int(solo as i32)]))
+    }
+
+    pub fn set_track_send(
+        track: i32,
+        send: i32,
+        volume: f32,
+    ) -> Result<(String, Vec<OscType>), String> {
+        let send = one_based(send, "send")?;
+        Ok((
+            track_addr(track, &format!("send/{send}/volume"))?,
+            vec![to_value(volume)],
+        ))
+    }
+
+    pub fn set_master_volume(volume: f32) -> (String, Vec<OscType>) {
+        ("/master/volume".into(), vec![to_value(volume)])
+    }
+
+    pub fn set_master_pan(pan: f32) -> (String, Vec<OscType>) {
+        let normalized = (pan.clamp(-1.0, 1.0) + 1.0) / 2.0;
+        ("/master/pan".into(), vec![to_value(normalized)])
+    }
+
+    pub fn set_track_recarm(track: i32, arm: bool) -> Result<(String, Vec<OscType>), String> {
+        Ok((track_addr(track, "recarm")?, vec![OscType::Int(arm as i32)]))
+    }
+
+    pub fn launch_clip(track: i32, scene: i32) -> Result<(String, Vec<OscType>), String> {
+        let scene = one_based(scene, "scene")?;
+        Ok((track_addr(track, &format!("clip/{scene}/launch"))?, vec![]))
+    }
+
+    /// Stops whatever clip is playing on `track`; Bitwig has no per-slot stop.
+    pub fn stop_track_clips(track: i32) -> Result<(String, Vec<OscType>), String> {
+        Ok((track_addr(track, "clip/stop")?, vec![]))
+    }
+
+    pub fn launch_scene(scene: i32) -> Result<(String, Vec<OscType>), String> {
+        Ok((format!("/scene/{}/launch", one_based(scene, "scene")?), vec![]))
+    }
+
+    /// `param` is 0-based within the selected page; `value` is 0–1.
+    pub fn set_device_param(param: usize, value: f32) -> (String, Vec<OscType>) {
+        (
+            format!("/device/param/{}/value", param + 1),
+            vec![to_value(value)],
+        )
+    }
+
+    /// `page` is 1-based, as shown in Bitwig.
+    pub fn select_device_page(page: usize) -> (String, Vec<OscType>) {
+        (format!("/device/page/{page}"), vec![])
+    }
+}
+
+/// `/track/<n>/<param>` with Maestro's 0-based index mapped to the 1-based `@`.
+fn track_addr(track: i32, param: &str) -> Result<String, String> {
+    Ok(format!("/track/{}/{}", one_based(track, "track")?, param))
+}
+
+/// Normalized 0–1 → integer value at the configured resolution.
+fn to_value(normalized: f32) -> OscType {
+    OscType::Int((normalized.clamp(0.0, 1.0) * VALUE_MAX).round() as i32)
+}
+
+fn from_value(arg: &OscType) -> Option<f64> {
+    args::as_f64(arg).map(|v| (v / VALUE_MAX as f64).clamp(0.0, 1.0))
+}
+
+/// Parse DrivenByMoss's `rgb(r,g,b)` colour strings (0–255 per channel).
+fn parse_rgb(s: &str) -> Option<u32> {
+    let inner = s.trim().strip_prefix("rgb(")?.strip_suffix(')')?;
+    let mut rgb = 0u32;
+    let mut count = 0;
+    for part in inner.split(',') {
+        let c: u8 = part.trim().parse().ok()?;
+        rgb = (rgb << 8) | c as u32;
+        count += 1;
+    }
+    (count == 3).then_some(rgb)
+}
+
+/// Convert a `bars.beats.sixteenths:ticks` position into beats from the
+/// song start.  The feedback carries no meter, so 4/4 is assumed.
+fn position_to_beats(s: &str) -> Option<f64> {
+    const BEATS_PER_BAR: f64 = 4.0;
+    let bbs = s.trim().split(':').next()?;
+    let mut parts = bbs.split('.').map(|p| p.trim().parse::<f64>());
+    let bars = parts.next()?.ok()?;
+    let beats = parts.next()?.ok()?;
+    let sixteenths = parts.next()?.ok()?;
+    Some((bars - 1.0) * BEATS_PER_BAR + (beats - 1.0) + (sixteenths - 1.0) / 4.0)
+}
+
+// ── Cursor device ──────────────────────────────────────────────────────────
+
+/// The cursor device's parameters on the selected remote page, collected
+/// from `/device/*` feedback.
+#[derive(Debug, Default)]
+struct CursorDevice {
+    exists: bool,
+    name: String,
+    page: String,
+    /// `Some(name)` for each parameter slot that exists on the page.
+    params: [Option<String>; PAGE_SIZE],
+    /// Last reported value of each slot, normalized 0–1.
+    values: [Option<f32>; PAGE_SIZE],
+}
+
+impl CursorDevice {
+    /// Apply a `/device/<path>` message.  Returns `false` if unhandled.
+    fn apply(&mut self, path: &str, osc_args: &[OscType]) -> bool {
+        match path {
+            "exists" => self.exists = args::first_bool(osc_args).unwrap_or(false),
+            "name" => self.name = args::first_str(osc_args).unwrap_or_default().to_string(),
+            "page/selected/name" => {
+                self.page = args::first_str(osc_args).unwrap_or_default().to_string()
+            }
+            _ => {
+                let Some((slot, field)) = path
+                    .strip_prefix("param/")
+                    .and_then(|rest| rest.split_once('/'))
+                    .and_then(|(n, field)| Some((n.parse::<usize>().ok()?.checked_sub(1)?, field)))
+                    .filter(|(slot, _)| *slot < PAGE_SIZE)
+                else {
+                    return false;
+                };
+                match field {
+                    "exists" => {
+                        if args::first_bool(osc_args) == Some(false) {
+                            self.params[slot] = None;
+                            self.values[slot] = None;
+                        } else if self.params[slot].is_none() {
+                            self.params[slot] = Some(String::new());
+                        }
+                    }
+                    "name" => {
+                        let name = args::first_str(osc_args).unwrap_or_default();
+                        // DrivenByMoss sends an empty name for unused slots.
+                        self.params[slot] = (!name.is_empty()).then(|| name.to_string());
+                    }
+                    "value" => {
+                        self.values[slot] = osc_args.first().and_then(from_value).map(|v| v as f32)
+                    }
+                    _ => return false,
+                }
+            }
+        }
+        true
+    }
+
+    fn param_defs(&self) -> Vec<ParamDef> {
+        if !self.exists {
+            return vec![];
+        }
+        let prefix = if self.page.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} / {}", self.name, self.page)
+        };
+        let mut defs: Vec<ParamDef> = self
+            .params
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, name)| {
+                let name = name.as_ref()?;
+                let label = if name.is_empty() {
+                    format!("Param {}", slot + 1)
+                } else {
+                    name.clone()
+                };
+                Some(ParamDef {
+                    id: format!("device/param/{}", slot + 1),
+                    label: format!("{prefix}: {label}"),
+                    min: 0.0,
+                    max: 1.0,
+                    value: self.values[slot],
+                })
+            })
+            .collect();
+        defs.push(ParamDef {
+            id: "device/page".into(),
+            label: format!("{}: Page", self.name),
+            min: 1.0,
+            max: PAGE_SIZE as f32,
+            value: None,
+        });
+        defs
+    }
+}
+
+// ── Feedback ───────────────────────────────────────────────────────────────
+
+/// Apply one DrivenByMoss transport/track/clip feedback message to `state`.
+fn apply_feedback(state: &StateManager, addr: &str, osc_args: &[OscType]) -> Change {
+    match addr {
+        "/play" => match args::first_bool(osc_args) {
+            Some(playing) => {
+                state.set_playing(playing);
+                Change::Transport
+            }
+            None => Change::None,
+        },
+        "/repeat" => match args::first_bool(osc_args) {
+            Some(enabled) => {
+                state.set_loop_enabled(enabled);
+                Change::Transport
+            }
+            None => Change::None,
+        },
+        "/tempo/raw" => match args::first_f64(osc_args) {
+            Some(bpm) => {
+                state.set_tempo(bpm);
+                Change::Tempo(bpm)
+            }
+            None => Change::None,
+        },
+        "/project/name" => match args::first_str(osc_args).filter(|n| !n.is_empty()) {
+            Some(name) => {
+                state.set_project(name.to_string());
+                Change::Project(name.to_string())
+            }
+            None => Change::None,
+        },
+        "/beat/str" => match args::first_str(osc_args).and_then(position_to_beats) {
+            Some(beats) => {
+                state.set_current_time(beats);
+                Change::SongTime(beats)
+            }
+            None => Change::None,
+        },
+        _ => match parse_track_addr(addr) {
+            Some((index, param)) => match param.strip_prefix("clip/") {
+                Some(clip) => apply_clip_feedback(state, index, clip, osc_args),
+                None => apply_track_feedback(state, index, param, osc_args),
+            },
+            None => Change::None,
+        },
+    }
+}
+
+fn apply_track_feedback(
+    state: &StateManager,
+    index: usize,
+    param: &str,
+    osc_args: &[OscType],
+) -> Change {
+    let Some(arg) = osc_args.first() else {
+        return Change::None;
+    };
+    let value = match param {
+        "volume" => from_value(arg).map(TrackValue::Volume),
+        "pan" => from_value(arg).map(|v| TrackValue::Pan(v * 2.0 - 1.0)),
+        "mute" => args::as_bool(arg).map(TrackValue::Mute),
+        "solo" => args::as_bool(arg).map(TrackValue::Solo),
+        "recarm" => args::as_bool(arg).map(TrackValue::Arm),
+        "name" => args::as_str(arg).map(TrackValue::Name),
+        "color" => args::as_str(arg).and_then(parse_rgb).map(TrackValue::Color),
+        "vu" => {
+            // Meters update constantly; don't grow the track list for them.
+            if let Some(level) = from_value(arg) {
+                state.set_track_meter(index, level);
+            }
+            None
+        }
+        _ => None,
+    };
+    match value {
+        Some(v) => feedback::apply_track_value(state, index, v),
+        None => Change::None,
+    }
+}
+
+/// Clip slot feedback: `/track/@/clip/#/<param>`.  Scenes past
+/// [`feedback::MAX_SCENES`] are ignored.
+fn apply_clip_feedback(
+    state: &StateManager,
+    track: usize,
+    path: &str,
+    osc_args: &[OscType],
+) -> Change {
+    let Some((scene, param)) = path
+        .split_once('/')
+        .and_then(|(n, param)| Some((n.parse::<usize>().ok()?.checked_sub(1)?, param)))
+        .filter(|(scene, _)| *scene < feedback::MAX_SCENES)
+    else {
+        return Change::None;
+    };
+    let Some(arg) = osc_args.first() else {
+        return Change::None;
+    };
+    let clip = match param {
+        "name" => match args::as_str(arg) {
+            Some(name) => state.set_clip_name(track, scene, name.to_string()),
+            None => return Change::None,
+        },
+        "color" => match args::as_str(arg).and_then(parse_rgb) {
+            Some(color) => state.set_clip_color(track, scene, color),
+            None => return Change::None,
+        },
+        "isPlaying" => match args::as_bool(arg) {
+            Some(playing) => state.update_clip(track, scene, |c| {
+                c.is_playing = playing;
+                if playing {
+                    c.is_triggered = false;
+                }
+            }),
+            None => return Change::None,
+        },
+        "isPlayingQueued" => match args::as_bool(arg) {
+            Some(queued) => state.update_clip(track, scene, |c| c.is_triggered = queued),
+            None => return Change::None,
+        },
+        // Emptied slots keep their place in the grid but lose their clip.
+        "hasContent" if args::as_bool(arg) == Some(false) => {
+            state.update_clip(track, scene, |c| {
+                c.name.clear();
+                c.color = 0;
+                c.is_playing = false;
+                c.is_triggered = false;
+            })
+        }
+        _ => return Change::None,
+    };
+    Change::Clip(clip)
+}
+
+fn handle_message(
+    state: &StateManager,
+    device: &Mutex<CursorDevice>,
+    addr: &str,
+    osc_args: &[OscType],
+) {
+    if let Some(path) = addr.strip_prefix("/device/") {
+        if device.lock().unwrap().apply(path, osc_args) {
+            return;
+        }
+    } else if apply_feedback(state, addr, osc_args) != Change::None {
+        return;
+    }
+    log::trace!("Unhandled Bitwig OSC: {} {:?}", addr, osc_args);
+}
+
+// ── Adapter ────────────────────────────────────────────────────────────────
+
+/// Bitwig Studio engine adapter using the DrivenByMoss OSC extension.
+pub struct BitwigEngine {
+    client: OscClient,
+    /// Shared with the listener thread, which fills it from `/device/*`.
+    device: Arc<Mutex<CursorDevice>>,
+}
+
+impl BitwigEngine {
+    pub fn new(client: OscClient) -> Self {
+        Self {
+            client,
+            device: Arc::new(Mutex::new(CursorDevice::default())),
+        }
+    }
+
+    fn send(&self, (addr, args): (String, Vec<OscType>)) -> Result<(), String> {
+        self.client.send(&addr, args).map_err(|e| e.to_string())
+    }
+}
+
+impl EngineAdapter for BitwigEngine {
+    fn name(&self) -> &'static str {
+        "Bitwig"
+    }
+
+    fn start(&self, app: AppHandle, state_manager: StateManager) {
+        // DrivenByMoss has no ping — treat the first feedback message as the
+        // connection being established.
+        let connected = AtomicBool::new(false);
+        let device = Arc::clone(&self.device);
+        listener::listen(BITWIG_OSC_LISTEN_PORT, move |msg| {
+            if !connected.swap(true, Ordering::Relaxed) {
+                log::info!("Bitwig OSC feedback received");
+                let _ = app.emit("engine-connection-changed", true);
+            }
+            handle_message(&state_manager, &device, &msg.addr, &msg.args);
+        });
+
+        if let Err(e) = self.send(BitwigMessages::refresh()) {
+            log::warn!("Failed to request Bitwig state refresh: {}", e);
+        }
+    }
+
+    // ── Transport ───────────────────────────────────────────────────────────
+
+    fn play(&self) -> Result<(), String> {
+        self.send(BitwigMessages::play())
+    }
+
+    fn stop(&self) -> Result<(), String> {
+        self.send(BitwigMessages::stop())
+    }
+
+    fn toggle_record(&self) -> Result<(), String> {
+        self.send(BitwigMessages::toggle_record())
+    }
+
+    fn set_tempo(&self, bpm: f32) -> Result<(), String> {
+        self.send(BitwigMessages::set_tempo(bpm))
+    }
+
+    fn set_loop_enabled(&self, enabled: bool) -> Result<(), String> {
+        self.send(BitwigMessages::set_repeat(enabled))
+    }
+
+    fn set_song_time(&self, beats: f64) -> Result<(), String> {
+        self.send(BitwigMessages::set_position(beats as f32))
+    }
+
+    // ── Tracks ──────────────────────────────────────────────────────────────
+
+    fn set_track_volume(&self, track_index: i32, volume: f32) -> Result<(), String> {
+        self.send(BitwigMessages::set_track_volume(track_index, volume)?)
+    }
+
+    fn set_track_mute(&self, track_index: i32, mute: bool) -> Result<(), String> {
+        self.send(BitwigMessages::set_track_mute(track_index, mute)?)
+    }
+
+    fn set_track_solo(&self, track_index: i32, solo: bool) -> Result<(), String> {
+        self.send(BitwigMessages::set_track_solo(track_index, solo)?)
+    }
+
+    fn set_track_pan(&self, track_index: i32, pan: f32) -> Result<(), String> {
+        self.send(BitwigMessages::set_track_pan(track_index, pan)?)
+    }
+
+    fn set_track_arm(&self, track_index: i32, arm: bool) -> Result<(), String> {
+        self.send(BitwigMessages::set_track_recarm(track_index, arm)?)
+    }
+
+    // ── Sends and master ────────────────────────────────────────────────────
+
+    fn set_track_send(&self, track_index: i32, send_index: i32, value: f32) -> Result<(), String> {
+        self.send(BitwigMessages::set_track_send(track_index, send_index, value)?)
+    }
+
+    fn set_master_volume(&self, volume: f32) -> Result<(), String> {
+        self.send(BitwigMessages::set_master_volume(volume))
+    }
+
+    fn set_master_pan(&self, pan: f32) -> Result<(), String> {
+        self.send(BitwigMessages::set_master_pan(pan))
+    }
+
+    // ── Clips ───────────────────────────────────────────────────────────────
+
+    fn fire_clip(&self, track_index: i32, scene_index: i32) -> Result<(), String> {
+        self.send(BitwigMessages::launch_clip(track_index, scene_index)?)
+    }
+
+    fn stop_clip(&self, track_index: i32, _scene_index: i32) -> Result<(), String> {
+        self.send(BitwigMessages::stop_track_clips(track_index)?)
+    }
+
+    // ── Scenes ──────────────────────────────────────────────────────────────
+
+    fn fire_scene(&self, scene_index: i32) -> Result<(), String> {
+        self.send(BitwigMessages::launch_scene(scene_index)?)
+    }
+
+    // ── Undo ────────────────────────────────────────────────────────────────
+
+    fn undo(&self) -> Result<(), String> {
+        self.send(BitwigMessages::undo())
+    }
+
+    fn redo(&self) -> Result<(), String> {
+        self.send(BitwigMessages::redo())
+    }
+
+    // ── Media ───────────────────────────────────────────────────────────────
+
+    fn load_sample(
+        &self,
+        _track_index: i32,
+        _scene_index: i32,
+        _file_path: &str,
+    ) -> Result<(), String> {
+        Err(
+            "Sample loading is not exposed by the DrivenByMoss OSC protocol. \
+             Drag audio files from Bitwig's browser into a clip slot."
+                .into(),
+        )
+    }
+
+    // ── Custom params ───────────────────────────────────────────────────────
+
+    /// The cursor device's parameters on its selected remote control page.
+    fn custom_params(&self) -> Vec<ParamDef> {
+        self.device.lock().unwrap().param_defs()
+    }
+
+    fn set_custom_param(&self, id: &str, value: f32) -> Result<(), String> {
+        if id == "device/page" {
+            let page = (value.round() as usize).clamp(1, PAGE_SIZE);
+            return self.send(BitwigMessages::select_device_page(page));
+        }
+        let slot = id
+            .strip_prefix("device/param/")
+            .and_then(|n| n.parse::<usize>().ok())
+            .and_then(|n| n.checked_sub(1))
+            .filter(|slot| *slot < PAGE_SIZE)
+            .ok_or_else(|| format!("Unknown custom param id: '{id}'"))?;
+        self.send(BitwigMessages::set_device_param(slot, value))
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_scale_to_default_resolution() {
+        assert_eq!(BitwigMessages::set_track_volume(0, 1.0).unwrap().1, vec![OscType::Int(127)]);
+        assert_eq!(BitwigMessages::set_track_pan(2, -1.0).unwrap().1, vec![OscType::Int(0)]);
+        assert_eq!(BitwigMessages::set_track_pan(2, 0.0).unwrap().0, "/track/3/pan");
+        assert_eq!(BitwigMessages::launch_clip(0, 3).unwrap().0, "/track/1/clip/4/launch");
+        assert!(BitwigMessages::set_track_volume(-1, 1.0).is_err());
+        assert!(BitwigMessages::launch_clip(i32::MAX, 0).is_err());
+        assert!(BitwigMessages::launch_scene(-1).is_err());
+        assert_eq!(BitwigMessages::set_device_param(7, 0.5).0, "/device/param/8/value");
+    }
+
+    #[test]
+    fn track_feedback_updates_state() {
+        let state = StateManager::new();
+        apply_feedback(&state, "/track/2/name", &[OscType::String("Drums".into())]);
+        apply_feedback(&state, "/track/2/volume", &[OscType::Int(127)]);
+        apply_feedback(&state, "/track/2/color", &[OscType::String("rgb(255,0,16)".into())]);
+        assert_eq!(
+            apply_feedback(&state, "/track/9/vu", &[OscType::Int(100)]),
+            Change::None
+        );
+
+        let s = state.get();
+        assert_eq!(s.num_tracks, 2);
+        assert_eq!(s.tracks[1].name, "Drums");
+        assert_eq!(s.tracks[1].volume, 1.0);
+        assert_eq!(s.tracks[1].color, 0xFF0010);
+    }
+
+    #[test]
+    fn clip_feedback_fills_grid() {
+        let state = StateManager::new();
+        let Change::Clip(clip) =
+            apply_feedback(&state, "/track/1/clip/3/name", &[OscType::String("Verse".into())])
+        else {
+            panic!("expected a clip change");
+        };
+        assert_eq!(clip.name, "Verse");
+        apply_feedback(&state, "/track/1/clip/3/isPlayingQueued", &[OscType::Int(1)]);
+        let s = state.get();
+        assert_eq!(s.num_scenes, 3);
+        let clip = &s.tracks[0].clips[2];
+        assert_eq!((clip.track_index, clip.scene_index), (0, 2));
+        assert_eq!(clip.name, "Verse");
+        assert!(clip.is_triggered && !clip.is_playing);
+
+        apply_feedback(&state, "/track/1/clip/3/isPlaying", &[OscType::Int(1)]);
+        let clip = &state.get().tracks[0].clips[2];
+        assert!(clip.is_playing && !clip.is_triggered);
+
+        apply_feedback(&state, "/track/1/clip/3/hasContent", &[OscType::Int(0)]);
+        assert_eq!(state.get().tracks[0].clips[2].name, "");
+
+        let far = "/track/1/clip/4000000000/isPlaying";
+        assert_eq!(apply_feedback(&state, far, &[OscType::Int(1)]), Change::None);
+        assert_eq!(state.get().num_scenes, 3);
+    }
+
+    #[test]
+    fn transport_feedback_updates_state() {
+        let state = StateManager::new();
+        apply_feedback(&state, "/play", &[OscType::Int(1)]);
+        apply_feedback(&state, "/tempo/raw", &[OscType::Float(128.0)]);
+        assert_eq!(
+            apply_feedback(&state, "/beat/str", &[OscType::String("2.3.3:00".into())]),
+            Change::SongTime(6.5)
+        );
+        apply_feedback(&state, "/project/name", &[OscType::String("Demo".into())]);
+        let s = state.get();
+        assert!(s.is_playing);
+        assert_eq!(s.tempo, 128.0);
+        assert_eq!(s.project.as_deref(), Some("Demo"));
+    }
+
+    #[test]
+    fn cursor_device_becomes_param_defs() {
+        let mut device = CursorDevice::default();
+        assert!(device.param_defs().is_empty());
+
+        device.apply("exists", &[OscType::Int(1)]);
+        device.apply("name", &[OscType::String("Polysynth".into())]);
+        device.apply("page/selected/name", &[OscType::String("Filter".into())]);
+        device.apply("param/1/name", &[OscType::String("Cutoff".into())]);
+        device.apply("param/1/value", &[OscType::Int(127)]);
+        device.apply("param/2/exists", &[OscType::Int(1)]);
+        device.apply("param/3/name", &[OscType::String(String::new())]);
+        assert!(!device.apply("param/9/name", &[OscType::String("Out of page".into())]));
+
+        let defs = device.param_defs();
+        let ids: Vec<_> = defs.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, ["device/param/1", "device/param/2", "device/page"]);
+        assert_eq!(defs[0].label, "Polysynth / Filter: Cutoff");
+        assert_eq!(defs[0].value, Some(1.0));
+        assert_eq!(defs[1].label, "Polysynth / Filter: Param 2");
+    }
+
+    #[test]
+    fn rgb_parsing() {
+        assert_eq!(parse_rgb("rgb(0, 128, 255)"), Some(0x0080FF));
+        assert_eq!(parse_rgb("rgb(1,2)"), None);
+        assert_eq!(parse_rgb("#ff0000"), None);
+    }
+}
@@ -0,0 +1,409 @@
+//! Typed change events published by [`StateManager`].
+//!
+//! Every `StateManager` mutation that changes a value publishes a
+//! [`StateChange`] on a broadcast channel; setting a value to what it
+//! already was publishes nothing.  [`bridge`] is the one place that turns
+//! them into frontend events, throttled to the UI frame rate; other
+//! subsystems call [`StateManager::subscribe`] to follow the engine.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use tokio::time::MissedTickBehavior;
+
+use super::state::{
+    ClipState, EngineState, SceneState, StateManager, TrackState, VersionedState,
+};
+
+/// Changes buffered per subscriber before it starts missing them.
+pub const CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum StateChange {
+    Playing(bool),
+    Tempo(f64),
+    SongTime(f64),
+    Loop(bool),
+    Project(String),
+    /// Tracks were added, removed or reordered; carries the new count.
+    TrackList(usize),
+    Track { index: usize, field: TrackField },
+    Clip(ClipState),
+    /// Scenes were added or removed; carries the new count.
+    SceneList(usize),
+    Scene(SceneState),
+    /// Return tracks were added or removed; carries the new count.
+    ReturnTrackList(usize),
+    ReturnTrack(TrackState),
+    Master(TrackState),
+}
+
+/// One changed value on a track.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "field", content = "value", rename_all = "snake_case")]
+pub enum TrackField {
+    Name(String),
+    Volume(f64),
+    Pan(f64),
+    Mute(bool),
+    Solo(bool),
+    Arm(bool),
+    Color(u32),
+    /// Send index and level.
+    Send(usize, f64),
+    Meter(f64),
+}
+
+impl TrackField {
+    /// Store the value on `track`.  Returns whether it changed.
+    pub(super) fn apply(&self, track: &mut TrackState) -> bool {
+        fn set<T: PartialEq + Clone>(slot: &mut T, value: &T) -> bool {
+            let changed = slot != value;
+            if changed {
+                *slot = value.clone();
+            }
+            changed
+        }
+        match self {
+            TrackField::Name(name) => set(&mut track.name, name),
+            TrackField::Volume(v) => set(&mut track.volume, v),
+            TrackField::Pan(p) => set(&mut track.panning, p),
+            TrackField::Mute(m) => set(&mut track.mute, m),
+            TrackField::Solo(s) => set(&mut track.solo, s),
+            TrackField::Arm(a) => set(&mut track.arm, a),
+            TrackField::Color(c) => set(&mut track.color, c),
+            TrackField::Send(send, level) => {
+                if track.sends.len() <= *send {
+                    track.sends.resize(send + 1, 0.0);
+                }
+                set(&mut track.sends[*send], level)
+            }
+            TrackField::Meter(level) => set(&mut track.meter_level, level),
+        }
+    }
+}
+
+/// Frame rate of coalesced UI updates when `MAESTRO_UI_FPS` is unset.
+pub const DEFAULT_FRAME_RATE: u32 = 30;
+
+/// The UI frame rate from `MAESTRO_UI_FPS` (1–120).
+pub fn frame_rate_from_env() -> u32 {
+    std::env::var("MAESTRO_UI_FPS")
+        .ok()
+        .and_then(|s| s.trim().parse::<u32>().ok())
+        .map_or(DEFAULT_FRAME_RATE, |fps| fps.clamp(1, 120))
+}
+
+/// Whether `change` skips the frame and goes out at once.  Transport
+/// changes must not wait behind a burst of meter updates.
+fn is_urgent(change: &StateChange) -> bool {
+    matches!(
+        change,
+        StateChange::Playing(_)
+            | StateChange::Loop(_)
+            | StateChange::Tempo(_)
+            | StateChange::Project(_)
+    )
+}
+
+/// What a change targets: a later change to the same target replaces it.
+type Target = (&'static str, usize, usize);
+
+fn target(change: &StateChange) -> Target {
+    match change {
+        StateChange::Playing(_) => ("playing", 0, 0),
+        StateChange::Tempo(_) => ("tempo", 0, 0),
+        StateChange::SongTime(_) => ("song_time", 0, 0),
+        StateChange::Loop(_) => ("loop", 0, 0),
+        StateChange::Project(_) => ("project", 0, 0),
+        StateChange::TrackList(_) => ("track_list", 0, 0),
+        StateChange::Track { index, field } => match field {
+            TrackField::Name(_) => ("track_name", *index, 0),
+            TrackField::Volume(_) => ("track_volume", *index, 0),
+            TrackField::Pan(_) => ("track_pan", *index, 0),
+            TrackField::Mute(_) => ("track_mute", *index, 0),
+            TrackField::Solo(_) => ("track_solo", *index, 0),
+            TrackField::Arm(_) => ("track_arm", *index, 0),
+            TrackField::Color(_) => ("track_color", *index, 0),
+            TrackField::Send(send, _) => ("track_send", *index, *send),
+            TrackField::Meter(_) => ("track_meter", *index, 0),
+        },
+        StateChange::Clip(clip) => ("clip", clip.track_index, clip.scene_index),
+        StateChange::SceneList(_) => ("scene_list", 0, 0),
+        StateChange::Scene(scene) => ("scene", scene.index, 0),
+        StateChange::ReturnTrackList(_) => ("return_track_list", 0, 0),
+        StateChange::ReturnTrack(track) => ("return_track", track.index, 0),
+        StateChange::Master(_) => ("master", 0, 0),
+    }
+}
+
+/// Changes collected between two UI frames, one per target, in the order
+/// their targets first changed.
+#[derive(Default)]
+struct Frame {
+    changes: Vec<StateChange>,
+    slots: HashMap<Target, usize>,
+}
+
+impl Frame {
+    fn push(&mut self, change: StateChange) {
+        match self.slots.entry(target(&change)) {
+            Entry::Occupied(slot) => self.changes[*slot.get()] = change,
+            Entry::Vacant(slot) => {
+                slot.insert(self.changes.len());
+                self.changes.push(change);
+            }
+        }
+    }
+
+    fn take(&mut self) -> Vec<StateChange> {
+        self.slots.clear();
+        std::mem::take(&mut self.changes)
+    }
+}
+
+/// Payload of the `state-changed` event.
+#[derive(Debug, Clone, Serialize)]
+struct StateBatch<'a> {
+    /// State version once these changes are applied.
+    version: u64,
+    /// Version of the previous batch, or `None` if changes were dropped
+    /// in between.  A UI that isn't at `since` has missed changes and
+    /// should reread the state with `get_engine_state`.
+    since: Option<u64>,
+    changes: &'a [StateChange],
+}
+
+/// One entry of the `meters-updated` payload.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct MeterLevel {
+    index: usize,
+    level: f64,
+}
+
+/// Whether `change` alters the track list beyond a meter level, so the UI
+/// needs the whole list again.
+fn reshapes_tracks(change: &StateChange) -> bool {
+    match change {
+        StateChange::TrackList(_) | StateChange::ReturnTrackList(_) => true,
+        StateChange::Track { field, .. } => !matches!(field, TrackField::Meter(_)),
+        _ => false,
+    }
+}
+
+/// The track meter levels in `batch`.
+fn meter_levels(batch: &[StateChange]) -> Vec<MeterLevel> {
+    batch
+        .iter()
+        .filter_map(|change| match change {
+            StateChange::Track {
+                index,
+                field: TrackField::Meter(level),
+            } => Some(MeterLevel {
+                index: *index,
+                level: *level,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Forward state changes to the frontend, for the life of the app.
+///
+/// Changes go out as versioned `state-changed` batches, followed by the per-area
+/// events the stores listen to (`tracks-updated`, `clip-changed`, …).
+/// Frames that only move meters send just the levels, as `meters-updated`.
+/// Transport, tempo and project changes are sent at once; everything else
+/// is coalesced and sent at most `fps` times a second, so streaming meters
+/// and song time don't flood the webview.  If the bridge falls behind and
+/// misses changes it resends every area.
+pub async fn bridge(
+    app: AppHandle,
+    state: StateManager,
+    mut changes: Receiver<StateChange>,
+    fps: u32,
+) {
+    let mut tick = tokio::time::interval(Duration::from_secs(1) / fps.max(1));
+    tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut frame = Frame::default();
+    let mut since = Some(state.version());
+    loop {
+        tokio::select! {
+            change = changes.recv() => match change {
+                Ok(change) if is_urgent(&change) => {
+                    since = Some(emit(&app, &state, since, &[change]));
+                }
+                Ok(change) => frame.push(change),
+                Err(RecvError::Lagged(missed)) => {
+                    log::debug!("State bridge missed {} changes; resending state", missed);
+                    frame.take();
+                    since = None;
+                    emit_all(&app, &state);
+                }
+                Err(RecvError::Closed) => return,
+            },
+            _ = tick.tick() => {
+                let batch = frame.take();
+                if !batch.is_empty() {
+                    since = Some(emit(&app, &state, since, &batch));
+                }
+            }
+        }
+    }
+}
+
+/// Send `batch` and the area events it touches, each area once.  Returns
+/// the version sent.
+fn emit(app: &AppHandle, state: &StateManager, since: Option<u64>, batch: &[StateChange]) -> u64 {
+    let VersionedState { version, state: s } = state.snapshot();
+    let _ = app.emit(
+        "state-changed",
+        StateBatch {
+            version,
+            since,
+            changes: batch,
+        },
+    );
+    let touches = |f: fn(&StateChange) -> bool| batch.iter().any(f);
+    let tracks = touches(reshapes_tracks);
+
+    if touches(|c| matches!(c, StateChange::Playing(_) | StateChange::Loop(_))) {
+        emit_transport(app, &s);
+    }
+    for change in batch {
+        match change {
+            StateChange::Tempo(bpm) => {
+                let _ = app.emit("tempo-changed", bpm);
+            }
+            StateChange::SongTime(beats) => {
+                let _ = app.emit("song-time", beats);
+            }
+            StateChange::Project(name) => {
+                let _ = app.emit("project-changed", name);
+            }
+            // The track list carries its clips.
+            StateChange::Clip(clip) if !tracks => {
+                let _ = app.emit("clip-changed", clip);
+            }
+            StateChange::Master(master) => {
+                let _ = app.emit("master-updated", master);
+            }
+            _ => {}
+        }
+    }
+    if tracks {
+        let _ = app.emit("tracks-updated", &s.tracks);
+    } else {
+        let meters = meter_levels(batch);
+        if !meters.is_empty() {
+            let _ = app.emit("meters-updated", &meters);
+        }
+    }
+    if touches(|c| matches!(c, StateChange::SceneList(_) | StateChange::Scene(_))) {
+        let _ = app.emit("scenes-updated", &s.scenes);
+    }
+    if touches(|c| matches!(c, StateChange::ReturnTrackList(_) | StateChange::ReturnTrack(_))) {
+        let _ = app.emit("return-tracks-updated", &s.return_tracks);
+    }
+    version
+}
+
+fn emit_transport(app: &AppHandle, s: &EngineState) {
+    let _ = app.emit(
+        "transport-state",
+        serde_json::json!({ "is_playing": s.is_playing, "loop_enabled": s.loop_enabled }),
+    );
+}
+
+fn emit_all(app: &AppHandle, state: &StateManager) {
+    let s = state.get();
+    emit_transport(app, &s);
+    let _ = app.emit("tempo-changed", s.tempo);
+    let _ = app.emit("song-time", s.current_time);
+    let _ = app.emit("tracks-updated", s.tracks);
+    let _ = app.emit("scenes-updated", s.scenes);
+    let _ = app.emit("return-tracks-updated", s.return_tracks);
+    let _ = app.emit("master-updated", s.master);
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meter(index: usize, level: f64) -> StateChange {
+        StateChange::Track {
+            index,
+            field: TrackField::Meter(level),
+        }
+    }
+
+    #[test]
+    fn frames_keep_the_latest_change_per_target() {
+        let mut frame = Frame::default();
+        frame.push(StateChange::SongTime(1.0));
+        frame.push(meter(0, 0.1));
+        frame.push(meter(1, 0.2));
+        frame.push(StateChange::SongTime(1.5));
+        frame.push(meter(0, 0.3));
+        frame.push(StateChange::Track {
+            index: 0,
+            field: TrackField::Volume(0.5),
+        });
+
+        assert_eq!(
+            frame.take(),
+            [
+                StateChange::SongTime(1.5),
+                meter(0, 0.3),
+                meter(1, 0.2),
+                StateChange::Track {
+                    index: 0,
+                    field: TrackField::Volume(0.5),
+                },
+            ]
+        );
+        assert!(frame.take().is_empty());
+    }
+
+    #[test]
+    fn transport_changes_are_urgent() {
+        assert!(is_urgent(&StateChange::Playing(true)));
+        assert!(is_urgent(&StateChange::Tempo(120.0)));
+        assert!(!is_urgent(&StateChange::SongTime(4.0)));
+        assert!(!is_urgent(&meter(0, 1.0)));
+    }
+
+    #[test]
+    fn meter_only_frames_send_just_the_levels() {
+        let volume = StateChange::Track {
+            index: 1,
+            field: TrackField::Volume(0.5),
+        };
+        assert!(!reshapes_tracks(&meter(0, 0.3)));
+        assert!(reshapes_tracks(&volume));
+        assert!(reshapes_tracks(&StateChange::TrackList(3)));
+
+        let batch = [StateChange::SongTime(2.0), meter(0, 0.3), meter(2, 0.1)];
+        assert_eq!(
+            meter_levels(&batch),
+            [
+                MeterLevel {
+                    index: 0,
+                    level: 0.3
+                },
+                MeterLevel {
+                    index: 2,
+                    level: 0.1
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,1101 @@
+//! Generic JACK MIDI engine adapter.
+//!
+//! Drives any DAW, hardware mixer or console that can be MIDI-learned,
+//! using nothing but the JACK audio server:
+//!
+//! * **Transport** (play/stop/tempo) — via the JACK Transport protocol, with
+//!   Maestro registered as JACK *timebase master* so BPM changes reach every
+//!   JACK client.  Each transport action can additionally send a mapped MIDI
+//!   message for targets that don't follow JACK transport.
+//!
+//! * **Track parameters** (volume, pan, mute, solo) — via MIDI CC on a JACK
+//!   MIDI output port (`maestro:control_out`).
+//!
+//! * **Custom params and note triggers** — arbitrary CCs and notes exposed to
+//!   the frontend as labeled controls.
+//!
+//! * **MIDI clock / MMC** (optional) — 24 ppqn clock and MIDI Machine Control
+//!   on the same port, see [`crate::engine::midi_sync`].
+//!
+//! Everything is described by a TOML mapping file, by default
+//! `~/.config/maestro/midi-map.toml` (override with `MAESTRO_MIDI_MAP`).
+//! Select this engine with `MAESTRO_ENGINE=midi`.  The Zrythm adapter is the
+//! same engine with Zrythm's map file and name.
+//!
+//! ```toml
+//! [tracks]
+//! volume = { cc = 7 }                          # channel = track index (default)
+//! pan    = { cc = 10 }
+//! mute   = { cc = 0, channel = 0, cc_stride = 1 }  # track N → CC N on MIDI channel 1
+//! solo   = { cc = 118 }
+//!
+//! [transport]
+//! jack   = true                                # JACK transport + timebase (default)
+//! play   = { channel = 15, cc = 115 }
+//! stop   = { channel = 15, cc = 116 }
+//! record = { channel = 15, note = 95 }
+//! loop   = { channel = 15, cc = 114 }          # 127 = on, 0 = off
+//!
+//! [[params]]
+//! id = "reverb"
+//! label = "Reverb Wet"
+//! cc = 20
+//! channel = 0
+//!
+//! [[notes]]
+//! id = "kick"
+//! label = "Kick"
+//! note = 36
+//! channel = 9
+//! velocity = 110
+//!
+//! [sync]
+//! midi_clock = true
+//! ```
+//!
+//! References:
+//! * <https://jackaudio.org/api/>
+
+use std::collections::VecDeque;
+use std::ffi::c_void;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use jack::{Client, ClientOptions, Control, MidiOut, Port, ProcessHandler, ProcessScope, RawMidi};
+use tauri::{AppHandle, Emitter};
+
+use crate::engine::midi_sync::{self, ClockState, MidiClock, MmcCommand, SyncConfig};
+use crate::engine::{EngineAdapter, ParamDef, StateManager};
+
+// ── Mapping file ───────────────────────────────────────────────────────────
+
+/// One entry from the `[[params]]` table: a CC exposed as a labeled slider.
+#[derive(Debug, serde::Deserialize)]
+pub struct CcParamConfig {
+    pub id: String,
+    pub label: String,
+    /// MIDI CC number (0–127).
+    pub cc: u8,
+    /// MIDI channel (0–15).
+    pub channel: u8,
+    #[serde(default = "default_zero")]
+    pub min: f32,
+    #[serde(default = "default_one")]
+    pub max: f32,
+}
+
+/// One entry from the `[[notes]]` table: a note exposed as an on/off trigger.
+#[derive(Debug, serde::Deserialize)]
+pub struct NoteTriggerConfig {
+    pub id: String,
+    pub label: String,
+    /// MIDI note number (0–127).
+    pub note: u8,
+    /// MIDI channel (0–15).
+    pub channel: u8,
+    #[serde(default = "default_velocity")]
+    pub velocity: u8,
+}
+
+/// CC assignment for one track parameter.
+///
+/// Without `channel`, the track index selects the MIDI channel (up to 16
+/// tracks).  With a fixed `channel`, track N uses CC `cc + N * cc_stride`,
+/// which suits hardware consoles that put every fader on one channel.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct TrackControl {
+    pub cc: u8,
+    #[serde(default)]
+    pub channel: Option<u8>,
+    #[serde(default = "default_stride")]
+    pub cc_stride: u8,
+}
+
+impl TrackControl {
+    const fn per_channel(cc: u8) -> Self {
+        Self {
+            cc,
+            channel: None,
+            cc_stride: 1,
+        }
+    }
+
+    /// Resolve the `(channel, cc)` pair addressing `track_index`.
+    pub fn address(&self, track_index: i32) -> Result<(u8, u8), String> {
+        match self.channel {
+            None => {
+                if !(0..MAX_MIDI_CH).contains(&track_index) {
+                    return Err(format!(
+                        "Track index {} out of MIDI channel range (0–{})",
+                        track_index,
+                        MAX_MIDI_CH - 1
+                    ));
+                }
+                Ok((track_index as u8, self.cc))
+            }
+            Some(ch) => {
+                let cc = track_index
+                    .checked_mul(self.cc_stride as i32)
+                    .and_then(|offset| offset.checked_add(self.cc as i32))
+                    .filter(|cc| track_index >= 0 && *cc <= 127);
+                let Some(cc) = cc else {
+                    return Err(format!(
+                        "Track index {} has no CC mapping (CC {} + {} × {} exceeds 127)",
+                        track_index, self.cc, track_index, self.cc_stride
+                    ));
+                };
+                Ok((ch, cc as u8))
+            }
+        }
+    }
+}
+
+/// The `[tracks]` table.  Defaults follow the standard MIDI volume/pan CCs
+/// plus two commonly free CCs for mute/solo.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct TrackMap {
+    #[serde(default = "default_volume")]
+    pub volume: TrackControl,
+    #[serde(default = "default_pan")]
+    pub pan: TrackControl,
+    #[serde(default = "default_mute")]
+    pub mute: TrackControl,
+    #[serde(default = "default_solo")]
+    pub solo: TrackControl,
+}
+
+impl Default for TrackMap {
+    fn default() -> Self {
+        Self {
+            volume: default_volume(),
+            pan: default_pan(),
+            mute: default_mute(),
+            solo: default_solo(),
+        }
+    }
+}
+
+/// A fixed MIDI message bound to a transport action.
+///
+/// Sends a CC when `cc` is set, otherwise a note-on/note-off pair for `note`.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct MidiAction {
+    pub channel: u8,
+    #[serde(default)]
+    pub cc: Option<u8>,
+    #[serde(default)]
+    pub note: Option<u8>,
+    /// CC value or note velocity for the "on" state.
+    #[serde(default = "default_on_value")]
+    pub value: u8,
+}
+
+impl MidiAction {
+    /// Encode this action; `on = false` sends CC value 0 (used for loop off).
+    pub fn messages(&self, on: bool) -> Vec<Vec<u8>> {
+        let ch = self.channel & 0x0F;
+        match (self.cc, self.note) {
+            (Some(cc), _) => vec![vec![0xB0 | ch, cc, if on { self.value } else { 0 }]],
+            (None, Some(note)) => vec![
+                vec![0x90 | ch, note, self.value.max(1)],
+                vec![0x80 | ch, note, 0],
+            ],
+            (None, None) => vec![],
+        }
+    }
+}
+
+/// The `[transport]` table.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct TransportMap {
+    /// Drive JACK transport and act as timebase master.
+    #[serde(default = "default_true")]
+    pub jack: bool,
+    #[serde(default)]
+    pub play: Option<MidiAction>,
+    #[serde(default)]
+    pub stop: Option<MidiAction>,
+    #[serde(default)]
+    pub record: Option<MidiAction>,
+    #[serde(default, rename = "loop")]
+    pub loop_toggle: Option<MidiAction>,
+}
+
+impl Default for TransportMap {
+    fn default() -> Self {
+        Self {
+            jack: true,
+            play: None,
+            stop: None,
+            record: None,
+            loop_toggle: None,
+        }
+    }
+}
+
+/// Root of a MIDI mapping file.
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct MidiMapFile {
+    #[serde(default)]
+    pub tracks: TrackMap,
+    #[serde(default)]
+    pub transport: TransportMap,
+    #[serde(default)]
+    pub params: Vec<CcParamConfig>,
+    #[serde(default)]
+    pub notes: Vec<NoteTriggerConfig>,
+    /// Optional MIDI clock / MMC output.
+    #[serde(default)]
+    pub sync: SyncConfig,
+}
+
+impl MidiMapFile {
+    /// Parse and validate a mapping file.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let map: Self = toml::from_str(content).map_err(|e| e.to_string())?;
+        map.validate()?;
+        Ok(map)
+    }
+
+    /// Load `path`, falling back to the defaults when it is missing or invalid.
+    pub fn load(engine_name: &str, path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(content) => match Self::parse(&content) {
+                Ok(map) => {
+                    log::info!(
+                        "{}: loaded {} custom param(s), {} note trigger(s) from {} \
+                         (MIDI clock: {}, MMC: {})",
+                        engine_name,
+                        map.params.len(),
+                        map.notes.len(),
+                        path.display(),
+                        map.sync.midi_clock,
+                        map.sync.mmc,
+                    );
+                    map
+                }
+                Err(e) => {
+                    log::warn!("{}: failed to parse {}: {}", engine_name, path.display(), e);
+                    Self::default()
+                }
+            },
+            Err(_) => {
+                log::info!(
+                    "{}: no custom MIDI map at {} (optional — create to add controls)",
+                    engine_name,
+                    path.display()
+                );
+                Self::default()
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        fn check(what: &str, value: u8, max: u8) -> Result<(), String> {
+            if value > max {
+                Err(format!("{what} {value} out of range (0–{max})"))
+            } else {
+                Ok(())
+            }
+        }
+
+        let tracks = [
+            ("volume", &self.tracks.volume),
+            ("pan", &self.tracks.pan),
+            ("mute", &self.tracks.mute),
+            ("solo", &self.tracks.solo),
+        ];
+        for (name, ctl) in tracks {
+            check(&format!("tracks.{name} cc"), ctl.cc, 127)?;
+            if let Some(ch) = ctl.channel {
+                check(&format!("tracks.{name} channel"), ch, 15)?;
+            }
+        }
+
+        let actions = [
+            ("play", &self.transport.play),
+            ("stop", &self.transport.stop),
+            ("record", &self.transport.record),
+            ("loop", &self.transport.loop_toggle),
+        ];
+        for (name, action) in actions {
+            if let Some(a) = action {
+                check(&format!("transport.{name} channel"), a.channel, 15)?;
+                check(&format!("transport.{name} value"), a.value, 127)?;
+                match (a.cc, a.note) {
+                    (Some(n), _) | (None, Some(n)) => check(&format!("transport.{name}"), n, 127)?,
+                    (None, None) => {
+                        return Err(format!("transport.{name} needs either `cc` or `note`"))
+                    }
+                }
+            }
+        }
+
+        for p in &self.params {
+            check(&format!("param '{}' cc", p.id), p.cc, 127)?;
+            check(&format!("param '{}' channel", p.id), p.channel, 15)?;
+            if p.max <= p.min {
+                return Err(format!("param '{}': max must be greater than min", p.id));
+            }
+        }
+        for n in &self.notes {
+            check(&format!("note '{}' note", n.id), n.note, 127)?;
+            check(&format!("note '{}' channel", n.id), n.channel, 15)?;
+            check(&format!("note '{}' velocity", n.id), n.velocity, 127)?;
+        }
+
+        let mut ids = std::collections::HashSet::new();
+        for id in self.params.iter().map(|p| &p.id).chain(self.notes.iter().map(|n| &n.id)) {
+            if !ids.insert(id) {
+                return Err(format!("duplicate control id '{id}'"));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn default_zero() -> f32 { 0.0 }
+fn default_one() -> f32 { 1.0 }
+fn default_true() -> bool { true }
+fn default_stride() -> u8 { 1 }
+fn default_velocity() -> u8 { 100 }
+fn default_on_value() -> u8 { 127 }
+fn default_volume() -> TrackControl { TrackControl::per_channel(CC_VOLUME) }
+fn default_pan() -> TrackControl { TrackControl::per_channel(CC_PAN) }
+fn default_mute() -> TrackControl { TrackControl::per_channel(CC_MUTE) }
+fn default_solo() -> TrackControl { TrackControl::per_channel(CC_SOLO) }
+
+/// Mapping file path: `MAESTRO_MIDI_MAP`, or `file_name` in the config dir.
+pub fn map_path(file_name: &str) -> PathBuf {
+    if let Ok(p) = std::env::var("MAESTRO_MIDI_MAP") {
+        return PathBuf::from(p);
+    }
+    crate::config::config_dir().join(file_name)
+}
+
+// ── Default MIDI CC assignments ────────────────────────────────────────────
+
+const CC_VOLUME: u8 = 7;   // Standard MIDI volume (spec CC #7)
+const CC_PAN: u8 = 10;     // Standard MIDI balance/pan (spec CC #10)
+const CC_MUTE: u8 = 119;   // Custom — bind via MIDI learn (≥64 = muted)
+const CC_SOLO: u8 = 118;   // Custom — bind via MIDI learn (≥64 = soloed)
+
+/// Maximum MIDI channels == maximum tracks addressable by channel.
+const MAX_MIDI_CH: i32 = 16;
+
+// ── JACK process handler ───────────────────────────────────────────────────
+
+struct MidiProcess {
+    midi_out: Port<MidiOut>,
+    /// Outbound MIDI queue (CC, real-time and SysEx messages).  `try_lock` is
+    /// used in the real-time callback to avoid blocking; skips the cycle if
+    /// the lock is contended.
+    pending: Arc<Mutex<VecDeque<Vec<u8>>>>,
+    /// MIDI clock generator; `None` unless `[sync] midi_clock` is enabled.
+    clock: Option<ClockOutput>,
+}
+
+/// Real-time side of the MIDI clock output.
+struct ClockOutput {
+    generator: MidiClock,
+    state: Arc<ClockState>,
+    was_running: bool,
+    sample_rate: f64,
+    bpm_cell: &'static AtomicU32,
+}
+
+impl ProcessHandler for MidiProcess {
+    fn process(&mut self, _client: &Client, ps: &ProcessScope) -> Control {
+        let mut writer = self.midi_out.writer(ps);
+        // JACK requires events in non-decreasing time order, so clock pulses
+        // never go out before the queued start/continue/SPP messages.
+        let mut last_time = 0;
+        if let Ok(mut queue) = self.pending.try_lock() {
+            for (t, msg) in queue.drain(..).enumerate() {
+                last_time = t as u32;
+                let _ = writer.write(&RawMidi {
+                    time: last_time,
+                    bytes: &msg,
+                });
+            }
+        }
+
+        if let Some(clock) = self.clock.as_mut() {
+            let running = clock.state.is_running();
+            if running && !clock.was_running {
+                clock.generator.reset();
+            }
+            clock.was_running = running;
+
+            if running {
+                let bpm = f32::from_bits(clock.bpm_cell.load(Ordering::Relaxed)) as f64;
+                let (state, n_frames) = (&clock.state, ps.n_frames());
+                clock.generator.advance(n_frames, clock.sample_rate, bpm, |offset| {
+                    let _ = writer.write(&RawMidi {
+                        time: offset.max(last_time),
+                        bytes: &[midi_sync::CLOCK],
+                    });
+                    state.advance();
+                });
+            }
+        }
+        Control::Continue
+    }
+}
+
+// ── JACK timebase callback ─────────────────────────────────────────────────
+
+/// Called each process cycle while Maestro is JACK timebase master.
+/// Writes the current BPM into `pos` so other JACK clients see it.
+///
+/// # Safety
+/// `arg` must point to a live `AtomicU32` containing the desired BPM as bits.
+unsafe extern "C" fn timebase_callback(
+    _state: jack_sys::jack_transport_state_t,
+    _nframes: jack_sys::jack_nframes_t,
+    pos: *mut jack_sys::jack_position_t,
+    _new_pos: ::std::os::raw::c_int,
+    arg: *mut c_void,
+) {
+    let bpm_cell = &*(arg as *const AtomicU32);
+    let bpm = f32::from_bits(bpm_cell.load(Ordering::Relaxed)) as f64;
+
+    (*pos).valid = jack_sys::JackPositionBBT as jack_sys::jack_position_bits_t;
+    (*pos).beats_per_minute = bpm;
+    (*pos).beats_per_bar = 4.0;
+    (*pos).beat_type = 4.0;
+    (*pos).ticks_per_beat = 1920.0;
+}
+
+// ── Internal handle ────────────────────────────────────────────────────────
+
+struct MidiHandle {
+    /// Active JACK client; transport calls deref through to `jack::Client`.
+    client: jack::AsyncClient<(), MidiProcess>,
+    /// Shared outbound MIDI queue (same `Arc` as inside `MidiProcess`).
+    pending_midi: Arc<Mutex<VecDeque<Vec<u8>>>>,
+    /// Desired BPM, read atomically by the timebase callback.
+    /// Allocated with `Box::leak` for a `'static` lifetime.
+    bpm_cell: &'static AtomicU32,
+    /// Song position / running flag shared with the MIDI clock generator.
+    clock: Arc<ClockState>,
+    sample_rate: f64,
+}
+
+// ── Public adapter ─────────────────────────────────────────────────────────
+
+pub struct MidiEngine {
+    /// Display name, e.g. "MIDI" or "Zrythm".
+    name: &'static str,
+    handle: Mutex<Option<MidiHandle>>,
+    /// Mapping loaded from the engine's map file at startup.
+    map: MidiMapFile,
+    /// Last record state sent, so `toggle_record` can alternate between
+    /// on/off (MMC RECORD STROBE / RECORD EXIT).
+    recording: AtomicBool,
+}
+
+impl MidiEngine {
+    /// Generic MIDI engine reading `midi-map.toml`.
+    pub fn new() -> Self {
+        Self::with_map_file("MIDI", &map_path("midi-map.toml"))
+    }
+
+    /// Build an engine named `name` from the mapping file at `path`.
+    pub fn with_map_file(name: &'static str, path: &Path) -> Self {
+        Self::with_map(name, MidiMapFile::load(name, path))
+    }
+
+    pub fn with_map(name: &'static str, map: MidiMapFile) -> Self {
+        Self {
+            name,
+            handle: Mutex::new(None),
+            map,
+            recording: AtomicBool::new(false),
+        }
+    }
+
+    fn with_handle<F, T>(&self, f: F) -> Result<T, String>
+    where
+        F: FnOnce(&MidiHandle) -> T,
+    {
+        let guard = self.handle.lock().map_err(|e| e.to_string())?;
+        match guard.as_ref() {
+            Some(h) => Ok(f(h)),
+            None => Err(format!(
+                "{} JACK connection not established. \
+                 Ensure jackd/pipewire-jack is running.",
+                self.name
+            )),
+        }
+    }
+
+    fn queue_midi(&self, msg: [u8; 3]) -> Result<(), String> {
+        self.queue_raw(vec![msg.to_vec()])
+    }
+
+    /// Queue several messages atomically so they go out in the same cycle.
+    fn queue_raw(&self, msgs: Vec<Vec<u8>>) -> Result<(), String> {
+        self.with_handle(|h| h.pending_midi.lock().unwrap().extend(msgs))
+    }
+
+    fn queue_mmc(&self, cmd: MmcCommand) -> Result<(), String> {
+        if !self.map.sync.mmc {
+            return Ok(());
+        }
+        self.queue_raw(vec![midi_sync::mmc_command(self.map.sync.mmc_device_id, cmd)])
+    }
+
+    fn queue_action(&self, action: &Option<MidiAction>, on: bool) -> Result<(), String> {
+        match action {
+            Some(a) => self.queue_raw(a.messages(on)),
+            None => Ok(()),
+        }
+    }
+
+    fn send_track_cc(&self, ctl: &TrackControl, track_index: i32, value: u8) -> Result<(), String> {
+        let (ch, cc) = ctl.address(track_index)?;
+        self.queue_midi([0xB0 | ch, cc, value])
+    }
+}
+
+impl Default for MidiEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EngineAdapter for MidiEngine {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn reports_transport(&self) -> bool {
+        false
+    }
+
+    fn start(&self, app: AppHandle, _state_manager: StateManager) {
+        let name = self.name;
+        log::info!("{name}: connecting to JACK server...");
+
+        // ── Connect to JACK ────────────────────────────────────────────────
+        let (client, _status) =
+            match Client::new("maestro", ClientOptions::NO_START_SERVER) {
+                Ok(c) => c,
+                Err(e) => {
+                    log::error!(
+                        "{name}: failed to connect to JACK: {e}. \
+                         Ensure jackd or pipewire-jack is running."
+                    );
+                    let _ = app.emit("engine-connection-changed", false);
+                    return;
+                }
+            };
+
+        log::info!(
+            "{}: connected to JACK (sample rate: {} Hz, buffer: {} frames)",
+            name,
+            client.sample_rate(),
+            client.buffer_size(),
+        );
+
+        // ── Register JACK MIDI output port ─────────────────────────────────
+        let pending: Arc<Mutex<VecDeque<Vec<u8>>>> = Arc::new(Mutex::new(VecDeque::new()));
+
+        let midi_out = match client.register_port("control_out", MidiOut::default()) {
+            Ok(p) => p,
+            Err(e) => {
+                log::error!("{name}: failed to register JACK MIDI port: {e}");
+                let _ = app.emit("engine-connection-changed", false);
+                return;
+            }
+        };
+
+        // Leak an AtomicU32 so the timebase callback (which has no Drop path
+        // in raw JACK) and the clock generator can read the desired BPM
+        // safely for the process lifetime.
+        let bpm_cell: &'static AtomicU32 =
+            Box::leak(Box::new(AtomicU32::new(120.0_f32.to_bits())));
+
+        let sample_rate = client.sample_rate() as f64;
+        let clock_state = Arc::new(ClockState::default());
+        let clock = self.map.sync.midi_clock.then(|| ClockOutput {
+            generator: MidiClock::default(),
+            state: Arc::clone(&clock_state),
+            was_running: false,
+            sample_rate,
+            bpm_cell,
+        });
+
+        // ── Activate the JACK client ───────────────────────────────────────
+        let active = match client.activate_async((), MidiProcess {
+            midi_out,
+            pending: Arc::clone(&pending),
+            clock,
+        }) {
+            Ok(a) => a,
+            Err(e) => {
+                log::error!("{name}: failed to activate JACK client: {e}");
+                let _ = app.emit("engine-connection-changed", false);
+                return;
+            }
+        };
+
+        // ── Register as JACK timebase master ──────────────────────────────
+        // Safety:
+        //   • `active.as_client().raw()` is valid while `active` lives (stored in handle).
+        //   • `timebase_callback` has the correct `extern "C"` signature.
+        //   • `bpm_cell` is `'static` and will never be freed.
+        if self.map.transport.jack {
+            unsafe {
+                let rc = jack_sys::jack_set_timebase_callback(
+                    active.as_client().raw(),
+                    0, // force (not conditional)
+                    Some(timebase_callback),
+                    bpm_cell as *const AtomicU32 as *mut c_void,
+                );
+                if rc != 0 {
+                    log::warn!(
+                        "{name}: could not register as JACK timebase master (rc={rc}). \
+                         Tempo changes will not propagate automatically."
+                    );
+                } else {
+                    log::info!("{name}: registered as JACK timebase master.");
+                }
+            }
+        }
+
+        *self.handle.lock().unwrap() = Some(MidiHandle {
+            client: active,
+            pending_midi: pending,
+            bpm_cell,
+            clock: clock_state,
+            sample_rate,
+        });
+
+        log::info!(
+            "{name} engine ready. \
+             Connect 'maestro:control_out' to the target's MIDI input in your patchbay."
+        );
+        let _ = app.emit("engine-connection-changed", true);
+    }
+
+    // ── Transport ──────────────────────────────────────────────────────────
+
+    fn play(&self) -> Result<(), String> {
+        if self.map.transport.jack {
+            self.with_handle(|h| {
+                h.client.as_client().transport().start().map_err(|e| e.to_string())
+            })??;
+        }
+
+        if self.map.sync.midi_clock {
+            // Resume from the current song position: SPP + CONTINUE, or a
+            // plain START when sitting at the very beginning.
+            self.with_handle(|h| {
+                let mut queue = h.pending_midi.lock().unwrap();
+                if h.clock.ticks() == 0 {
+                    queue.push_back(vec![midi_sync::START]);
+                } else {
+                    let spp = midi_sync::song_position_pointer(h.clock.sixteenths());
+                    queue.push_back(spp.to_vec());
+                    queue.push_back(vec![midi_sync::CONTINUE]);
+                }
+                h.clock.set_running(true);
+            })?;
+        }
+        self.queue_mmc(MmcCommand::Play)?;
+        self.queue_action(&self.map.transport.play, true)
+    }
+
+    fn stop(&self) -> Result<(), String> {
+        if self.map.transport.jack {
+            self.with_handle(|h| {
+                h.client.as_client().transport().stop().map_err(|e| e.to_string())
+            })??;
+        }
+
+        if self.map.sync.midi_clock {
+            self.with_handle(|h| {
+                h.clock.set_running(false);
+                h.pending_midi.lock().unwrap().push_back(vec![midi_sync::STOP]);
+            })?;
+        }
+        self.queue_mmc(MmcCommand::Stop)?;
+        self.queue_action(&self.map.transport.stop, true)
+    }
+
+    fn toggle_record(&self) -> Result<(), String> {
+        // JACK transport has no standardised record-arm message; use MMC
+        // and/or the mapped `[transport] record` message instead.
+        if !self.map.sync.mmc && self.map.transport.record.is_none() {
+            return Err(
+                "Record toggle is not standardised in JACK transport. \
+                 Enable `[sync] mmc` or map `[transport] record` to a MIDI-learned control."
+                    .into(),
+            );
+        }
+        let was_recording = self.recording.fetch_xor(true, Ordering::Relaxed);
+        self.queue_mmc(if was_recording {
+            MmcCommand::RecordExit
+        } else {
+            MmcCommand::RecordStrobe
+        })?;
+        self.queue_action(&self.map.transport.record, !was_recording)
+    }
+
+    fn set_tempo(&self, bpm: f32) -> Result<(), String> {
+        if !(20.0..=999.0).contains(&bpm) {
+            return Err(format!("BPM {bpm} out of valid range (20–999)"));
+        }
+        self.with_handle(|h| h.bpm_cell.store(bpm.to_bits(), Ordering::Relaxed))?;
+        log::debug!("{}: desired tempo → {bpm} BPM (pushed via JACK timebase)", self.name);
+        Ok(())
+    }
+
+    fn set_loop_enabled(&self, enabled: bool) -> Result<(), String> {
+        // JACK transport has no standardised loop-enable message; only a
+        // mapped `[transport] loop` control can change it.
+        if self.map.transport.loop_toggle.is_none() {
+            log::debug!(
+                "{}: set_loop_enabled({enabled}) — no JACK equivalent, ignored",
+                self.name
+            );
+            return Ok(());
+        }
+        self.queue_action(&self.map.transport.loop_toggle, enabled)
+    }
+
+    fn set_song_time(&self, beats: f64) -> Result<(), String> {
+        if beats < 0.0 {
+            return Err(format!("Song position {beats} must not be negative"));
+        }
+        let (bpm, sample_rate) = self.with_handle(|h| {
+            (f32::from_bits(h.bpm_cell.load(Ordering::Relaxed)) as f64, h.sample_rate)
+        })?;
+        let seconds = beats * 60.0 / bpm;
+
+        if self.map.transport.jack {
+            self.with_handle(|h| {
+                h.client
+                    .as_client()
+                    .transport()
+                    .locate((seconds * sample_rate).round() as u32)
+                    .map_err(|e| e.to_string())
+            })??;
+        }
+
+        let mut msgs = vec![];
+        if self.map.sync.midi_clock {
+            self.with_handle(|h| {
+                h.clock.locate(beats);
+                msgs.push(midi_sync::song_position_pointer(h.clock.sixteenths()).to_vec());
+            })?;
+        }
+        if self.map.sync.mmc {
+            msgs.push(midi_sync::mmc_locate(self.map.sync.mmc_device_id, seconds));
+        }
+        self.queue_raw(msgs)
+    }
+
+    // ── Tracks ─────────────────────────────────────────────────────────────
+
+    fn set_track_volume(&self, track_index: i32, volume: f32) -> Result<(), String> {
+        let value = (volume.clamp(0.0, 1.0) * 127.0).round() as u8;
+        self.send_track_cc(&self.map.tracks.volume, track_index, value)
+    }
+
+    fn set_track_mute(&self, track_index: i32, mute: bool) -> Result<(), String> {
+        self.send_track_cc(&self.map.tracks.mute, track_index, if mute { 127 } else { 0 })
+    }
+
+    fn set_track_solo(&self, track_index: i32, solo: bool) -> Result<(), String> {
+        self.send_track_cc(&self.map.tracks.solo, track_index, if solo { 127 } else { 0 })
+    }
+
+    fn set_track_pan(&self, track_index: i32, pan: f32) -> Result<(), String> {
+        // Map −1.0..1.0 → 0..127  (centre = 64)
+        let value = ((pan.clamp(-1.0, 1.0) + 1.0) / 2.0 * 127.0).round() as u8;
+        self.send_track_cc(&self.map.tracks.pan, track_index, value)
+    }
+
+    // ── Media ───────────────────────────────────────────────────────────────
+
+    fn load_sample(
+        &self,
+        _track_index: i32,
+        _scene_index: i32,
+        _file_path: &str,
+    ) -> Result<(), String> {
+        Err(format!(
+            "Sample loading is not supported via JACK MIDI. \
+             Drag audio files directly into {}.",
+            self.name
+        ))
+    }
+
+    // ── Custom params ───────────────────────────────────────────────────────
+
+    fn custom_params(&self) -> Vec<ParamDef> {
+        let ccs = self.map.params.iter().map(|p| ParamDef {
+            id: p.id.clone(),
+            label: p.label.clone(),
+            min: p.min,
+            max: p.max,
+            value: None,
+        });
+        let notes = self.map.notes.iter().map(|n| ParamDef {
+            id: n.id.clone(),
+            label: n.label.clone(),
+            min: 0.0,
+            max: 1.0,
+            value: None,
+        });
+        ccs.chain(notes).collect()
+    }
+
+    fn set_custom_param(&self, id: &str, value: f32) -> Result<(), String> {
+        if let Some(param) = self.map.params.iter().find(|p| p.id == id) {
+            // Map value from [min, max] → MIDI [0, 127].
+            let norm = (value - param.min) / (param.max - param.min);
+            let cc_val = (norm.clamp(0.0, 1.0) * 127.0).round() as u8;
+            return self.queue_midi([0xB0 | param.channel, param.cc, cc_val]);
+        }
+        if let Some(trigger) = self.map.notes.iter().find(|n| n.id == id) {
+            // Values ≥ 0.5 press the note, lower values release it.
+            let ch = trigger.channel;
+            return if value >= 0.5 {
+                self.queue_midi([0x90 | ch, trigger.note, trigger.velocity.max(1)])
+            } else {
+                self.queue_midi([0x80 | ch, trigger.note, 0])
+            };
+        }
+        Err(format!("Unknown custom param id: '{id}'"))
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+//
+// These cover mapping-file parsing/validation and the value → MIDI math.
+// They do not require JACK to be running.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ── TOML parsing ────────────────────────────────────────────────────────
+
+    #[test]
+    fn parse_valid_params() {
+        let raw = r#"
+[[params]]
+id      = "reverb"
+label   = "Reverb Wet"
+cc      = 20
+channel = 0
+min     = 0.0
+max     = 1.0
+
+[[params]]
+id      = "gain"
+label   = "Input Gain"
+cc      = 21
+channel = 2
+"#;
+        let cfg = MidiMapFile::parse(raw).expect("parse failed");
+        assert_eq!(cfg.params.len(), 2);
+
+        let r = &cfg.params[0];
+        assert_eq!(r.id, "reverb");
+        assert_eq!(r.label, "Reverb Wet");
+        assert_eq!(r.cc, 20);
+        assert_eq!(r.channel, 0);
+        assert_eq!(r.min, 0.0);
+        assert_eq!(r.max, 1.0);
+
+        // gain uses defaults for min/max
+        let g = &cfg.params[1];
+        assert_eq!(g.min, 0.0); // default_zero
+        assert_eq!(g.max, 1.0); // default_one
+    }
+
+    #[test]
+    fn parse_empty_file_uses_defaults() {
+        let cfg = MidiMapFile::parse("").expect("empty file should parse");
+        assert!(cfg.params.is_empty());
+        assert!(cfg.notes.is_empty());
+        assert!(cfg.transport.jack);
+        assert_eq!(cfg.tracks.volume.cc, 7);
+        assert_eq!(cfg.tracks.pan.cc, 10);
+        assert_eq!(cfg.tracks.mute.cc, 119);
+        assert_eq!(cfg.tracks.solo.cc, 118);
+    }
+
+    #[test]
+    fn parse_file_without_params_table() {
+        let cfg = MidiMapFile::parse("[other]\nkey = 1").expect("file without params should parse");
+        assert!(cfg.params.is_empty());
+    }
+
+    #[test]
+    fn parse_sync_table() {
+        let raw = r#"
+[sync]
+midi_clock = true
+mmc        = true
+"#;
+        let cfg = MidiMapFile::parse(raw).expect("parse failed");
+        assert!(cfg.sync.midi_clock);
+        assert!(cfg.sync.mmc);
+        assert_eq!(cfg.sync.mmc_device_id, 0x7F);
+
+        let cfg = MidiMapFile::parse("").unwrap();
+        assert!(!cfg.sync.midi_clock && !cfg.sync.mmc);
+    }
+
+    #[test]
+    fn parse_invalid_toml_errors() {
+        assert!(MidiMapFile::parse("[[params]\n").is_err());
+    }
+
+    #[test]
+    fn parse_full_mapping() {
+        let raw = r#"
+[tracks]
+volume = { cc = 0, channel = 0 }
+mute   = { cc = 64, channel = 1, cc_stride = 2 }
+
+[transport]
+jack   = false
+play   = { channel = 15, cc = 115 }
+record = { channel = 15, note = 95, value = 90 }
+loop   = { channel = 15, cc = 114 }
+
+[[notes]]
+id      = "kick"
+label   = "Kick"
+note    = 36
+channel = 9
+"#;
+        let cfg = MidiMapFile::parse(raw).expect("parse failed");
+        assert!(!cfg.transport.jack);
+        assert_eq!(cfg.tracks.volume.channel, Some(0));
+        assert_eq!(cfg.tracks.pan.cc, 10); // unspecified → default
+        assert_eq!(cfg.notes[0].velocity, 100);
+        assert!(cfg.transport.stop.is_none());
+        assert!(cfg.transport.loop_toggle.is_some());
+    }
+
+    #[test]
+    fn validation_rejects_bad_mappings() {
+        assert!(MidiMapFile::parse("[tracks]\nvolume = { cc = 200 }").is_err());
+        assert!(MidiMapFile::parse("[transport]\nplay = { channel = 16, cc = 1 }").is_err());
+        assert!(MidiMapFile::parse("[transport]\nplay = { channel = 0 }").is_err());
+        let dup = r#"
+[[params]]
+id = "x"
+label = "X"
+cc = 1
+channel = 0
+[[notes]]
+id = "x"
+label = "X"
+note = 1
+channel = 0
+"#;
+        assert!(MidiMapFile::parse(dup).is_err());
+        let inverted = r#"
+[[params]]
+id = "x"
+label = "X"
+cc = 1
+channel = 0
+min = 1.0
+max = 0.0
+"#;
+        assert!(MidiMapFile::parse(inverted).is_err());
+    }
+
+    // ── Track addressing ────────────────────────────────────────────────────
+
+    #[test]
+    fn track_address_per_channel() {
+        let ctl = TrackControl::per_channel(7);
+        assert_eq!(ctl.address(0).unwrap(), (0, 7));
+        assert_eq!(ctl.address(15).unwrap(), (15, 7));
+        assert!(ctl.address(16).is_err());
+        assert!(ctl.address(-1).is_err());
+    }
+
+    #[test]
+    fn track_address_fixed_channel_with_stride() {
+        let ctl = TrackControl {
+            cc: 64,
+            channel: Some(1),
+            cc_stride: 2,
+        };
+        assert_eq!(ctl.address(0).unwrap(), (1, 64));
+        assert_eq!(ctl.address(3).unwrap(), (1, 70));
+        assert!(ctl.address(32).is_err()); // 64 + 64 > 127
+        assert!(ctl.address(i32::MAX).is_err());
+        assert!(ctl.address(-1).is_err());
+    }
+
+    // ── Transport actions ───────────────────────────────────────────────────
+
+    #[test]
+    fn action_messages() {
+        let cc = MidiAction {
+            channel: 2,
+            cc: Some(114),
+            note: None,
+            value: 127,
+        };
+        assert_eq!(cc.messages(true), vec![vec![0xB2, 114, 127]]);
+        assert_eq!(cc.messages(false), vec![vec![0xB2, 114, 0]]);
+
+        let note = MidiAction {
+            channel: 9,
+            cc: None,
+            note: Some(36),
+            value: 100,
+        };
+        assert_eq!(
+            note.messages(true),
+            vec![vec![0x99, 36, 100], vec![0x89, 36, 0]]
+        );
+    }
+
+    // ── MIDI value mapping math ─────────────────────────────────────────────
+    //
+    // The formula in set_custom_param is:
+    //   norm = (value - min) / (max - min)
+    //   cc   = round(clamp(norm, 0, 1) * 127)
+
+    fn midi_cc(min: f32, max: f32, value: f32) -> u8 {
+        let norm = (value - min) / (max - min);
+        (norm.clamp(0.0, 1.0) * 127.0).round() as u8
+    }
+
+    #[test]
+    fn mapping_unit_range() {
+        assert_eq!(midi_cc(0.0, 1.0, 0.0), 0);
+        assert_eq!(midi_cc(0.0, 1.0, 0.5), 64);
+        assert_eq!(midi_cc(0.0, 1.0, 1.0), 127);
+    }
+
+    #[test]
+    fn mapping_clamps_out_of_range() {
+        assert_eq!(midi_cc(0.0, 1.0, -1.0), 0);   // below min
+        assert_eq!(midi_cc(0.0, 1.0, 2.0), 127);   // above max
+    }
+
+    #[test]
+    fn mapping_negative_db_range() {
+        // -20..0 dB: midpoint (-10 dB) → 64
+        assert_eq!(midi_cc(-20.0, 0.0, -20.0), 0);
+        assert_eq!(midi_cc(-20.0, 0.0, -10.0), 64);
+        assert_eq!(midi_cc(-20.0, 0.0, 0.0), 127);
+    }
+}
@@ -0,0 +1,291 @@
+//! MIDI clock and MIDI Machine Control (MMC) message generation.
+//!
+//! Used by JACK-based adapters to drive hardware synths and drum machines
+//! that follow MIDI clock (24 ppqn) rather than JACK transport.
+//!
+//! Everything in this module is plain data and arithmetic so it can run
+//! inside the JACK real-time callback and be unit-tested without a server.
+//!
+//! References:
+//! * MIDI 1.0 Detailed Specification — System Real-Time / Song Position Pointer
+//! * MIDI Machine Control 1.0 (MMA RP-013)
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+// ── System real-time / common bytes ────────────────────────────────────────
+
+pub const CLOCK: u8 = 0xF8;
+pub const START: u8 = 0xFA;
+pub const CONTINUE: u8 = 0xFB;
+pub const STOP: u8 = 0xFC;
+pub const SONG_POSITION: u8 = 0xF2;
+
+/// MIDI clock resolution: pulses per quarter note.
+pub const PPQN: u64 = 24;
+
+/// Clock pulses per Song Position Pointer unit (one MIDI beat = a 16th note).
+const TICKS_PER_SIXTEENTH: u64 = PPQN / 4;
+
+/// MMC "all devices" broadcast id.
+const MMC_BROADCAST: u8 = 0x7F;
+
+// ── Config ─────────────────────────────────────────────────────────────────
+
+/// The `[sync]` table of an engine's MIDI map file.
+///
+/// ```toml
+/// [sync]
+/// midi_clock    = true   # 24 ppqn clock + start/stop/continue + SPP
+/// mmc           = true   # MMC play/stop/record/locate SysEx
+/// mmc_device_id = 127    # optional — 127 addresses all devices
+/// ```
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct SyncConfig {
+    #[serde(default)]
+    pub midi_clock: bool,
+    #[serde(default)]
+    pub mmc: bool,
+    #[serde(default = "default_mmc_device_id")]
+    pub mmc_device_id: u8,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            midi_clock: false,
+            mmc: false,
+            mmc_device_id: MMC_BROADCAST,
+        }
+    }
+}
+
+fn default_mmc_device_id() -> u8 {
+    MMC_BROADCAST
+}
+
+// ── MIDI clock ─────────────────────────────────────────────────────────────
+
+/// Transport state shared between the adapter (command thread) and the
+/// JACK process callback that emits clock pulses.
+#[derive(Default)]
+pub struct ClockState {
+    running: AtomicBool,
+    /// Clock pulses emitted since song position zero.
+    ticks: AtomicU64,
+}
+
+impl ClockState {
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+
+    pub fn set_running(&self, running: bool) {
+        self.running.store(running, Ordering::Relaxed);
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.ticks.load(Ordering::Relaxed)
+    }
+
+    pub fn advance(&self) {
+        self.ticks.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Move the song position to `beats` quarter notes from the start.
+    pub fn locate(&self, beats: f64) {
+        let ticks = (beats.max(0.0) * PPQN as f64).round() as u64;
+        self.ticks.store(ticks, Ordering::Relaxed);
+    }
+
+    /// Current song position in MIDI beats (16th notes), as sent in SPP.
+    pub fn sixteenths(&self) -> u16 {
+        (self.ticks() / TICKS_PER_SIXTEENTH).min(0x3FFF) as u16
+    }
+}
+
+/// Schedules 24 ppqn clock pulses across JACK process cycles.
+///
+/// Keeps the fractional frame phase between cycles so the pulse rate stays
+/// exact at any tempo/sample-rate combination.
+#[derive(Default)]
+pub struct MidiClock {
+    /// Frames remaining (from the start of the next cycle) until the next pulse.
+    phase: f64,
+}
+
+impl MidiClock {
+    pub fn frames_per_tick(sample_rate: f64, bpm: f64) -> f64 {
+        sample_rate * 60.0 / (bpm * PPQN as f64)
+    }
+
+    /// Restart the pulse grid so the next pulse lands on frame 0.
+    pub fn reset(&mut self) {
+        self.phase = 0.0;
+    }
+
+    /// Advance by one process cycle, calling `on_tick` with the frame offset
+    /// of every pulse that falls inside it.
+    pub fn advance(
+        &mut self,
+        nframes: u32,
+        sample_rate: f64,
+        bpm: f64,
+        mut on_tick: impl FnMut(u32),
+    ) {
+        if bpm <= 0.0 || sample_rate <= 0.0 {
+            return;
+        }
+        let step = Self::frames_per_tick(sample_rate, bpm);
+        let mut pos = self.phase;
+        while pos < nframes as f64 {
+            on_tick(pos as u32);
+            pos += step;
+        }
+        self.phase = pos - nframes as f64;
+    }
+}
+
+/// Song Position Pointer message for a position in 16th notes (14-bit).
+pub fn song_position_pointer(sixteenths: u16) -> [u8; 3] {
+    let pos = sixteenths.min(0x3FFF);
+    [SONG_POSITION, (pos & 0x7F) as u8, (pos >> 7) as u8]
+}
+
+// ── MIDI Machine Control ───────────────────────────────────────────────────
+
+/// MMC transport commands (single-byte command field).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MmcCommand {
+    Stop = 0x01,
+    Play = 0x02,
+    RecordStrobe = 0x06,
+    RecordExit = 0x07,
+}
+
+/// `F0 7F <device> 06 <command> F7`
+pub fn mmc_command(device_id: u8, cmd: MmcCommand) -> Vec<u8> {
+    vec![0xF0, 0x7F, device_id & 0x7F, 0x06, cmd as u8, 0xF7]
+}
+
+/// MMC LOCATE to an absolute time, encoded as 30 fps non-drop SMPTE.
+///
+/// `F0 7F <device> 06 44 06 01 hr mn sc fr ff F7`
+pub fn mmc_locate(device_id: u8, seconds: f64) -> Vec<u8> {
+    const FPS: f64 = 30.0;
+    const RATE_30_NON_DROP: u8 = 0b11 << 5;
+
+    let total_frames = seconds.max(0.0) * FPS;
+    let whole = total_frames.floor() as u64;
+    let subframes = ((total_frames - whole as f64) * 100.0).floor() as u8;
+
+    let frames = (whole % FPS as u64) as u8;
+    let secs = ((whole / FPS as u64) % 60) as u8;
+    let mins = ((whole / (FPS as u64 * 60)) % 60) as u8;
+    let hours = ((whole / (FPS as u64 * 3600)) % 24) as u8;
+
+    vec![
+        0xF0,
+        0x7F,
+        device_id & 0x7F,
+        0x06,
+        0x44,
+        0x06,
+        0x01,
+        RATE_30_NON_DROP | hours,
+        mins,
+        secs,
+        frames,
+        subframes,
+        0xF7,
+    ]
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_ticks(clock: &mut MidiClock, cycles: u32, nframes: u32, sr: f64, bpm: f64) -> usize {
+        let mut n = 0;
+        for _ in 0..cycles {
+            clock.advance(nframes, sr, bpm, |_| n += 1);
+        }
+        n
+    }
+
+    #[test]
+    fn clock_rate_matches_tempo() {
+        // 120 BPM @ 48 kHz → 1000 frames per pulse → 48 pulses per second.
+        let mut clock = MidiClock::default();
+        assert_eq!(
+            count_ticks(&mut clock, 48000 / 256, 256, 48000.0, 120.0),
+            48
+        );
+        let mut clock = MidiClock::default();
+        assert_eq!(count_ticks(&mut clock, 1000, 48, 48000.0, 120.0), 48);
+    }
+
+    #[test]
+    fn clock_keeps_fractional_phase_across_cycles() {
+        // 44.1 kHz / 125 BPM is not an integer number of frames per pulse.
+        let mut clock = MidiClock::default();
+        let mut offsets = vec![];
+        for cycle in 0..100u32 {
+            clock.advance(64, 44100.0, 125.0, |o| offsets.push(cycle * 64 + o));
+        }
+        let step = MidiClock::frames_per_tick(44100.0, 125.0);
+        for (i, frame) in offsets.iter().enumerate() {
+            assert_eq!(*frame, (i as f64 * step) as u32);
+        }
+    }
+
+    #[test]
+    fn clock_ignores_invalid_tempo() {
+        let mut clock = MidiClock::default();
+        assert_eq!(count_ticks(&mut clock, 4, 256, 48000.0, 0.0), 0);
+    }
+
+    #[test]
+    fn spp_encodes_14_bit_lsb_first() {
+        assert_eq!(song_position_pointer(0), [0xF2, 0, 0]);
+        assert_eq!(song_position_pointer(130), [0xF2, 2, 1]);
+        assert_eq!(song_position_pointer(u16::MAX), [0xF2, 0x7F, 0x7F]);
+    }
+
+    #[test]
+    fn clock_state_locate_rounds_to_sixteenths() {
+        let state = ClockState::default();
+        state.locate(4.0);
+        assert_eq!(state.ticks(), 96);
+        assert_eq!(state.sixteenths(), 16);
+        state.advance();
+        assert_eq!(state.sixteenths(), 16);
+    }
+
+    #[test]
+    fn mmc_transport_sysex() {
+        assert_eq!(
+            mmc_command(0x7F, MmcCommand::Play),
+            vec![0xF0, 0x7F, 0x7F, 0x06, 0x02, 0xF7]
+        );
+        assert_eq!(mmc_command(0x10, MmcCommand::Stop)[2], 0x10);
+    }
+
+    #[test]
+    fn mmc_locate_encodes_smpte() {
+        // 1 h 2 min 3 s + 15.5 frames
+        let secs = 3600.0 + 120.0 + 3.0 + 15.5 / 30.0;
+        let msg = mmc_locate(0x7F, secs);
+        assert_eq!(&msg[..7], &[0xF0, 0x7F, 0x7F, 0x06, 0x44, 0x06, 0x01]);
+        assert_eq!(&msg[7..], &[0x60 | 1, 2, 3, 15, 50, 0xF7]);
+    }
+
+    #[test]
+    fn sync_config_defaults() {
+        let cfg: SyncConfig = toml::from_str("").unwrap();
+        assert!(!cfg.midi_clock);
+        assert!(!cfg.mmc);
+        assert_eq!(cfg.mmc_device_id, 0x7F);
+    }
+}
@@ -1,6 +1,6 @@
 use tauri::{AppHandle, Emitter};
 
-use super::{EngineAdapter, StateManager};
+use super::{EngineAdapter, Note, NoteRegion, StateManager, TrackKind};
 
 /// No-op engine adapter for offline use and automated testing.
 ///
@@ -13,6 +13,10 @@ impl EngineAdapter for MockEngine {
         "Mock"
     }
 
+    fn reports_transport(&self) -> bool {
+        false
+    }
+
     fn start(&self, app: AppHandle, _state_manager: StateManager) {
         log::info!("Mock engine started — all commands are no-ops");
         let _ = app.emit("engine-connection-changed", true);
@@ -43,6 +47,11 @@ impl EngineAdapter for MockEngine {
         Ok(())
     }
 
+    fn set_song_time(&self, beats: f64) -> Result<(), String> {
+        log::debug!("Mock: set_song_time({})", beats);
+        Ok(())
+    }
+
     fn set_track_volume(&self, track_index: i32, volume: f32) -> Result<(), String> {
         log::debug!("Mock: set_track_volume({}, {})", track_index, volume);
         Ok(())
@@ -63,6 +72,131 @@ impl EngineAdapter for MockEngine {
         Ok(())
     }
 
+    fn set_track_send(&self, track_index: i32, send_index: i32, value: f32) -> Result<(), String> {
+        log::debug!("Mock: set_track_send({}, {}, {})", track_index, send_index, value);
+        Ok(())
+    }
+
+    fn set_return_track_volume(&self, return_index: i32, volume: f32) -> Result<(), String> {
+        log::debug!("Mock: set_return_track_volume({}, {})", return_index, volume);
+        Ok(())
+    }
+
+    fn set_return_track_pan(&self, return_index: i32, pan: f32) -> Result<(), String> {
+        log::debug!("Mock: set_return_track_pan({}, {})", return_index, pan);
+        Ok(())
+    }
+
+    fn set_master_volume(&self, volume: f32) -> Result<(), String> {
+        log::debug!("Mock: set_master_volume({})", volume);
+        Ok(())
+    }
+
+    fn set_master_pan(&self, pan: f32) -> Result<(), String> {
+        log::debug!("Mock: set_master_pan({})", pan);
+        Ok(())
+    }
+
+    fn create_track(&self, kind: TrackKind, track_index: i32) -> Result<(), String> {
+        log::debug!("Mock: create_track({:?}, {})", kind, track_index);
+        Ok(())
+    }
+
+    fn delete_track(&self, track_index: i32) -> Result<(), String> {
+        log::debug!("Mock: delete_track({})", track_index);
+        Ok(())
+    }
+
+    fn duplicate_track(&self, track_index: i32) -> Result<(), String> {
+        log::debug!("Mock: duplicate_track({})", track_index);
+        Ok(())
+    }
+
+    fn rename_track(&self, track_index: i32, name: &str) -> Result<(), String> {
+        log::debug!("Mock: rename_track({}, {})", track_index, name);
+        Ok(())
+    }
+
+    fn set_track_color(&self, track_index: i32, color: u32) -> Result<(), String> {
+        log::debug!("Mock: set_track_color({}, {:06x})", track_index, color);
+        Ok(())
+    }
+
+    fn set_track_arm(&self, track_index: i32, arm: bool) -> Result<(), String> {
+        log::debug!("Mock: set_track_arm({}, {})", track_index, arm);
+        Ok(())
+    }
+
+    fn fire_clip(&self, track_index: i32, scene_index: i32) -> Result<(), String> {
+        log::debug!("Mock: fire_clip({}, {})", track_index, scene_index);
+        Ok(())
+    }
+
+    fn stop_clip(&self, track_index: i32, scene_index: i32) -> Result<(), String> {
+        log::debug!("Mock: stop_clip({}, {})", track_index, scene_index);
+        Ok(())
+    }
+
+    fn create_clip(&self, track_index: i32, scene_index: i32, length: f64) -> Result<(), String> {
+        log::debug!("Mock: create_clip({}, {}, {})", track_index, scene_index, length);
+        Ok(())
+    }
+
+    fn get_clip_notes(&self, track_index: i32, scene_index: i32) -> Result<Vec<Note>, String> {
+        log::debug!("Mock: get_clip_notes({}, {})", track_index, scene_index);
+        Ok(vec![])
+    }
+
+    fn add_notes(&self, track_index: i32, scene_index: i32, notes: &[Note]) -> Result<(), String> {
+        log::debug!("Mock: add_notes({}, {}, {} notes)", track_index, scene_index, notes.len());
+        Ok(())
+    }
+
+    fn remove_notes(
+        &self,
+        track_index: i32,
+        scene_index: i32,
+        region: Option<NoteRegion>,
+    ) -> Result<(), String> {
+        log::debug!("Mock: remove_notes({}, {}, {:?})", track_index, scene_index, region);
+        Ok(())
+    }
+
+    fn fire_scene(&self, scene_index: i32) -> Result<(), String> {
+        log::debug!("Mock: fire_scene({})", scene_index);
+        Ok(())
+    }
+
+    fn stop_scene(&self, scene_index: i32) -> Result<(), String> {
+        log::debug!("Mock: stop_scene({})", scene_index);
+        Ok(())
+    }
+
+    fn create_scene(&self, scene_index: i32) -> Result<(), String> {
+        log::debug!("Mock: create_scene({})", scene_index);
+        Ok(())
+    }
+
+    fn duplicate_scene(&self, scene_index: i32) -> Result<(), String> {
+        log::debug!("Mock: duplicate_scene({})", scene_index);
+        Ok(())
+    }
+
+    fn delete_scene(&self, scene_index: i32) -> Result<(), String> {
+        log::debug!("Mock: delete_scene({})", scene_index);
+        Ok(())
+    }
+
+    fn undo(&self) -> Result<(), String> {
+        log::debug!("Mock: undo");
+        Ok(())
+    }
+
+    fn redo(&self) -> Result<(), String> {
+        log::debug!("Mock: redo");
+        Ok(())
+    }
+
     fn load_sample(
         &self,
         track_index: i32,
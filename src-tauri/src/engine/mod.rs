@@ -1,31 +1,64 @@
+pub mod ardour;
+pub mod bitwig;
+pub mod events;
+pub mod midi;
+pub mod midi_sync;
 pub mod mock;
+pub mod notes;
+pub mod reaper;
+pub mod smf;
 pub mod state;
 pub mod zrythm;
 
-pub use state::{ClipState, EngineState, StateManager, TrackState};
+pub use events::StateChange;
+pub use notes::NoteRegion;
+pub use state::{
+    ClipState, EngineState, Note, SceneState, StateManager, TrackState, VersionedState,
+};
 
 /// A user-defined engine parameter exposed to the frontend as a labeled slider.
 ///
-/// Defined in `~/.config/maestro/zrythm-map.toml` for the Zrythm engine.
-/// Other engines may return an empty list from `custom_params`.
+/// Defined in the MIDI map file (`midi-map.toml` / `zrythm-map.toml`) for the
+/// JACK MIDI engines; discovered from the DAW's devices for Ableton, Ardour and
+/// Bitwig.  Other engines may return an empty list from `custom_params`.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ParamDef {
     pub id: String,
     pub label: String,
     pub min: f32,
     pub max: f32,
+    /// Current value, when the engine reports one (MIDI CCs are send-only).
+    #[serde(default)]
+    pub value: Option<f32>,
 }
 
 use tauri::AppHandle;
 
+/// Kind of track to create with [`EngineAdapter::create_track`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackKind {
+    Audio,
+    Midi,
+    Return,
+}
+
 /// Which audio engine backend to connect to.
 /// Controlled via the `MAESTRO_ENGINE` environment variable.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum EngineKind {
     /// Ableton Live via the AbletonOSC control surface (default)
     AbletonOsc,
-    /// Zrythm via its OSC/scripting interface (stub — not yet implemented)
+    /// Zrythm via JACK transport + MIDI CC (`zrythm-map.toml`)
     Zrythm,
+    /// Any DAW or hardware mixer via JACK transport + a configurable MIDI map
+    Midi,
+    /// Reaper via its native OSC control surface
+    Reaper,
+    /// Ardour via its native OSC control surface
+    Ardour,
+    /// Bitwig Studio via the DrivenByMoss OSC extension
+    Bitwig,
     /// No-op adapter for offline use and testing
     Mock,
 }
@@ -34,6 +67,10 @@ impl EngineKind {
     pub fn from_str(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "zrythm" => EngineKind::Zrythm,
+            "midi" => EngineKind::Midi,
+            "reaper" => EngineKind::Reaper,
+            "ardour" => EngineKind::Ardour,
+            "bitwig" => EngineKind::Bitwig,
             "mock" => EngineKind::Mock,
             _ => EngineKind::AbletonOsc,
         }
@@ -49,7 +86,8 @@ impl EngineKind {
 /// Abstraction over different audio engine backends.
 ///
 /// Each implementation translates high-level commands into engine-specific
-/// protocol calls (OSC for AbletonOSC, Guile scripting for Zrythm, no-op for Mock).
+/// protocol calls (OSC for AbletonOSC/Reaper/Ardour/Bitwig, JACK MIDI for Zrythm/MIDI,
+/// no-op for Mock).
 ///
 /// Implementations must be `Send + Sync` so they can be shared across Tauri's
 /// async command handlers as managed state.
@@ -74,6 +112,19 @@ pub trait EngineAdapter: Send + Sync {
     /// Set the loop region enabled/disabled.
     fn set_loop_enabled(&self, enabled: bool) -> Result<(), String>;
 
+    /// Move the playhead to `beats` quarter notes from the song start.
+    ///
+    /// Defaults to an error for engines without a locate command.
+    fn set_song_time(&self, _beats: f64) -> Result<(), String> {
+        Err(format!("{} does not support setting the song position", self.name()))
+    }
+
+    /// Whether the engine reports play state and song time back through
+    /// `StateManager`, which automation recording relies on.
+    fn reports_transport(&self) -> bool {
+        true
+    }
+
     // ── Tracks ──────────────────────────────────────────────────────────────
 
     fn set_track_volume(&self, track_index: i32, volume: f32) -> Result<(), String>;
@@ -81,6 +132,165 @@ pub trait EngineAdapter: Send + Sync {
     fn set_track_solo(&self, track_index: i32, solo: bool) -> Result<(), String>;
     fn set_track_pan(&self, track_index: i32, pan: f32) -> Result<(), String>;
 
+    // ── Sends, returns and master ───────────────────────────────────────────
+
+    /// Set send `send_index` (feeding return track `send_index`) of a track, 0–1.
+    fn set_track_send(
+        &self,
+        _track_index: i32,
+        _send_index: i32,
+        _value: f32,
+    ) -> Result<(), String> {
+        Err(format!("{} does not support sends", self.name()))
+    }
+
+    fn set_return_track_volume(&self, _return_index: i32, _volume: f32) -> Result<(), String> {
+        Err(format!("{} does not expose return tracks", self.name()))
+    }
+
+    fn set_return_track_pan(&self, _return_index: i32, _pan: f32) -> Result<(), String> {
+        Err(format!("{} does not expose return tracks", self.name()))
+    }
+
+    fn set_master_volume(&self, _volume: f32) -> Result<(), String> {
+        Err(format!("{} does not expose the master track", self.name()))
+    }
+
+    fn set_master_pan(&self, _pan: f32) -> Result<(), String> {
+        Err(format!("{} does not expose the master track", self.name()))
+    }
+
+    // ── Track structure ─────────────────────────────────────────────────────
+    //
+    // Engines keep `StateManager` track indices consistent after inserts and
+    // deletes.  All default to an error for engines that can't edit tracks.
+    // There is no reorder: Live's Object Model, and so AbletonOSC, has no
+    // way to move a track, and the other engines don't edit tracks at all.
+
+    /// Insert a track at `track_index` (−1 appends).  Return tracks are
+    /// always appended.
+    fn create_track(&self, _kind: TrackKind, _track_index: i32) -> Result<(), String> {
+        Err(format!("{} cannot create tracks", self.name()))
+    }
+
+    fn delete_track(&self, _track_index: i32) -> Result<(), String> {
+        Err(format!("{} cannot delete tracks", self.name()))
+    }
+
+    /// Duplicate the track, inserting the copy after it.
+    fn duplicate_track(&self, _track_index: i32) -> Result<(), String> {
+        Err(format!("{} cannot duplicate tracks", self.name()))
+    }
+
+    fn rename_track(&self, _track_index: i32, _name: &str) -> Result<(), String> {
+        Err(format!("{} cannot rename tracks", self.name()))
+    }
+
+    /// `color` is `0xRRGGBB`.
+    fn set_track_color(&self, _track_index: i32, _color: u32) -> Result<(), String> {
+        Err(format!("{} cannot recolor tracks", self.name()))
+    }
+
+    fn set_track_arm(&self, _track_index: i32, _arm: bool) -> Result<(), String> {
+        Err(format!("{} cannot arm tracks", self.name()))
+    }
+
+    // ── Clips ───────────────────────────────────────────────────────────────
+
+    /// Launch the clip in the given track/scene slot.
+    ///
+    /// Defaults to an error for engines without a clip launcher.
+    fn fire_clip(&self, _track_index: i32, _scene_index: i32) -> Result<(), String> {
+        Err(format!("{} has no clip launcher", self.name()))
+    }
+
+    /// Stop the clip in the given track/scene slot.
+    fn stop_clip(&self, _track_index: i32, _scene_index: i32) -> Result<(), String> {
+        Err(format!("{} has no clip launcher", self.name()))
+    }
+
+    /// Create an empty MIDI clip of `length` beats in an empty slot.
+    fn create_clip(
+        &self,
+        _track_index: i32,
+        _scene_index: i32,
+        _length: f64,
+    ) -> Result<(), String> {
+        Err(format!("{} cannot create clips", self.name()))
+    }
+
+    // ── Notes ───────────────────────────────────────────────────────────────
+    //
+    // Transforms (quantize, transpose, velocity) live in [`notes`] and run on
+    // the list returned by `get_clip_notes`; engines only read and write notes.
+
+    /// Read every note of the MIDI clip in the given slot.
+    fn get_clip_notes(&self, _track_index: i32, _scene_index: i32) -> Result<Vec<Note>, String> {
+        Err(format!("{} does not support note editing", self.name()))
+    }
+
+    /// Add notes to the MIDI clip in the given slot.
+    fn add_notes(
+        &self,
+        _track_index: i32,
+        _scene_index: i32,
+        _notes: &[Note],
+    ) -> Result<(), String> {
+        Err(format!("{} does not support note editing", self.name()))
+    }
+
+    /// Remove the notes inside `region` (every note when `None`).
+    fn remove_notes(
+        &self,
+        _track_index: i32,
+        _scene_index: i32,
+        _region: Option<NoteRegion>,
+    ) -> Result<(), String> {
+        Err(format!("{} does not support note editing", self.name()))
+    }
+
+    // ── Scenes ──────────────────────────────────────────────────────────────
+    //
+    // Scene names and colours are read from `StateManager`, which each engine
+    // keeps up to date.  All default to an error for engines without scenes.
+
+    /// Launch every clip slot in the scene row.
+    fn fire_scene(&self, _scene_index: i32) -> Result<(), String> {
+        Err(format!("{} has no scenes", self.name()))
+    }
+
+    /// Stop the clips playing in the scene row.
+    fn stop_scene(&self, _scene_index: i32) -> Result<(), String> {
+        Err(format!("{} has no scenes", self.name()))
+    }
+
+    /// Insert an empty scene at `scene_index` (−1 appends).
+    fn create_scene(&self, _scene_index: i32) -> Result<(), String> {
+        Err(format!("{} has no scenes", self.name()))
+    }
+
+    /// Duplicate the scene, inserting the copy below it.
+    fn duplicate_scene(&self, _scene_index: i32) -> Result<(), String> {
+        Err(format!("{} has no scenes", self.name()))
+    }
+
+    fn delete_scene(&self, _scene_index: i32) -> Result<(), String> {
+        Err(format!("{} has no scenes", self.name()))
+    }
+
+    // ── Undo (optional) ─────────────────────────────────────────────────────
+    //
+    // The engine's own undo history.  Maestro's journal (`crate::journal`)
+    // reverts its own edits and falls back to these for everything else.
+
+    fn undo(&self) -> Result<(), String> {
+        Err(format!("{} has no undo history", self.name()))
+    }
+
+    fn redo(&self) -> Result<(), String> {
+        Err(format!("{} has no undo history", self.name()))
+    }
+
     // ── Media ───────────────────────────────────────────────────────────────
 
     /// Load an audio file into the given track/scene slot.
@@ -95,8 +305,8 @@ pub trait EngineAdapter: Send + Sync {
 
     /// Return the list of user-defined parameters for this engine.
     ///
-    /// Defaults to an empty list; only the Zrythm adapter reads
-    /// `~/.config/maestro/zrythm-map.toml` and returns real entries.
+    /// Defaults to an empty list; the JACK MIDI adapters return the
+    /// `[[params]]` and `[[notes]]` entries of their map file.
     fn custom_params(&self) -> Vec<ParamDef> {
         vec![]
     }
@@ -0,0 +1,207 @@
+//! MIDI note editing.
+//!
+//! Engines read and write a clip's notes through `EngineAdapter`
+//! (`get_clip_notes`, `add_notes`, `remove_notes`); everything else —
+//! quantize, transpose and velocity edits — is done here on the note list,
+//! so every engine that can read and write notes gets the same edits.
+
+use serde::{Deserialize, Serialize};
+
+use super::{EngineAdapter, Note};
+
+/// A pitch/time window of a clip, as used by `remove_notes`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NoteRegion {
+    pub start_pitch: u8,
+    pub pitch_span: u8,
+    /// Beats from the clip start.
+    pub start_time: f64,
+    pub time_span: f64,
+}
+
+/// An edit applied to every note of a clip.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NoteTransform {
+    /// Move note starts towards the nearest `grid` line (in beats).
+    /// `strength` 1.0 snaps fully, 0.5 moves halfway.
+    Quantize {
+        grid: f64,
+        #[serde(default = "full_strength")]
+        strength: f64,
+    },
+    Transpose { semitones: i32 },
+    /// Multiply velocities by `factor`.
+    ScaleVelocity { factor: f64 },
+    /// Offset each velocity by a random amount in `[-amount, amount]`.
+    /// A fixed `seed` makes the result repeatable.
+    HumanizeVelocity {
+        amount: u8,
+        #[serde(default)]
+        seed: Option<u64>,
+    },
+}
+
+fn full_strength() -> f64 {
+    1.0
+}
+
+impl NoteTransform {
+    pub fn apply(&self, notes: &mut [Note]) {
+        match *self {
+            Self::Quantize { grid, strength } => quantize(notes, grid, strength),
+            Self::Transpose { semitones } => transpose(notes, semitones),
+            Self::ScaleVelocity { factor } => scale_velocity(notes, factor),
+            Self::HumanizeVelocity { amount, seed } => {
+                humanize_velocity(notes, amount, seed.unwrap_or_else(time_seed))
+            }
+        }
+    }
+
+    /// Transform every note of a clip by rewriting its note list through
+    /// the engine.  Returns the notes as written.  Blocks while the engine
+    /// reads the clip.
+    pub fn rewrite_clip(
+        &self,
+        engine: &dyn EngineAdapter,
+        track_index: i32,
+        scene_index: i32,
+    ) -> Result<Vec<Note>, String> {
+        let original = engine.get_clip_notes(track_index, scene_index)?;
+        let mut notes = original.clone();
+        self.apply(&mut notes);
+        engine.remove_notes(track_index, scene_index, None)?;
+        if let Err(e) = engine.add_notes(track_index, scene_index, &notes) {
+            // Don't leave the clip empty.
+            return Err(match engine.add_notes(track_index, scene_index, &original) {
+                Ok(()) => format!("Writing notes failed: {e} (original notes restored)"),
+                Err(r) => format!("Writing notes failed: {e}; restoring them also failed: {r}"),
+            });
+        }
+        Ok(notes)
+    }
+}
+
+pub fn quantize(notes: &mut [Note], grid: f64, strength: f64) {
+    if grid <= 0.0 {
+        return;
+    }
+    let strength = strength.clamp(0.0, 1.0);
+    for note in notes {
+        let target = (note.start / grid).round() * grid;
+        note.start += (target - note.start) * strength;
+    }
+}
+
+/// Shift every note by `semitones`, clamping to the MIDI range.
+pub fn transpose(notes: &mut [Note], semitones: i32) {
+    for note in notes {
+        note.pitch = (note.pitch as i32 + semitones).clamp(0, 127) as u8;
+    }
+}
+
+pub fn scale_velocity(notes: &mut [Note], factor: f64) {
+    for note in notes {
+        note.velocity = clamp_velocity((note.velocity as f64 * factor).round() as i32);
+    }
+}
+
+pub fn humanize_velocity(notes: &mut [Note], amount: u8, seed: u64) {
+    let mut rng = XorShift(seed | 1);
+    let span = 2 * amount as u64 + 1;
+    for note in notes {
+        let offset = (rng.next() % span) as i32 - amount as i32;
+        note.velocity = clamp_velocity(note.velocity as i32 + offset);
+    }
+}
+
+/// Velocity 0 is a note-off, so edited notes stay audible at 1.
+fn clamp_velocity(v: i32) -> u8 {
+    v.clamp(1, 127) as u8
+}
+
+fn time_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// Minimal xorshift64 — humanizing doesn't need a real RNG crate.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(pitch: u8, start: f64, velocity: u8) -> Note {
+        Note {
+            pitch,
+            start,
+            duration: 0.25,
+            velocity,
+            mute: false,
+        }
+    }
+
+    #[test]
+    fn quantize_snaps_by_strength() {
+        let mut notes = vec![note(60, 0.3, 100), note(62, 0.9, 100)];
+        quantize(&mut notes, 0.5, 1.0);
+        assert_eq!((notes[0].start, notes[1].start), (0.5, 1.0));
+
+        let mut notes = vec![note(60, 0.2, 100)];
+        quantize(&mut notes, 0.5, 0.5);
+        assert!((notes[0].start - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn transpose_and_scale_clamp_to_midi_range() {
+        let mut notes = vec![note(120, 0.0, 100), note(3, 0.0, 2)];
+        transpose(&mut notes, 12);
+        assert_eq!(notes[0].pitch, 127);
+        transpose(&mut notes, -24);
+        assert_eq!(notes[1].pitch, 0);
+
+        scale_velocity(&mut notes, 1.5);
+        assert_eq!((notes[0].velocity, notes[1].velocity), (127, 3));
+        scale_velocity(&mut notes, 0.0);
+        assert_eq!(notes[0].velocity, 1);
+    }
+
+    #[test]
+    fn humanize_stays_within_amount_and_is_repeatable() {
+        let original: Vec<Note> = (0..32).map(|i| note(60, i as f64, 64)).collect();
+        let mut a = original.clone();
+        let mut b = original.clone();
+        humanize_velocity(&mut a, 10, 42);
+        humanize_velocity(&mut b, 10, 42);
+        assert_eq!(a, b);
+        assert!(a.iter().all(|n| (54..=74).contains(&n.velocity)));
+        assert!(a.iter().any(|n| n.velocity != 64));
+    }
+
+    #[test]
+    fn transforms_deserialize_from_tagged_json() {
+        let t: NoteTransform =
+            serde_json::from_str(r#"{"kind":"quantize","grid":0.25}"#).unwrap();
+        assert_eq!(
+            t,
+            NoteTransform::Quantize {
+                grid: 0.25,
+                strength: 1.0
+            }
+        );
+    }
+}
@@ -0,0 +1,437 @@
+//! Reaper engine adapter over OSC.
+//!
+//! Uses Reaper's built-in OSC control surface with the stock
+//! `Default.ReaperOSC` pattern config:
+//!
+//! - **Send** commands to Reaper's local listen port (default 8000)
+//! - **Receive** feedback on the surface's device port (default 9000)
+//!
+//! | Maestro          | Reaper address              | Value                  |
+//! |------------------|-----------------------------|------------------------|
+//! | play / stop      | `/play`, `/stop`            | —                      |
+//! | record           | `/record`                   | toggle                 |
+//! | tempo            | `/tempo/raw`                | BPM                    |
+//! | loop             | `/repeat`                   | 0 / 1                  |
+//! | song position    | `/time`                     | seconds                |
+//! | track volume     | `/track/@/volume`           | normalized fader 0–1   |
+//! | track pan        | `/track/@/pan`              | 0–1, 0.5 = centre      |
+//! | track mute/solo  | `/track/@/mute`, `/solo`    | 0 / 1                  |
+//! | track arm        | `/track/@/recarm`           | 0 / 1                  |
+//! | track send       | `/track/@/send/@/volume`    | normalized fader 0–1   |
+//! | master vol/pan   | `/master/volume`, `/pan`    | 0–1                    |
+//!
+//! `@` is the 1-based track number within the surface's track bank, so set
+//! the bank size ("DEVICE_TRACK_COUNT") large enough to cover the project.
+//!
+//! ## Setup
+//!
+//! In Reaper → **Preferences → Control/OSC/web → Add → OSC**:
+//! mode "Configure device IP + local port", device port **9000**,
+//! device IP `127.0.0.1`, local listen port **8000**, pattern config
+//! `Default`.  Then launch Maestro with `MAESTRO_ENGINE=reaper`.
+//!
+//! References:
+//! * <https://www.reaper.fm/sdk/osc/osc.php>
+
+use std::sync::OnceLock;
+
+use rosc::OscType;
+use tauri::{AppHandle, Emitter};
+
+use crate::engine::{EngineAdapter, StateManager};
+use crate::osc::feedback::{self, one_based, parse_track_addr, Change, TrackValue};
+use crate::osc::{args, client::OscClient, listener};
+
+/// Port Reaper sends feedback to (the surface's "device port").
+const REAPER_OSC_LISTEN_PORT: u16 = 9000;
+
+/// "Control surface: refresh all surfaces" — makes Reaper resend all feedback.
+const ACTION_REFRESH_SURFACES: i32 = 41743;
+
+/// "Edit: Undo" / "Edit: Redo".
+const ACTION_UNDO: i32 = 40029;
+const ACTION_REDO: i32 = 40030;
+
+// ── Message builders ───────────────────────────────────────────────────────
+
+/// Typed OSC message builders for Reaper's default pattern config.
+pub struct ReaperMessages;
+
+impl ReaperMessages {
+    pub fn play() -> (String, Vec<OscType>) {
+        ("/play".into(), vec![])
+    }
+
+    pub fn stop() -> (String, Vec<OscType>) {
+        ("/stop".into(), vec![])
+    }
+
+    pub fn toggle_record() -> (String, Vec<OscType>) {
+        ("/record".into(), vec![])
+    }
+
+    pub fn set_tempo(bpm: f32) -> (String, Vec<OscType>) {
+        ("/tempo/raw".into(), vec![OscType::Float(bpm)])
+    }
+
+    pub fn set_repeat(enabled: bool) -> (String, Vec<OscType>) {
+        ("/repeat".into(), vec![OscType::Int(enabled as i32)])
+    }
+
+    pub fn set_time(seconds: f32) -> (String, Vec<OscType>) {
+        ("/time".into(), vec![OscType::Float(seconds)])
+    }
+
+    pub fn action(command_id: i32) -> (String, Vec<OscType>) {
+        ("/action".into(), vec![OscType::Int(command_id)])
+    }
+
+    pub fn set_track_volume(track: i32, volume: f32) -> Result<(String, Vec<OscType>), String> {
+        Ok((track_addr(track, "volume")?, vec![OscType::Float(volume)]))
+    }
+
+    /// `pan` is Maestro's −1..1; Reaper expects 0..1 with 0.5 = centre.
+    pub fn set_track_pan(track: i32, pan: f32) -> Result<(String, Vec<OscType>), String> {
+        let normalized = (pan.clamp(-1.0, 1.0) + 1.0) / 2.0;
+        Ok((track_addr(track, "pan")?, vec![OscType::Float(normalized)]))
+    }
+
+    pub fn set_track_mute(track: i32, mute: bool) -> Result<(String, Vec<OscType>), String> {
+        Ok((track_addr(track, "mute")?, vec![OscType::Int(mute as i32)]))
+    }
+
+    pub fn set_track_solo(track: i32, solo: bool) -> Result<(String, Vec<OscType>), String> {
+        Ok((track_addr(track, "solo")?, vec![OscType::Int(solo as i32)]))
+    }
+
+    pub fn set_track_send(
+        track: i32,
+        send: i32,
+        volume: f32,
+    ) -> Result<(String, Vec<OscType>), String> {
+        let send = one_based(send, "send")?;
+        Ok((
+            track_addr(track, &format!("send/{send}/volume"))?,
+            vec![OscType::Float(volume)],
+        ))
+    }
+
+    pub fn set_master_volume(volume: f32) -> (String, Vec<OscType>) {
+        ("/master/volume".into(), vec![OscType::Float(volume)])
+    }
+
+    pub fn set_master_pan(pan: f32) -> (String, Vec<OscType>) {
+        let normalized = (pan.clamp(-1.0, 1.0) + 1.0) / 2.0;
+        ("/master/pan".into(), vec![OscType::Float(normalized)])
+    }
+
+    pub fn set_track_recarm(track: i32, arm: bool) -> Result<(String, Vec<OscType>), String> {
+        Ok((track_addr(track, "recarm")?, vec![OscType::Int(arm as i32)]))
+    }
+}
+
+/// `/track/<n>/<param>` with Maestro's 0-based index mapped to Reaper's 1-based `@`.
+fn track_addr(track: i32, param: &str) -> Result<String, String> {
+    Ok(format!("/track/{}/{}", one_based(track, "track")?, param))
+}
+
+// ── Feedback ───────────────────────────────────────────────────────────────
+
+/// Apply one Reaper feedback message to `state`.
+fn apply_feedback(state: &StateManager, addr: &str, osc_args: &[OscType]) -> Change {
+    match addr {
+        "/play" => match args::first_bool(osc_args) {
+            Some(playing) => {
+                state.set_playing(playing);
+                Change::Transport
+            }
+            None => Change::None,
+        },
+        "/repeat" => match args::first_bool(osc_args) {
+            Some(enabled) => {
+                state.set_loop_enabled(enabled);
+                Change::Transport
+            }
+            None => Change::None,
+        },
+        "/tempo/raw" => match args::first_f64(osc_args) {
+            Some(bpm) => {
+                state.set_tempo(bpm);
+                Change::Tempo(bpm)
+            }
+            None => Change::None,
+        },
+        "/time" => {
+            // Reaper reports seconds; Maestro's song time is in beats.
+            let tempo = state.get().tempo;
+            match args::first_f64(osc_args) {
+                Some(seconds) if tempo > 0.0 => {
+                    let beats = seconds * tempo / 60.0;
+                    state.set_current_time(beats);
+                    Change::SongTime(beats)
+                }
+                _ => Change::None,
+            }
+        }
+        _ => match parse_track_addr(addr) {
+            Some((index, param)) => apply_track_feedback(state, index, param, osc_args),
+            None => Change::None,
+        },
+    }
+}
+
+fn apply_track_feedback(
+    state: &StateManager,
+    index: usize,
+    param: &str,
+    osc_args: &[OscType],
+) -> Change {
+    let Some(arg) = osc_args.first() else {
+        return Change::None;
+    };
+    let value = match param {
+        "volume" => args::as_f64(arg).map(TrackValue::Volume),
+        "pan" => args::as_f64(arg).map(|v| TrackValue::Pan(v * 2.0 - 1.0)),
+        "mute" => args::as_bool(arg).map(TrackValue::Mute),
+        "solo" => args::as_bool(arg).map(TrackValue::Solo),
+        "recarm" => args::as_bool(arg).map(TrackValue::Arm),
+        "name" => args::as_str(arg).map(TrackValue::Name),
+        "vu" => {
+            // Meters update constantly; don't grow the track list for them.
+            if let Some(level) = args::as_f64(arg) {
+                state.set_track_meter(index, level);
+            }
+            None
+        }
+        _ => None,
+    };
+    match value {
+        Some(v) => feedback::apply_track_value(state, index, v),
+        None => Change::None,
+    }
+}
+
+fn handle_message(state: &StateManager, addr: &str, osc_args: &[OscType]) {
+    if apply_feedback(state, addr, osc_args) == Change::None {
+        log::trace!("Unhandled Reaper OSC: {} {:?}", addr, osc_args);
+    }
+}
+
+// ── Adapter ────────────────────────────────────────────────────────────────
+
+/// Reaper engine adapter using Reaper's native OSC control surface.
+pub struct ReaperEngine {
+    client: OscClient,
+    /// Set in `start`; used to convert beats ↔ seconds with the current tempo.
+    state: OnceLock<StateManager>,
+}
+
+impl ReaperEngine {
+    pub fn new(client: OscClient) -> Self {
+        Self {
+            client,
+            state: OnceLock::new(),
+        }
+    }
+
+    fn send(&self, (addr, args): (String, Vec<OscType>)) -> Result<(), String> {
+        self.client.send(&addr, args).map_err(|e| e.to_string())
+    }
+}
+
+impl EngineAdapter for ReaperEngine {
+    fn name(&self) -> &'static str {
+        "Reaper"
+    }
+
+    fn start(&self, app: AppHandle, state_manager: StateManager) {
+        let _ = self.state.set(state_manager.clone());
+
+        // Reaper only sends feedback, it has no ping — treat the first
+        // message as the connection being established.
+        let connected = std::sync::atomic::AtomicBool::new(false);
+        listener::listen(REAPER_OSC_LISTEN_PORT, move |msg| {
+            if !connected.swap(true, std::sync::atomic::Ordering::Relaxed) {
+                log::info!("Reaper OSC feedback received");
+                let _ = app.emit("engine-connection-changed", true);
+            }
+            handle_message(&state_manager, &msg.addr, &msg.args);
+        });
+
+        // Ask Reaper to resend the full surface state (transport, tracks).
+        if let Err(e) = self.send(ReaperMessages::action(ACTION_REFRESH_SURFACES)) {
+            log::warn!("Failed to request Reaper surface refresh: {}", e);
+        }
+    }
+
+    // ── Transport ───────────────────────────────────────────────────────────
+
+    fn play(&self) -> Result<(), String> {
+        self.send(ReaperMessages::play())
+    }
+
+    fn stop(&self) -> Result<(), String> {
+        self.send(ReaperMessages::stop())
+    }
+
+    fn toggle_record(&self) -> Result<(), String> {
+        self.send(ReaperMessages::toggle_record())
+    }
+
+    fn set_tempo(&self, bpm: f32) -> Result<(), String> {
+        self.send(ReaperMessages::set_tempo(bpm))
+    }
+
+    fn set_loop_enabled(&self, enabled: bool) -> Result<(), String> {
+        self.send(ReaperMessages::set_repeat(enabled))
+    }
+
+    fn set_song_time(&self, beats: f64) -> Result<(), String> {
+        let tempo = self.state.get().map(|s| s.get().tempo).unwrap_or(0.0);
+        if tempo <= 0.0 {
+            return Err("Reaper tempo unknown — cannot convert beats to seconds yet".into());
+        }
+        self.send(ReaperMessages::set_time((beats * 60.0 / tempo) as f32))
+    }
+
+    // ── Tracks ──────────────────────────────────────────────────────────────
+
+    fn set_track_volume(&self, track_index: i32, volume: f32) -> Result<(), String> {
+        self.send(ReaperMessages::set_track_volume(track_index, volume)?)
+    }
+
+    fn set_track_mute(&self, track_index: i32, mute: bool) -> Result<(), String> {
+        self.send(ReaperMessages::set_track_mute(track_index, mute)?)
+    }
+
+    fn set_track_solo(&self, track_index: i32, solo: bool) -> Result<(), String> {
+        self.send(ReaperMessages::set_track_solo(track_index, solo)?)
+    }
+
+    fn set_track_pan(&self, track_index: i32, pan: f32) -> Result<(), String> {
+        self.send(ReaperMessages::set_track_pan(track_index, pan)?)
+    }
+
+    fn set_track_arm(&self, track_index: i32, arm: bool) -> Result<(), String> {
+        self.send(ReaperMessages::set_track_recarm(track_index, arm)?)
+    }
+
+    // ── Sends and master ────────────────────────────────────────────────────
+
+    fn set_track_send(&self, track_index: i32, send_index: i32, value: f32) -> Result<(), String> {
+        self.send(ReaperMessages::set_track_send(track_index, send_index, value)?)
+    }
+
+    fn set_master_volume(&self, volume: f32) -> Result<(), String> {
+        self.send(ReaperMessages::set_master_volume(volume))
+    }
+
+    fn set_master_pan(&self, pan: f32) -> Result<(), String> {
+        self.send(ReaperMessages::set_master_pan(pan))
+    }
+
+    // ── Undo ────────────────────────────────────────────────────────────────
+
+    fn undo(&self) -> Result<(), String> {
+        self.send(ReaperMessages::action(ACTION_UNDO))
+    }
+
+    fn redo(&self) -> Result<(), String> {
+        self.send(ReaperMessages::action(ACTION_REDO))
+    }
+
+    // ── Media ───────────────────────────────────────────────────────────────
+
+    fn load_sample(
+        &self,
+        _track_index: i32,
+        _scene_index: i32,
+        _file_path: &str,
+    ) -> Result<(), String> {
+        Err(
+            "Sample loading is not exposed by Reaper's OSC surface. \
+             Drag audio files directly into Reaper's arrange view."
+                .into(),
+        )
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn track_addresses_are_one_based() {
+        assert_eq!(ReaperMessages::set_track_volume(0, 0.5).unwrap().0, "/track/1/volume");
+        // `/track/0` is the master track.
+        assert!(ReaperMessages::set_track_volume(-1, 0.5).is_err());
+        assert!(ReaperMessages::set_track_send(0, -1, 0.5).is_err());
+        assert!(ReaperMessages::set_track_mute(i32::MAX, true).is_err());
+        assert_eq!(parse_track_addr("/track/1/volume"), Some((0, "volume")));
+        assert_eq!(parse_track_addr("/track/12/fx/1/name"), Some((11, "fx/1/name")));
+        assert_eq!(parse_track_addr("/track/0/volume"), None);
+        assert_eq!(parse_track_addr("/track/volume"), None);
+    }
+
+    #[test]
+    fn pan_maps_to_normalized_range() {
+        let pan = |p| match ReaperMessages::set_track_pan(0, p).unwrap().1[0] {
+            OscType::Float(f) => f,
+            _ => unreachable!(),
+        };
+        assert_eq!(pan(-1.0), 0.0);
+        assert_eq!(pan(0.0), 0.5);
+        assert_eq!(pan(1.0), 1.0);
+    }
+
+    #[test]
+    fn transport_feedback_updates_state() {
+        let state = StateManager::new();
+        assert_eq!(
+            apply_feedback(&state, "/play", &[OscType::Float(1.0)]),
+            Change::Transport
+        );
+        assert!(state.get().is_playing);
+        assert_eq!(
+            apply_feedback(&state, "/tempo/raw", &[OscType::Float(90.0)]),
+            Change::Tempo(90.0)
+        );
+        // 2 s at 90 BPM = 3 beats
+        assert_eq!(
+            apply_feedback(&state, "/time", &[OscType::Float(2.0)]),
+            Change::SongTime(3.0)
+        );
+        apply_feedback(&state, "/repeat", &[OscType::Int(1)]);
+        assert!(state.get().loop_enabled);
+    }
+
+    #[test]
+    fn track_feedback_grows_track_list() {
+        let state = StateManager::new();
+        assert_eq!(
+            apply_feedback(&state, "/track/3/name", &[OscType::String("Bass".into())]),
+            Change::Tracks
+        );
+        apply_feedback(&state, "/track/3/pan", &[OscType::Float(0.25)]);
+        apply_feedback(&state, "/track/3/mute", &[OscType::Float(1.0)]);
+
+        let s = state.get();
+        assert_eq!(s.num_tracks, 3);
+        assert_eq!(s.tracks[2].index, 2);
+        assert_eq!(s.tracks[2].name, "Bass");
+        assert_eq!(s.tracks[2].panning, -0.5);
+        assert!(s.tracks[2].mute);
+    }
+
+    #[test]
+    fn meters_do_not_create_tracks() {
+        let state = StateManager::new();
+        assert_eq!(
+            apply_feedback(&state, "/track/5/vu", &[OscType::Float(0.7)]),
+            Change::None
+        );
+        assert_eq!(state.get().num_tracks, 0);
+    }
+}
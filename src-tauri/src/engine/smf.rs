@@ -0,0 +1,517 @@
+//! Standard MIDI File (SMF) reading and writing for clip notes.
+//!
+//! Reads format 0 and 1 files (notes from every track are merged into one
+//! list) and writes either format.  Note times are converted to beats: for
+//! pulses-per-quarter-note files that is just `ticks / ppq`; for SMPTE
+//! timecode files the tempo map is used to turn seconds into beats.
+//!
+//! The first tempo and time-signature meta events are reported back so the
+//! caller can apply them, and are written when exporting.
+//!
+//! References:
+//! * Standard MIDI Files 1.0 (MMA RP-001)
+
+use serde::{Deserialize, Serialize};
+
+use super::Note;
+
+/// Resolution used when writing files.
+pub const EXPORT_PPQ: u16 = 480;
+
+/// Tempo assumed until the first tempo event (MIDI's default, 120 BPM).
+const DEFAULT_US_PER_BEAT: u32 = 500_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeSignature {
+    pub numerator: u8,
+    pub denominator: u8,
+}
+
+impl TimeSignature {
+    /// Quarter-note beats per bar.
+    pub fn beats_per_bar(&self) -> f64 {
+        self.numerator as f64 * 4.0 / self.denominator.max(1) as f64
+    }
+}
+
+impl Default for TimeSignature {
+    fn default() -> Self {
+        Self {
+            numerator: 4,
+            denominator: 4,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MidiFile {
+    /// SMF format: 0 (single track) or 1 (conductor track + note track).
+    pub format: u16,
+    /// Notes sorted by start; times in beats.
+    pub notes: Vec<Note>,
+    /// First tempo event, in BPM.
+    pub tempo: Option<f64>,
+    /// First time-signature event.
+    pub time_signature: Option<TimeSignature>,
+}
+
+impl MidiFile {
+    /// Length in beats covering every note, rounded up to whole bars (at
+    /// least one).
+    pub fn bar_aligned_length(&self) -> f64 {
+        let bar = self.time_signature.unwrap_or_default().beats_per_bar();
+        let end = self
+            .notes
+            .iter()
+            .map(|n| n.start + n.duration)
+            .fold(0.0, f64::max);
+        ((end / bar).ceil() * bar).max(bar)
+    }
+}
+
+// ── Reading ────────────────────────────────────────────────────────────────
+
+/// How ticks map to time, from the header's division field.
+#[derive(Debug, Clone, Copy)]
+enum Division {
+    Ppq(u16),
+    /// Ticks per second (frames per second × ticks per frame).
+    Smpte(f64),
+}
+
+struct RawNote {
+    start: u64,
+    end: u64,
+    pitch: u8,
+    velocity: u8,
+}
+
+pub fn parse(bytes: &[u8]) -> Result<MidiFile, String> {
+    let mut r = Reader { bytes, pos: 0 };
+    let (id, header) = r.chunk()?;
+    if id != *b"MThd" || header.len() < 6 {
+        return Err("Not a Standard MIDI File (missing MThd header)".into());
+    }
+    let format = u16::from_be_bytes([header[0], header[1]]);
+    let num_tracks = u16::from_be_bytes([header[2], header[3]]);
+    if format > 1 {
+        return Err(format!("SMF format {format} is not supported (only 0 and 1)"));
+    }
+    let division = match i16::from_be_bytes([header[4], header[5]]) {
+        d if d > 0 => Division::Ppq(d as u16),
+        d if d < 0 => {
+            // High byte is −fps (two's complement), low byte ticks per frame.
+            let fps = match (d >> 8) as i8 {
+                -29 => 29.97,
+                fps => -(fps as f64),
+            };
+            Division::Smpte(fps * header[5] as f64)
+        }
+        _ => return Err("Invalid SMF division of 0".into()),
+    };
+
+    let mut notes = Vec::new();
+    let mut tempos: Vec<(u64, u32)> = Vec::new();
+    let mut time_signature = None;
+    for _ in 0..num_tracks {
+        let (id, data) = r.chunk()?;
+        // Unknown chunk types must be skipped, per the spec.
+        if id == *b"MTrk" {
+            parse_track(data, &mut notes, &mut tempos, &mut time_signature)?;
+        }
+    }
+    tempos.sort_by_key(|&(tick, _)| tick);
+
+    let to_beats = |tick: u64| ticks_to_beats(tick, division, &tempos);
+    let mut notes: Vec<Note> = notes
+        .iter()
+        .map(|n| {
+            let start = to_beats(n.start);
+            Note {
+                pitch: n.pitch,
+                start,
+                duration: to_beats(n.end) - start,
+                velocity: n.velocity,
+                mute: false,
+            }
+        })
+        .collect();
+    notes.sort_by(|a, b| a.start.total_cmp(&b.start).then(a.pitch.cmp(&b.pitch)));
+
+    Ok(MidiFile {
+        format,
+        notes,
+        tempo: tempos.first().map(|&(_, us)| 60_000_000.0 / us as f64),
+        time_signature,
+    })
+}
+
+fn parse_track(
+    data: &[u8],
+    notes: &mut Vec<RawNote>,
+    tempos: &mut Vec<(u64, u32)>,
+    time_signature: &mut Option<TimeSignature>,
+) -> Result<(), String> {
+    let mut r = Reader {
+        bytes: data,
+        pos: 0,
+    };
+    let mut tick = 0u64;
+    let mut running_status = None;
+    // Open notes per (channel, pitch); overlapping repeats close first-in, first-out.
+    let mut open: Vec<(u8, RawNote)> = Vec::new();
+
+    while !r.at_end() {
+        tick += r.vlq()? as u64;
+        let mut status = r.u8()?;
+        match status {
+            0xFF => {
+                let kind = r.u8()?;
+                let len = r.vlq()? as usize;
+                let meta = r.take(len)?;
+                match (kind, meta) {
+                    (0x2F, _) => break,
+                    (0x51, [0, 0, 0]) => return Err("Invalid MIDI tempo of 0".into()),
+                    (0x51, [a, b, c]) => {
+                        tempos.push((tick, u32::from_be_bytes([0, *a, *b, *c])));
+                    }
+                    (0x58, [0, ..]) => {
+                        return Err("Invalid MIDI time signature numerator of 0".into())
+                    }
+                    (0x58, [nn, dd, ..]) if time_signature.is_none() => {
+                        *time_signature = Some(TimeSignature {
+                            numerator: *nn,
+                            denominator: 1u8.checked_shl(*dd as u32).unwrap_or(4),
+                        });
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            0xF0 | 0xF7 => {
+                let len = r.vlq()? as usize;
+                r.take(len)?;
+                continue;
+            }
+            _ => {}
+        }
+        let first_data = if status < 0x80 {
+            // Running status: this byte is the first data byte.
+            let data = status;
+            status = running_status.ok_or("MIDI data byte without a status byte")?;
+            data
+        } else {
+            running_status = Some(status);
+            r.u8()?
+        };
+        let channel = status & 0x0F;
+        match status & 0xF0 {
+            0x90 | 0x80 => {
+                let velocity = r.u8()?;
+                let pitch = first_data & 0x7F;
+                if status & 0xF0 == 0x90 && velocity > 0 {
+                    open.push((
+                        channel,
+                        RawNote {
+                            start: tick,
+                            end: tick,
+                            pitch,
+                            velocity,
+                        },
+                    ));
+                } else if let Some(i) = open
+                    .iter()
+                    .position(|(c, n)| *c == channel && n.pitch == pitch)
+                {
+                    let (_, mut note) = open.remove(i);
+                    note.end = tick;
+                    notes.push(note);
+                }
+            }
+            // Program change and channel pressure have one data byte.
+            0xC0 | 0xD0 => {}
+            _ => {
+                r.u8()?;
+            }
+        }
+    }
+    // Notes never released end with the track.
+    for (_, mut note) in open {
+        note.end = tick;
+        notes.push(note);
+    }
+    Ok(())
+}
+
+fn ticks_to_beats(tick: u64, division: Division, tempos: &[(u64, u32)]) -> f64 {
+    let ticks_per_second = match division {
+        Division::Ppq(ppq) => return tick as f64 / ppq as f64,
+        Division::Smpte(tps) => tps,
+    };
+    // Walk the tempo map, accumulating beats segment by segment.
+    let mut beats = 0.0;
+    let mut last_tick = 0u64;
+    let mut us_per_beat = DEFAULT_US_PER_BEAT;
+    for &(t, us) in tempos.iter().take_while(|&&(t, _)| t < tick) {
+        beats += segment_beats(t - last_tick, ticks_per_second, us_per_beat);
+        last_tick = t;
+        us_per_beat = us;
+    }
+    beats + segment_beats(tick - last_tick, ticks_per_second, us_per_beat)
+}
+
+fn segment_beats(ticks: u64, ticks_per_second: f64, us_per_beat: u32) -> f64 {
+    ticks as f64 / ticks_per_second * 1_000_000.0 / us_per_beat as f64
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn at_end(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|&e| e <= self.bytes.len());
+        let end = end.ok_or("Unexpected end of MIDI file")?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Variable-length quantity: 7 bits per byte, high bit set on all but the last.
+    fn vlq(&mut self) -> Result<u32, String> {
+        let mut value = 0u32;
+        for _ in 0..4 {
+            let b = self.u8()?;
+            value = (value << 7) | (b & 0x7F) as u32;
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("Invalid variable-length quantity in MIDI file".into())
+    }
+
+    fn chunk(&mut self) -> Result<([u8; 4], &'a [u8]), String> {
+        let id: [u8; 4] = self.take(4)?.try_into().unwrap();
+        let len = u32::from_be_bytes(self.take(4)?.try_into().unwrap());
+        Ok((id, self.take(len as usize)?))
+    }
+}
+
+// ── Writing ────────────────────────────────────────────────────────────────
+
+/// Encode `file` at [`EXPORT_PPQ`].  Muted notes are left out, since SMF
+/// has no way to mark a note silent.  Notes go on channel 1.  Fails if
+/// events are further apart than a MIDI delta time can express.
+pub fn write(file: &MidiFile) -> Result<Vec<u8>, String> {
+    let mut conductor: Vec<(u64, Vec<u8>)> = Vec::new();
+    if let Some(bpm) = file.tempo.filter(|b| *b > 0.0) {
+        let us = ((60_000_000.0 / bpm).round() as u32).to_be_bytes();
+        conductor.push((0, vec![0xFF, 0x51, 0x03, us[1], us[2], us[3]]));
+    }
+    if let Some(ts) = file.time_signature {
+        let dd = ts.denominator.max(1).trailing_zeros() as u8;
+        conductor.push((0, vec![0xFF, 0x58, 0x04, ts.numerator, dd, 24, 8]));
+    }
+
+    let to_ticks = |beats: f64| (beats.max(0.0) * EXPORT_PPQ as f64).round() as u64;
+    let mut note_events: Vec<(u64, Vec<u8>)> = Vec::new();
+    for note in file.notes.iter().filter(|n| !n.mute) {
+        let start = to_ticks(note.start);
+        let end = to_ticks(note.start + note.duration).max(start);
+        let pitch = note.pitch.min(127);
+        note_events.push((start, vec![0x90, pitch, note.velocity.clamp(1, 127)]));
+        note_events.push((end, vec![0x80, pitch, 0x40]));
+    }
+    // Stable sort by time, note-offs first, so back-to-back notes of the
+    // same pitch don't cut each other off.
+    note_events.sort_by_key(|(tick, ev)| (*tick, ev[0] == 0x90));
+
+    let mut out = Vec::new();
+    let tracks = if file.format == 0 {
+        conductor.extend(note_events);
+        conductor.sort_by_key(|(tick, _)| *tick);
+        vec![conductor]
+    } else {
+        vec![conductor, note_events]
+    };
+    out.extend_from_slice(b"MThd");
+    out.extend_from_slice(&6u32.to_be_bytes());
+    out.extend_from_slice(&file.format.min(1).to_be_bytes());
+    out.extend_from_slice(&(tracks.len() as u16).to_be_bytes());
+    out.extend_from_slice(&EXPORT_PPQ.to_be_bytes());
+    for events in tracks {
+        write_track(&mut out, &events)?;
+    }
+    Ok(out)
+}
+
+/// Largest delta time a variable-length quantity holds (four bytes).
+const MAX_DELTA: u64 = 0x0FFF_FFFF;
+
+fn write_track(out: &mut Vec<u8>, events: &[(u64, Vec<u8>)]) -> Result<(), String> {
+    let mut data = Vec::new();
+    let mut last = 0;
+    for (tick, bytes) in events {
+        let delta = tick - last;
+        if delta > MAX_DELTA {
+            return Err(format!(
+                "Events {} beats apart can't be written to a MIDI file",
+                delta / EXPORT_PPQ as u64
+            ));
+        }
+        write_vlq(&mut data, delta as u32);
+        data.extend_from_slice(bytes);
+        last = *tick;
+    }
+    write_vlq(&mut data, 0);
+    data.extend_from_slice(&[0xFF, 0x2F, 0x00]);
+
+    out.extend_from_slice(b"MTrk");
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(&data);
+    Ok(())
+}
+
+/// Encode `value`, at most [`MAX_DELTA`].
+fn write_vlq(out: &mut Vec<u8>, mut value: u32) {
+    let mut buf = [0u8; 4];
+    let mut i = buf.len() - 1;
+    buf[i] = (value & 0x7F) as u8;
+    value >>= 7;
+    while value > 0 {
+        i -= 1;
+        buf[i] = (value & 0x7F) as u8 | 0x80;
+        value >>= 7;
+    }
+    out.extend_from_slice(&buf[i..]);
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note(pitch: u8, start: f64, duration: f64) -> Note {
+        Note {
+            pitch,
+            start,
+            duration,
+            velocity: 100,
+            mute: false,
+        }
+    }
+
+    fn file(format: u16) -> MidiFile {
+        MidiFile {
+            format,
+            notes: vec![note(60, 0.0, 1.0), note(64, 0.5, 0.25), note(60, 1.0, 2.5)],
+            tempo: Some(128.0),
+            time_signature: Some(TimeSignature {
+                numerator: 3,
+                denominator: 4,
+            }),
+        }
+    }
+
+    #[test]
+    fn write_then_parse_round_trips_both_formats() {
+        for format in [0, 1] {
+            let original = file(format);
+            let parsed = parse(&write(&original).unwrap()).unwrap();
+            assert_eq!(parsed.format, format);
+            assert_eq!(parsed.notes, original.notes);
+            assert!((parsed.tempo.unwrap() - 128.0).abs() < 0.01);
+            assert_eq!(parsed.time_signature, original.time_signature);
+        }
+    }
+
+    #[test]
+    fn out_of_range_deltas_are_an_error() {
+        let last_beat = (MAX_DELTA / EXPORT_PPQ as u64) as f64;
+        let mut far = file(1);
+        far.notes = vec![note(60, last_beat, 0.0)];
+        assert!(write(&far).is_ok());
+        far.notes = vec![note(60, last_beat + 1.0, 0.0)];
+        assert!(write(&far).unwrap_err().contains("beats apart"));
+    }
+
+    #[test]
+    fn length_rounds_up_to_whole_bars() {
+        // Last note ends at beat 3.5; bars of 3/4 are 3 beats long.
+        assert_eq!(file(1).bar_aligned_length(), 6.0);
+    }
+
+    #[test]
+    fn parses_running_status_and_zero_velocity_note_off() {
+        #[rustfmt::skip]
+        let track = [
+            0x00, 0x90, 60, 100,   // note on
+            0x00, 62, 90,          // running status note on
+            0x60, 60, 0,           // running status note on, velocity 0 = off
+            0x60, 62, 0,
+            0x00, 0xFF, 0x2F, 0x00,
+        ];
+        let mut bytes = b"MThd\0\0\0\x06\0\0\0\x01\0\x60MTrk".to_vec();
+        bytes.extend_from_slice(&(track.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&track);
+
+        let parsed = parse(&bytes).unwrap();
+        let soft = Note {
+            velocity: 90,
+            ..note(62, 0.0, 2.0)
+        };
+        assert_eq!(parsed.notes, vec![note(60, 0.0, 1.0), soft]);
+        assert_eq!((parsed.tempo, parsed.time_signature), (None, None));
+    }
+
+    #[test]
+    fn smpte_files_use_the_tempo_map() {
+        // 25 fps × 40 ticks per frame = 1000 ticks per second.  At 60 BPM a
+        // beat is one second until the tempo doubles at tick 1000.
+        #[rustfmt::skip]
+        let track = [
+            0x00, 0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40,       // 1,000,000 µs/beat
+            0x87, 0x68, 0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20, // +1000: 500,000 µs/beat
+            0x00, 0x90, 60, 100,
+            0x87, 0x68, 0x80, 60, 0,                        // +1000
+            0x00, 0xFF, 0x2F, 0x00,
+        ];
+        let mut bytes = b"MThd\0\0\0\x06\0\0\0\x01\xE7\x28MTrk".to_vec();
+        bytes.extend_from_slice(&(track.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&track);
+
+        let parsed = parse(&bytes).unwrap();
+        assert_eq!(parsed.notes[0].start, 1.0);
+        assert_eq!(parsed.notes[0].duration, 2.0);
+        assert_eq!(parsed.tempo, Some(60.0));
+    }
+
+    #[test]
+    fn rejects_zero_tempo_and_numerator() {
+        for meta in [[0x51, 0x03, 0, 0, 0, 0], [0x58, 0x04, 0, 2, 24, 8]] {
+            let mut track = vec![0x00, 0xFF];
+            track.extend_from_slice(&meta[..2 + meta[1] as usize]);
+            track.extend_from_slice(&[0x00, 0xFF, 0x2F, 0x00]);
+            let mut bytes = b"MThd\0\0\0\x06\0\0\0\x01\0\x60MTrk".to_vec();
+            bytes.extend_from_slice(&(track.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(&track);
+            assert!(parse(&bytes).unwrap_err().contains("of 0"));
+        }
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(parse(b"RIFF....").is_err());
+        assert!(parse(b"MThd\0\0\0\x06\0\x02\0\x01\0\x60").is_err());
+    }
+}
@@ -1,11 +1,20 @@
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
+use tokio::sync::broadcast;
+
+use super::events::{StateChange, TrackField, CHANNEL_CAPACITY};
+
+/// Sends kept per track on engines that don't report their return tracks.
+pub const MAX_SENDS: usize = 64;
 
 /// Generic engine state shared across all backends.
 ///
 /// Populated by each engine's listener and surfaced to Tauri commands
-/// via the `StateManager` wrapper.
+/// via the `StateManager` wrapper.  Serialized in camelCase, like the
+/// frontend types.
 #[derive(Default, Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
 pub struct EngineState {
     pub is_playing: bool,
     pub tempo: f64,
@@ -16,9 +25,18 @@ pub struct EngineState {
     pub num_tracks: usize,
     pub num_scenes: usize,
     pub tracks: Vec<TrackState>,
+    pub scenes: Vec<SceneState>,
+    /// Return (aux) tracks; `TrackState::sends[i]` feeds `return_tracks[i]`.
+    pub return_tracks: Vec<TrackState>,
+    pub master: TrackState,
+    /// The open project, for engines that report one (Bitwig's project
+    /// name, Ardour's session name).
+    #[serde(default)]
+    pub project: Option<String>,
 }
 
-#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct TrackState {
     pub index: usize,
     pub name: String,
@@ -30,9 +48,12 @@ pub struct TrackState {
     pub color: u32,
     pub meter_level: f64,
     pub clips: Vec<ClipState>,
+    /// Send levels (0–1), one per return track.
+    pub sends: Vec<f64>,
 }
 
-#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct ClipState {
     pub track_index: usize,
     pub scene_index: usize,
@@ -43,18 +64,55 @@ pub struct ClipState {
     pub is_triggered: bool,
 }
 
+/// A MIDI note in a clip.  `start` and `duration` are in beats from the
+/// clip start; `velocity` is 1–127.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Note {
+    pub pitch: u8,
+    pub start: f64,
+    pub duration: f64,
+    pub velocity: u8,
+    #[serde(default)]
+    pub mute: bool,
+}
+
+#[derive(Default, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneState {
+    pub index: usize,
+    pub name: String,
+    pub color: u32,
+}
+
+/// `EngineState` together with the version it was read at.
+#[derive(Serialize, Clone, Debug)]
+pub struct VersionedState {
+    pub version: u64,
+    #[serde(flatten)]
+    pub state: EngineState,
+}
+
 /// Thread-safe wrapper around `EngineState`.
 ///
 /// Engine listeners write into this manager; Tauri commands read from it.
+/// Every write that changes a value publishes a [`StateChange`] to the
+/// receivers handed out by [`StateManager::subscribe`] and bumps the
+/// state's version by one, so a reader can tell how many changes it missed.
 #[derive(Clone)]
 pub struct StateManager {
     state: Arc<RwLock<EngineState>>,
+    /// Only bumped under the write lock.
+    version: Arc<AtomicU64>,
+    changes: broadcast::Sender<StateChange>,
 }
 
 impl StateManager {
     pub fn new() -> Self {
+        let (changes, _) = broadcast::channel(CHANNEL_CAPACITY);
         Self {
             state: Arc::new(RwLock::new(EngineState::default())),
+            version: Arc::default(),
+            changes,
         }
     }
 
@@ -62,53 +120,551 @@ impl StateManager {
         self.state.read().unwrap().clone()
     }
 
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::SeqCst)
+    }
+
+    /// The state and its version, read together.
+    pub fn snapshot(&self) -> VersionedState {
+        let state = self.state.read().unwrap();
+        VersionedState {
+            version: self.version(),
+            state: state.clone(),
+        }
+    }
+
+    /// Receive every change made from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<StateChange> {
+        self.changes.subscribe()
+    }
+
+    /// Run `f` under the write lock and publish the changes it collects.
+    /// Publishing under the lock keeps changes in the order they were made.
+    fn update<R>(&self, f: impl FnOnce(&mut EngineState, &mut Vec<StateChange>) -> R) -> R {
+        let mut state = self.state.write().unwrap();
+        let mut changes = Vec::new();
+        let result = f(&mut state, &mut changes);
+        for change in changes {
+            self.version.fetch_add(1, Ordering::SeqCst);
+            // Nobody listening is fine.
+            let _ = self.changes.send(change);
+        }
+        result
+    }
+
     pub fn set_playing(&self, playing: bool) {
-        self.state.write().unwrap().is_playing = playing;
+        self.update(|s, out| {
+            if replace(&mut s.is_playing, playing) {
+                out.push(StateChange::Playing(playing));
+            }
+        })
     }
 
     pub fn set_tempo(&self, tempo: f64) {
-        self.state.write().unwrap().tempo = tempo;
+        self.update(|s, out| {
+            if replace(&mut s.tempo, tempo) {
+                out.push(StateChange::Tempo(tempo));
+            }
+        })
     }
 
     pub fn set_current_time(&self, time: f64) {
-        self.state.write().unwrap().current_time = time;
+        self.update(|s, out| {
+            if replace(&mut s.current_time, time) {
+                out.push(StateChange::SongTime(time));
+            }
+        })
     }
 
     pub fn set_loop_enabled(&self, enabled: bool) {
-        self.state.write().unwrap().loop_enabled = enabled;
+        self.update(|s, out| {
+            if replace(&mut s.loop_enabled, enabled) {
+                out.push(StateChange::Loop(enabled));
+            }
+        })
+    }
+
+    pub fn set_project(&self, project: String) {
+        self.update(|s, out| {
+            if replace(&mut s.project, Some(project.clone())) {
+                out.push(StateChange::Project(project));
+            }
+        })
     }
 
     pub fn set_tracks(&self, tracks: Vec<TrackState>) {
-        let mut state = self.state.write().unwrap();
-        state.num_tracks = tracks.len();
-        state.tracks = tracks;
+        self.update(|s, out| {
+            s.num_tracks = tracks.len();
+            s.tracks = tracks;
+            out.push(StateChange::TrackList(s.num_tracks));
+        })
+    }
+
+    /// Grow the track list so that `index` exists.
+    ///
+    /// For engines that report tracks one at a time through feedback
+    /// messages instead of a full track listing.
+    pub fn ensure_track(&self, index: usize) {
+        self.update(|s, out| grow_tracks(s, index, out))
+    }
+
+    /// Set one field of track `index`, if it exists.  Sends past the last
+    /// return track (or [`MAX_SENDS`] when the engine reports none) are
+    /// ignored.
+    pub fn set_track_field(&self, index: usize, field: TrackField) {
+        self.update(|s, out| {
+            if let TrackField::Send(send, _) = field {
+                let sends = match s.return_tracks.len() {
+                    0 => MAX_SENDS,
+                    n => n,
+                };
+                if send >= sends {
+                    return;
+                }
+            }
+            if let Some(track) = s.tracks.get_mut(index) {
+                if field.apply(track) {
+                    out.push(StateChange::Track { index, field });
+                }
+            }
+        })
+    }
+
+    pub fn set_track_name(&self, index: usize, name: String) {
+        self.set_track_field(index, TrackField::Name(name));
     }
 
     pub fn set_track_volume(&self, index: usize, volume: f64) {
-        let mut state = self.state.write().unwrap();
-        if let Some(track) = state.tracks.get_mut(index) {
-            track.volume = volume;
-        }
+        self.set_track_field(index, TrackField::Volume(volume));
     }
 
     pub fn set_track_mute(&self, index: usize, mute: bool) {
-        let mut state = self.state.write().unwrap();
-        if let Some(track) = state.tracks.get_mut(index) {
-            track.mute = mute;
-        }
+        self.set_track_field(index, TrackField::Mute(mute));
     }
 
     pub fn set_track_solo(&self, index: usize, solo: bool) {
-        let mut state = self.state.write().unwrap();
-        if let Some(track) = state.tracks.get_mut(index) {
-            track.solo = solo;
+        self.set_track_field(index, TrackField::Solo(solo));
+    }
+
+    pub fn set_track_pan(&self, index: usize, panning: f64) {
+        self.set_track_field(index, TrackField::Pan(panning));
+    }
+
+    pub fn set_track_arm(&self, index: usize, arm: bool) {
+        self.set_track_field(index, TrackField::Arm(arm));
+    }
+
+    pub fn set_track_color(&self, index: usize, color: u32) {
+        self.set_track_field(index, TrackField::Color(color));
+    }
+
+    /// Mutate the clip slot at `track`/`scene`, growing the track list and
+    /// the track's slot list as needed.  Returns the updated clip.
+    pub fn update_clip(
+        &self,
+        track: usize,
+        scene: usize,
+        f: impl FnOnce(&mut ClipState),
+    ) -> ClipState {
+        self.update(|s, out| {
+            grow_tracks(s, track, out);
+            let clips = &mut s.tracks[track].clips;
+            let before = clips.get(scene).cloned();
+            while clips.len() <= scene {
+                let scene_index = clips.len();
+                clips.push(ClipState {
+                    track_index: track,
+                    scene_index,
+                    ..Default::default()
+                });
+            }
+            f(&mut clips[scene]);
+            let clip = clips[scene].clone();
+            if s.scenes.len() <= scene {
+                resize_scenes(s, scene + 1, out);
+            }
+            if before.as_ref() != Some(&clip) {
+                out.push(StateChange::Clip(clip.clone()));
+            }
+            clip
+        })
+    }
+
+    pub fn set_clip_name(&self, track: usize, scene: usize, name: String) -> ClipState {
+        self.update_clip(track, scene, |c| c.name = name)
+    }
+
+    pub fn set_clip_color(&self, track: usize, scene: usize, color: u32) -> ClipState {
+        self.update_clip(track, scene, |c| c.color = color)
+    }
+
+    pub fn set_clip_length(&self, track: usize, scene: usize, length: f64) -> ClipState {
+        self.update_clip(track, scene, |c| c.length = length)
+    }
+
+    /// Mark `slot` as the playing clip on `track` (`None` = nothing playing).
+    /// A clip that starts playing is no longer triggered.
+    ///
+    /// Returns the clips whose state changed.
+    pub fn set_playing_slot(&self, track: usize, slot: Option<usize>) -> Vec<ClipState> {
+        self.set_slot_flag(track, slot, |clip, on| {
+            let changed = clip.is_playing != on || (on && clip.is_triggered);
+            clip.is_playing = on;
+            if on {
+                clip.is_triggered = false;
+            }
+            changed
+        })
+    }
+
+    /// Mark `slot` as the triggered (launch-queued) clip on `track`.
+    ///
+    /// Returns the clips whose state changed.
+    pub fn set_triggered_slot(&self, track: usize, slot: Option<usize>) -> Vec<ClipState> {
+        self.set_slot_flag(track, slot, |clip, on| {
+            let changed = clip.is_triggered != on;
+            clip.is_triggered = on;
+            changed
+        })
+    }
+
+    /// Apply `set(clip, clip_is_slot)` to every clip on `track`; `set`
+    /// returns whether it changed anything.
+    fn set_slot_flag(
+        &self,
+        track: usize,
+        slot: Option<usize>,
+        set: impl Fn(&mut ClipState, bool) -> bool,
+    ) -> Vec<ClipState> {
+        if let Some(slot) = slot {
+            self.update_clip(track, slot, |_| {});
         }
+        self.update(|s, out| {
+            let Some(t) = s.tracks.get_mut(track) else {
+                return vec![];
+            };
+            let changed: Vec<ClipState> = t
+                .clips
+                .iter_mut()
+                .filter_map(|clip| {
+                    let on = Some(clip.scene_index) == slot;
+                    set(clip, on).then(|| clip.clone())
+                })
+                .collect();
+            out.extend(changed.iter().cloned().map(StateChange::Clip));
+            changed
+        })
+    }
+
+    /// Insert an empty track at `index` (clamped to the end), shifting the
+    /// tracks after it.
+    pub fn insert_track(&self, index: usize) {
+        self.update(|s, out| {
+            let index = index.min(s.tracks.len());
+            let sends = vec![0.0; s.return_tracks.len()];
+            s.tracks.insert(
+                index,
+                TrackState {
+                    sends,
+                    ..Default::default()
+                },
+            );
+            reindex_tracks(&mut s.tracks[index..], index);
+            s.num_tracks = s.tracks.len();
+            out.push(StateChange::TrackList(s.num_tracks));
+        })
+    }
+
+    /// Remove the track at `index`, shifting the tracks after it.
+    pub fn remove_track(&self, index: usize) {
+        self.update(|s, out| {
+            if index < s.tracks.len() {
+                s.tracks.remove(index);
+                reindex_tracks(&mut s.tracks[index..], index);
+                s.num_tracks = s.tracks.len();
+                out.push(StateChange::TrackList(s.num_tracks));
+            }
+        })
+    }
+
+    /// Resize the track list to `count` tracks, keeping existing ones.
+    pub fn set_track_count(&self, count: usize) {
+        self.update(|s, out| {
+            let len = s.tracks.len();
+            let sends = vec![0.0; s.return_tracks.len()];
+            s.tracks.truncate(count);
+            s.tracks.extend((len..count).map(|index| TrackState {
+                index,
+                sends: sends.clone(),
+                ..Default::default()
+            }));
+            s.num_tracks = count;
+            if len != count {
+                out.push(StateChange::TrackList(count));
+            }
+        })
+    }
+
+    /// Resize the scene list to `count` scenes, keeping existing ones.
+    pub fn set_scene_count(&self, count: usize) {
+        self.update(|s, out| resize_scenes(s, count, out))
+    }
+
+    pub fn set_scene_name(&self, index: usize, name: String) {
+        self.update_scene(index, |scene| scene.name = name)
+    }
+
+    pub fn set_scene_color(&self, index: usize, color: u32) {
+        self.update_scene(index, |scene| scene.color = color)
+    }
+
+    fn update_scene(&self, index: usize, f: impl FnOnce(&mut SceneState)) {
+        self.update(|s, out| {
+            if let Some(scene) = s.scenes.get_mut(index) {
+                let before = scene.clone();
+                f(scene);
+                if *scene != before {
+                    out.push(StateChange::Scene(scene.clone()));
+                }
+            }
+        })
+    }
+
+    /// Set send `send` of track `index`, growing its send list as needed.
+    pub fn set_track_send(&self, index: usize, send: usize, level: f64) {
+        self.set_track_field(index, TrackField::Send(send, level));
+    }
+
+    /// Resize the return track list to `count` and every track's send list
+    /// to match.
+    pub fn set_return_track_count(&self, count: usize) {
+        self.update(|s, out| {
+            let len = s.return_tracks.len();
+            s.return_tracks.truncate(count);
+            s.return_tracks.extend((len..count).map(|index| TrackState {
+                index,
+                ..Default::default()
+            }));
+            for track in &mut s.tracks {
+                track.sends.resize(count, 0.0);
+            }
+            if len != count {
+                out.push(StateChange::ReturnTrackList(count));
+            }
+        })
+    }
+
+    /// Mutate return track `index`, if it exists.
+    pub fn update_return_track(&self, index: usize, f: impl FnOnce(&mut TrackState)) {
+        self.update(|s, out| {
+            if let Some(track) = s.return_tracks.get_mut(index) {
+                let before = track.clone();
+                f(track);
+                if *track != before {
+                    out.push(StateChange::ReturnTrack(track.clone()));
+                }
+            }
+        })
+    }
+
+    pub fn update_master(&self, f: impl FnOnce(&mut TrackState)) {
+        self.update(|s, out| {
+            let before = s.master.clone();
+            f(&mut s.master);
+            if s.master != before {
+                out.push(StateChange::Master(s.master.clone()));
+            }
+        })
     }
 
     pub fn set_track_meter(&self, index: usize, level: f64) {
-        let mut state = self.state.write().unwrap();
-        if let Some(track) = state.tracks.get_mut(index) {
-            track.meter_level = level;
+        self.set_track_field(index, TrackField::Meter(level));
+    }
+}
+
+/// Set `slot` to `value`.  Returns whether it changed.
+fn replace<T: PartialEq>(slot: &mut T, value: T) -> bool {
+    let changed = *slot != value;
+    *slot = value;
+    changed
+}
+
+fn grow_tracks(state: &mut EngineState, index: usize, out: &mut Vec<StateChange>) {
+    if state.tracks.len() > index {
+        return;
+    }
+    while state.tracks.len() <= index {
+        let i = state.tracks.len();
+        state.tracks.push(TrackState {
+            index: i,
+            ..Default::default()
+        });
+    }
+    state.num_tracks = state.tracks.len();
+    out.push(StateChange::TrackList(state.num_tracks));
+}
+
+/// Renumber `tracks` (and their clips) starting at `first`.
+fn reindex_tracks(tracks: &mut [TrackState], first: usize) {
+    for (i, track) in tracks.iter_mut().enumerate() {
+        track.index = first + i;
+        for clip in &mut track.clips {
+            clip.track_index = first + i;
         }
     }
 }
+
+fn resize_scenes(state: &mut EngineState, count: usize, out: &mut Vec<StateChange>) {
+    let len = state.scenes.len();
+    state.scenes.truncate(count);
+    state.scenes.extend((len..count).map(|index| SceneState {
+        index,
+        ..Default::default()
+    }));
+    state.num_scenes = count;
+    if len != count {
+        out.push(StateChange::SceneList(count));
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scene_count_keeps_existing_scenes() {
+        let state = StateManager::new();
+        state.set_scene_count(3);
+        state.set_scene_name(1, "Chorus".into());
+        state.set_scene_count(2);
+        state.set_scene_count(4);
+
+        let s = state.get();
+        assert_eq!(s.num_scenes, 4);
+        assert_eq!(s.scenes[1].name, "Chorus");
+        assert_eq!(s.scenes[3].index, 3);
+    }
+
+    #[test]
+    fn clip_updates_grow_tracks_and_scenes() {
+        let state = StateManager::new();
+        state.update_clip(1, 2, |c| c.name = "Hook".into());
+
+        let s = state.get();
+        assert_eq!((s.num_tracks, s.num_scenes), (2, 3));
+        assert_eq!(s.scenes.len(), 3);
+        assert_eq!(s.tracks[1].clips[2].name, "Hook");
+        assert_eq!(s.tracks[1].clips[2].scene_index, 2);
+    }
+
+    #[test]
+    fn track_inserts_and_removes_keep_indices_consistent() {
+        let state = StateManager::new();
+        state.set_track_count(3);
+        state.set_track_name(2, "Bass".into());
+        state.update_clip(2, 0, |c| c.name = "Riff".into());
+
+        state.insert_track(1);
+        let s = state.get();
+        assert_eq!(s.num_tracks, 4);
+        assert_eq!(s.tracks[3].name, "Bass");
+        assert_eq!(s.tracks[3].index, 3);
+        assert_eq!(s.tracks[3].clips[0].track_index, 3);
+
+        state.remove_track(0);
+        let s = state.get();
+        assert_eq!(s.num_tracks, 3);
+        assert_eq!(s.tracks[2].name, "Bass");
+        assert_eq!(s.tracks[2].clips[0].track_index, 2);
+        assert!(s.tracks.iter().enumerate().all(|(i, t)| t.index == i));
+    }
+
+    #[test]
+    fn return_track_count_resizes_sends() {
+        let state = StateManager::new();
+        state.set_track_count(2);
+        state.set_track_send(0, 1, 0.5);
+        assert_eq!(state.get().tracks[0].sends, vec![0.0, 0.5]);
+
+        state.set_return_track_count(3);
+        state.update_return_track(2, |t| t.name = "Delay".into());
+        let s = state.get();
+        assert_eq!(s.return_tracks[2].name, "Delay");
+        assert_eq!(s.tracks[0].sends, vec![0.0, 0.5, 0.0]);
+        assert_eq!(s.tracks[1].sends.len(), 3);
+
+        // Sends past the last return track, or bogus indices, are ignored.
+        state.set_track_send(0, 3, 1.0);
+        state.set_track_send(0, usize::MAX, 1.0);
+        assert_eq!(state.get().tracks[0].sends, vec![0.0, 0.5, 0.0]);
+        state.set_return_track_count(0);
+        state.set_track_send(1, MAX_SENDS, 1.0);
+        assert!(state.get().tracks[1].sends.is_empty());
+    }
+
+    #[test]
+    fn playing_slot_reports_only_changed_clips() {
+        let state = StateManager::new();
+        state.set_triggered_slot(0, Some(1));
+        assert!(state.get().tracks[0].clips[1].is_triggered);
+
+        let changed = state.set_playing_slot(0, Some(1));
+        assert_eq!(changed.len(), 1);
+        assert!(changed[0].is_playing && !changed[0].is_triggered);
+
+        // Same slot again: nothing changes.
+        assert!(state.set_playing_slot(0, Some(1)).is_empty());
+
+        let changed = state.set_playing_slot(0, None);
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].scene_index, 1);
+        assert!(!changed[0].is_playing);
+    }
+
+    #[test]
+    fn mutations_publish_only_real_changes() {
+        let state = StateManager::new();
+        let mut changes = state.subscribe();
+        state.set_tempo(120.0);
+        state.set_tempo(120.0);
+        state.update_clip(1, 0, |c| c.name = "Hook".into());
+        state.set_track_volume(1, 0.5);
+        state.set_track_volume(1, 0.5);
+        state.update_master(|m| m.volume = 0.0);
+
+        let mut received = Vec::new();
+        while let Ok(change) = changes.try_recv() {
+            received.push(change);
+        }
+        let clip = state.get().tracks[1].clips[0].clone();
+        assert_eq!(state.version(), received.len() as u64);
+        assert_eq!(
+            received,
+            [
+                StateChange::Tempo(120.0),
+                StateChange::TrackList(2),
+                StateChange::SceneList(1),
+                StateChange::Clip(clip),
+                StateChange::Track {
+                    index: 1,
+                    field: TrackField::Volume(0.5),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn versioned_state_serializes_in_camel_case() {
+        let state = StateManager::new();
+        state.update_clip(0, 0, |c| c.is_playing = true);
+        let json = serde_json::to_value(state.snapshot()).unwrap();
+        assert_eq!(json["version"], 3);
+        assert_eq!(json["numTracks"], 1);
+        assert_eq!(json["isPlaying"], false);
+        assert_eq!(json["tracks"][0]["meterLevel"], 0.0);
+        assert_eq!(json["tracks"][0]["clips"][0]["isPlaying"], true);
+        assert_eq!(json["tracks"][0]["clips"][0]["trackIndex"], 0);
+    }
+}
@@ -0,0 +1,735 @@
+//! Undo/redo for operations Maestro sends to the engine.
+//!
+//! Value edits (mixer, tempo, loop, track name and colour, device params)
+//! go through [`Journal::apply`], which reads the current value from
+//! `StateManager` before calling the engine, so undo just sets it back.
+//! Edits made while a transaction is open — e.g. every step of a slider
+//! drag — become one undo step.
+//!
+//! Operations Maestro can't invert itself (track and scene structure, note
+//! edits) are recorded as native steps and undone with the engine's own
+//! undo (`/live/song/undo` for Live).  With nothing journaled, undo and redo
+//! go straight to the engine.
+//!
+//! Maestro's value sets land on the engine's undo stack as well, so once
+//! anything was sent after a native step, the engine's undo would revert
+//! that instead: such steps are refused rather than undoing the wrong
+//! thing.  Changes made in the DAW or from a controller aren't seen by
+//! Maestro and can still get in between.
+
+use std::sync::{Arc, Mutex};
+
+use crate::engine::events::TrackField;
+use crate::engine::{EngineAdapter, EngineState, StateManager};
+
+/// Oldest steps are dropped beyond this.
+const MAX_UNDO_STEPS: usize = 200;
+
+/// A reversible engine operation: one value set on one target.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Edit {
+    Tempo(f64),
+    Loop(bool),
+    TrackVolume(i32, f64),
+    TrackPan(i32, f64),
+    TrackMute(i32, bool),
+    TrackSolo(i32, bool),
+    TrackArm(i32, bool),
+    TrackSend(i32, i32, f64),
+    TrackName(i32, String),
+    TrackColor(i32, u32),
+    ReturnVolume(i32, f64),
+    ReturnPan(i32, f64),
+    MasterVolume(f64),
+    MasterPan(f64),
+    CustomParam(String, f32),
+}
+
+impl Edit {
+    /// The same target's current value, or `None` if it isn't known.
+    pub fn current(&self, state: &EngineState, engine: &dyn EngineAdapter) -> Option<Edit> {
+        let track = |i: &i32| state.tracks.get(usize::try_from(*i).ok()?);
+        let ret = |i: &i32| state.return_tracks.get(usize::try_from(*i).ok()?);
+        Some(match self {
+            // 0 BPM means the engine hasn't reported a tempo yet.
+            Self::Tempo(_) => Self::Tempo(Some(state.tempo).filter(|t| *t > 0.0)?),
+            Self::Loop(_) => Self::Loop(state.loop_enabled),
+            Self::TrackVolume(i, _) => Self::TrackVolume(*i, track(i)?.volume),
+            Self::TrackPan(i, _) => Self::TrackPan(*i, track(i)?.panning),
+            Self::TrackMute(i, _) => Self::TrackMute(*i, track(i)?.mute),
+            Self::TrackSolo(i, _) => Self::TrackSolo(*i, track(i)?.solo),
+            Self::TrackArm(i, _) => Self::TrackArm(*i, track(i)?.arm),
+            Self::TrackSend(i, s, _) => {
+                let level = *track(i)?.sends.get(usize::try_from(*s).ok()?)?;
+                Self::TrackSend(*i, *s, level)
+            }
+            Self::TrackName(i, _) => Self::TrackName(*i, track(i)?.name.clone()),
+            Self::TrackColor(i, _) => Self::TrackColor(*i, track(i)?.color),
+            Self::ReturnVolume(i, _) => Self::ReturnVolume(*i, ret(i)?.volume),
+            Self::ReturnPan(i, _) => Self::ReturnPan(*i, ret(i)?.panning),
+            Self::MasterVolume(_) => Self::MasterVolume(state.master.volume),
+            Self::MasterPan(_) => Self::MasterPan(state.master.panning),
+            Self::CustomParam(id, _) => {
+                let params = engine.custom_params();
+                let value = params.iter().find(|p| p.id == *id)?.value?;
+                Self::CustomParam(id.clone(), value)
+            }
+        })
+    }
+
+    fn apply(&self, engine: &dyn EngineAdapter) -> Result<(), String> {
+        match self {
+            Self::Tempo(bpm) => engine.set_tempo(*bpm as f32),
+            Self::Loop(on) => engine.set_loop_enabled(*on),
+            Self::TrackVolume(i, v) => engine.set_track_volume(*i, *v as f32),
+            Self::TrackPan(i, v) => engine.set_track_pan(*i, *v as f32),
+            Self::TrackMute(i, on) => engine.set_track_mute(*i, *on),
+            Self::TrackSolo(i, on) => engine.set_track_solo(*i, *on),
+            Self::TrackArm(i, on) => engine.set_track_arm(*i, *on),
+            Self::TrackSend(i, s, v) => engine.set_track_send(*i, *s, *v as f32),
+            Self::TrackName(i, name) => engine.rename_track(*i, name),
+            Self::TrackColor(i, color) => engine.set_track_color(*i, *color),
+            Self::ReturnVolume(i, v) => engine.set_return_track_volume(*i, *v as f32),
+            Self::ReturnPan(i, v) => engine.set_return_track_pan(*i, *v as f32),
+            Self::MasterVolume(v) => engine.set_master_volume(*v as f32),
+            Self::MasterPan(v) => engine.set_master_pan(*v as f32),
+            Self::CustomParam(id, v) => engine.set_custom_param(id, *v),
+        }
+    }
+
+    /// Write the value into `state`, for engines that don't report it back.
+    /// Negative indices address nothing and are skipped.
+    fn mirror(&self, state: &StateManager) {
+        let index = |i: &i32| usize::try_from(*i).ok();
+        let track = |i: &i32, field: TrackField| {
+            if let Some(i) = index(i) {
+                state.set_track_field(i, field);
+            }
+        };
+        match self {
+            Self::Tempo(bpm) => state.set_tempo(*bpm),
+            Self::Loop(on) => state.set_loop_enabled(*on),
+            Self::TrackVolume(i, v) => track(i, TrackField::Volume(*v)),
+            Self::TrackPan(i, v) => track(i, TrackField::Pan(*v)),
+            Self::TrackMute(i, on) => track(i, TrackField::Mute(*on)),
+            Self::TrackSolo(i, on) => track(i, TrackField::Solo(*on)),
+            Self::TrackArm(i, on) => track(i, TrackField::Arm(*on)),
+            Self::TrackSend(i, s, v) => {
+                if let Some(s) = index(s) {
+                    track(i, TrackField::Send(s, *v));
+                }
+            }
+            Self::TrackName(i, name) => track(i, TrackField::Name(name.clone())),
+            Self::TrackColor(i, color) => track(i, TrackField::Color(*color)),
+            Self::ReturnVolume(i, v) => {
+                if let Some(i) = index(i) {
+                    state.update_return_track(i, |t| t.volume = *v);
+                }
+            }
+            Self::ReturnPan(i, v) => {
+                if let Some(i) = index(i) {
+                    state.update_return_track(i, |t| t.panning = *v);
+                }
+            }
+            Self::MasterVolume(v) => state.update_master(|t| t.volume = *v),
+            Self::MasterPan(v) => state.update_master(|t| t.panning = *v),
+            // The engine keeps custom param values itself.
+            Self::CustomParam(..) => {}
+        }
+    }
+
+    fn same_target(&self, other: &Edit) -> bool {
+        use Edit::*;
+        match (self, other) {
+            (Tempo(_), Tempo(_))
+            | (Loop(_), Loop(_))
+            | (MasterVolume(_), MasterVolume(_))
+            | (MasterPan(_), MasterPan(_)) => true,
+            (TrackVolume(a, _), TrackVolume(b, _))
+            | (TrackPan(a, _), TrackPan(b, _))
+            | (TrackMute(a, _), TrackMute(b, _))
+            | (TrackSolo(a, _), TrackSolo(b, _))
+            | (TrackArm(a, _), TrackArm(b, _))
+            | (TrackName(a, _), TrackName(b, _))
+            | (TrackColor(a, _), TrackColor(b, _))
+            | (ReturnVolume(a, _), ReturnVolume(b, _))
+            | (ReturnPan(a, _), ReturnPan(b, _)) => a == b,
+            (TrackSend(a, s, _), TrackSend(b, t, _)) => a == b && s == t,
+            (CustomParam(a, _), CustomParam(b, _)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Change {
+    forward: Edit,
+    inverse: Edit,
+}
+
+/// Maestro's view of the engine's own undo and redo stacks: the native
+/// step on top of each, or `None` once anything else went on top.  Value
+/// sets land on those stacks too, so a native step can only be undone
+/// while nothing was sent to the engine after it.
+#[derive(Debug, Clone, Copy, Default)]
+struct EngineStacks {
+    undo: Option<u64>,
+    redo: Option<u64>,
+    next_id: u64,
+}
+
+impl EngineStacks {
+    /// A value was set in the engine: it is on top of the engine's undo
+    /// stack, and the engine's redo stack is gone.
+    fn sent(&mut self) {
+        self.undo = None;
+        self.redo = None;
+    }
+}
+
+#[derive(Debug)]
+struct Native {
+    id: u64,
+    /// The native step under this one on the engine's undo stack.
+    below: Option<u64>,
+    /// The native step under this one on the engine's redo stack, once
+    /// undone.
+    redo_below: Option<u64>,
+}
+
+#[derive(Debug)]
+enum Step {
+    Edits(Vec<Change>),
+    /// Undone and redone by the engine itself.
+    Native(Native),
+    /// Several steps undone as one, e.g. a macro skill.
+    Group(Vec<Step>),
+}
+
+impl Step {
+    /// Whether undoing (or, with `redo`, redoing) the step would make the
+    /// engine undo only this step's own native operations.  Updates
+    /// `stacks` the way running the step would.
+    fn runnable(&self, redo: bool, stacks: &mut EngineStacks) -> bool {
+        match self {
+            Step::Edits(_) => {
+                stacks.sent();
+                true
+            }
+            Step::Native(native) if redo => {
+                let on_top = stacks.redo == Some(native.id);
+                stacks.redo = native.redo_below;
+                stacks.undo = Some(native.id);
+                on_top
+            }
+            Step::Native(native) => {
+                let on_top = stacks.undo == Some(native.id);
+                stacks.undo = native.below;
+                stacks.redo = Some(native.id);
+                on_top
+            }
+            Step::Group(steps) if redo => steps.iter().all(|s| s.runnable(redo, stacks)),
+            Step::Group(steps) => steps.iter().rev().all(|s| s.runnable(redo, stacks)),
+        }
+    }
+
+    fn revert(
+        &mut self,
+        engine: &dyn EngineAdapter,
+        state: &StateManager,
+        stacks: &mut EngineStacks,
+    ) -> Result<(), String> {
+        match self {
+            Step::Edits(changes) => {
+                stacks.sent();
+                changes.iter().rev().try_for_each(|c| {
+                    c.inverse.apply(engine)?;
+                    c.inverse.mirror(state);
+                    Ok(())
+                })
+            }
+            Step::Native(native) => {
+                engine.undo()?;
+                native.redo_below = stacks.redo;
+                stacks.undo = native.below;
+                stacks.redo = Some(native.id);
+                Ok(())
+            }
+            Step::Group(steps) => steps
+                .iter_mut()
+                .rev()
+                .try_for_each(|s| s.revert(engine, state, stacks)),
+        }
+    }
+
+    fn reapply(
+        &mut self,
+        engine: &dyn EngineAdapter,
+        state: &StateManager,
+        stacks: &mut EngineStacks,
+    ) -> Result<(), String> {
+        match self {
+            Step::Edits(changes) => {
+                stacks.sent();
+                changes.iter().try_for_each(|c| {
+                    c.forward.apply(engine)?;
+                    c.forward.mirror(state);
+                    Ok(())
+                })
+            }
+            Step::Native(native) => {
+                engine.redo()?;
+                stacks.redo = native.redo_below;
+                stacks.undo = Some(native.id);
+                Ok(())
+            }
+            Step::Group(steps) => steps
+                .iter_mut()
+                .try_for_each(|s| s.reapply(engine, state, stacks)),
+        }
+    }
+}
+
+#[derive(Default)]
+struct History {
+    undo: Vec<Step>,
+    redo: Vec<Step>,
+    /// Changes of the open transaction, one per target.
+    open: Option<Vec<Change>>,
+    depth: usize,
+    /// A child's history keeps every step until it is adopted.
+    unbounded: bool,
+}
+
+impl History {
+    fn push(&mut self, step: Step) {
+        self.undo.push(step);
+        if !self.unbounded && self.undo.len() > MAX_UNDO_STEPS {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    fn close_transaction(&mut self) {
+        self.depth = 0;
+        if let Some(changes) = self.open.take().filter(|c| !c.is_empty()) {
+            self.push(Step::Edits(changes));
+        }
+    }
+}
+
+/// Undo/redo history of Maestro-issued operations.  Clones share the same
+/// history.
+#[derive(Clone, Default)]
+pub struct Journal {
+    history: Arc<Mutex<History>>,
+    stacks: Arc<Mutex<EngineStacks>>,
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply `edit` through the engine and record how to revert it.
+    pub fn apply(
+        &self,
+        engine: &dyn EngineAdapter,
+        state: &StateManager,
+        edit: Edit,
+    ) -> Result<(), String> {
+        let inverse = edit.current(&state.get(), engine);
+        self.send(engine, state, edit.clone())?;
+
+        let Some(inverse) = inverse else {
+            log::debug!("Not journaling {:?}: previous value unknown", edit);
+            return Ok(());
+        };
+        self.record(edit, inverse);
+        Ok(())
+    }
+
+    /// Apply `edit` through the engine without journaling it, e.g. for
+    /// automation playback.
+    pub fn send(
+        &self,
+        engine: &dyn EngineAdapter,
+        state: &StateManager,
+        edit: Edit,
+    ) -> Result<(), String> {
+        edit.apply(engine)?;
+        edit.mirror(state);
+        self.stacks.lock().unwrap().sent();
+        Ok(())
+    }
+
+    /// Journal `edit`, already sent outside the journal (e.g. by a ramp),
+    /// as undone by `inverse`.
+    pub fn record(&self, edit: Edit, inverse: Edit) {
+        let change = Change {
+            forward: edit,
+            inverse,
+        };
+        let mut history = self.history.lock().unwrap();
+        match history.open.as_mut() {
+            Some(open) => {
+                // Within a transaction, keep the first inverse per target.
+                match open.iter_mut().find(|c| c.forward.same_target(&change.forward)) {
+                    Some(existing) => existing.forward = change.forward,
+                    None => open.push(change),
+                }
+                history.redo.clear();
+            }
+            None => history.push(Step::Edits(vec![change])),
+        }
+    }
+
+    /// Record an operation only the engine's own undo can revert.
+    pub fn record_native(&self) {
+        let mut history = self.history.lock().unwrap();
+        history.close_transaction();
+        let mut stacks = self.stacks.lock().unwrap();
+        stacks.next_id += 1;
+        let native = Native {
+            id: stacks.next_id,
+            below: stacks.undo,
+            redo_below: None,
+        };
+        stacks.undo = Some(native.id);
+        stacks.redo = None;
+        history.push(Step::Native(native));
+    }
+
+    /// Group the following edits into one undo step until the matching
+    /// [`commit`](Self::commit).  Transactions nest.
+    pub fn begin(&self) {
+        let mut history = self.history.lock().unwrap();
+        history.depth += 1;
+        history.open.get_or_insert_with(Vec::new);
+    }
+
+    pub fn commit(&self) {
+        let mut history = self.history.lock().unwrap();
+        match history.depth {
+            0 => {}
+            1 => history.close_transaction(),
+            _ => history.depth -= 1,
+        }
+    }
+
+    /// Undo the last step.  A step with a native operation that other
+    /// changes were sent on top of can't be undone safely: the engine
+    /// would revert those instead.  It is dropped with an error, and the
+    /// engine's own undo is left to the user.
+    pub fn undo(&self, engine: &dyn EngineAdapter, state: &StateManager) -> Result<(), String> {
+        let mut history = self.history.lock().unwrap();
+        history.close_transaction();
+        let mut stacks = self.stacks.lock().unwrap();
+        let Some(mut step) = history.undo.pop() else {
+            stacks.sent();
+            return engine.undo();
+        };
+        if !step.runnable(false, &mut stacks.clone()) {
+            return Err(format!(
+                "Can't undo that from Maestro: other changes were made in {0} since. \
+                 Use {0}'s own undo.",
+                engine.name()
+            ));
+        }
+        let result = step.revert(engine, state, &mut stacks);
+        match result {
+            Ok(()) => history.redo.push(step),
+            Err(_) => history.undo.push(step),
+        }
+        result
+    }
+
+    /// Redo the last undone step, with the same caveat as [`undo`].
+    ///
+    /// [`undo`]: Self::undo
+    pub fn redo(&self, engine: &dyn EngineAdapter, state: &StateManager) -> Result<(), String> {
+        let mut history = self.history.lock().unwrap();
+        history.close_transaction();
+        let mut stacks = self.stacks.lock().unwrap();
+        let Some(mut step) = history.redo.pop() else {
+            stacks.sent();
+            return engine.redo();
+        };
+        if !step.runnable(true, &mut stacks.clone()) {
+            return Err(format!(
+                "Can't redo that from Maestro: other changes were made in {} since.",
+                engine.name()
+            ));
+        }
+        let result = step.reapply(engine, state, &mut stacks);
+        match result {
+            Ok(()) => history.undo.push(step),
+            Err(_) => history.redo.push(step),
+        }
+        result
+    }
+
+    /// A journal of its own for a run of edits that may have to be rolled
+    /// back, e.g. a macro skill.  It shares this journal's view of the
+    /// engine, so native steps stay in order with everything else sent.
+    /// [`adopt`](Self::adopt) it to keep its steps as one undo step, or
+    /// [`rollback`](Self::rollback) it.
+    pub fn child(&self) -> Journal {
+        Journal {
+            history: Arc::new(Mutex::new(History {
+                unbounded: true,
+                ..History::default()
+            })),
+            stacks: Arc::clone(&self.stacks),
+        }
+    }
+
+    /// How many steps were recorded so far.
+    pub fn steps(&self) -> usize {
+        let history = self.history.lock().unwrap();
+        history.undo.len() + usize::from(history.open.as_ref().is_some_and(|c| !c.is_empty()))
+    }
+
+    /// Move every step of `child` into this journal as one undo step.
+    pub fn adopt(&self, child: &Journal) {
+        let mut steps = {
+            let mut child = child.history.lock().unwrap();
+            child.close_transaction();
+            std::mem::take(&mut child.undo)
+        };
+        let step = match steps.len() {
+            0 => return,
+            1 => steps.pop().unwrap(),
+            _ => Step::Group(steps),
+        };
+        let mut history = self.history.lock().unwrap();
+        history.close_transaction();
+        history.push(step);
+    }
+
+    /// Revert and forget every step recorded so far, last first.  Native
+    /// steps that other changes were sent on top of are left in place;
+    /// their positions (in recording order) are returned.
+    pub fn rollback(
+        &self,
+        engine: &dyn EngineAdapter,
+        state: &StateManager,
+    ) -> Result<Vec<usize>, String> {
+        let mut history = self.history.lock().unwrap();
+        history.close_transaction();
+        let mut stacks = self.stacks.lock().unwrap();
+        let mut kept = Vec::new();
+        while let Some(mut step) = history.undo.pop() {
+            if step.runnable(false, &mut stacks.clone()) {
+                step.revert(engine, state, &mut stacks)?;
+            } else {
+                kept.push(history.undo.len());
+            }
+        }
+        kept.reverse();
+        Ok(kept)
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tauri::AppHandle;
+
+    /// Logs every engine call.
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl Recorder {
+        fn log(&self, call: String) -> Result<(), String> {
+            self.0.lock().unwrap().push(call);
+            Ok(())
+        }
+
+        fn take(&self) -> Vec<String> {
+            std::mem::take(&mut self.0.lock().unwrap())
+        }
+    }
+
+    impl EngineAdapter for Recorder {
+        fn name(&self) -> &'static str {
+            "Recorder"
+        }
+        fn start(&self, _app: AppHandle, _state_manager: StateManager) {}
+        fn play(&self) -> Result<(), String> {
+            self.log("play".into())
+        }
+        fn stop(&self) -> Result<(), String> {
+            self.log("stop".into())
+        }
+        fn toggle_record(&self) -> Result<(), String> {
+            self.log("record".into())
+        }
+        fn set_tempo(&self, bpm: f32) -> Result<(), String> {
+            self.log(format!("tempo {bpm}"))
+        }
+        fn set_loop_enabled(&self, enabled: bool) -> Result<(), String> {
+            self.log(format!("loop {enabled}"))
+        }
+        fn set_track_volume(&self, track_index: i32, volume: f32) -> Result<(), String> {
+            self.log(format!("volume {track_index} {volume}"))
+        }
+        fn set_track_mute(&self, track_index: i32, mute: bool) -> Result<(), String> {
+            self.log(format!("mute {track_index} {mute}"))
+        }
+        fn set_track_solo(&self, track_index: i32, solo: bool) -> Result<(), String> {
+            self.log(format!("solo {track_index} {solo}"))
+        }
+        fn set_track_pan(&self, track_index: i32, pan: f32) -> Result<(), String> {
+            self.log(format!("pan {track_index} {pan}"))
+        }
+        fn set_track_send(&self, track_index: i32, send: i32, value: f32) -> Result<(), String> {
+            self.log(format!("send {track_index} {send} {value}"))
+        }
+        fn set_return_track_volume(&self, index: i32, volume: f32) -> Result<(), String> {
+            self.log(format!("return volume {index} {volume}"))
+        }
+        fn load_sample(&self, _track: i32, _scene: i32, _path: &str) -> Result<(), String> {
+            self.log("load_sample".into())
+        }
+        fn undo(&self) -> Result<(), String> {
+            self.log("native undo".into())
+        }
+    }
+
+    fn setup() -> (Journal, Recorder, StateManager) {
+        let state = StateManager::new();
+        state.set_track_count(2);
+        state.set_track_volume(1, 0.5);
+        (Journal::new(), Recorder::default(), state)
+    }
+
+    #[test]
+    fn undo_restores_the_previous_value_and_redo_reapplies() {
+        let (journal, engine, state) = setup();
+        journal.apply(&engine, &state, Edit::TrackVolume(1, 0.75)).unwrap();
+        assert_eq!(state.get().tracks[1].volume, 0.75);
+
+        journal.undo(&engine, &state).unwrap();
+        assert_eq!(state.get().tracks[1].volume, 0.5);
+        journal.redo(&engine, &state).unwrap();
+        assert_eq!(
+            engine.take(),
+            ["volume 1 0.75", "volume 1 0.5", "volume 1 0.75"]
+        );
+    }
+
+    #[test]
+    fn a_transaction_is_one_undo_step() {
+        let (journal, engine, state) = setup();
+        journal.begin();
+        for v in [0.6, 0.7, 0.8] {
+            journal.apply(&engine, &state, Edit::TrackVolume(1, v)).unwrap();
+        }
+        journal.apply(&engine, &state, Edit::TrackMute(0, true)).unwrap();
+        journal.commit();
+        engine.take();
+
+        journal.undo(&engine, &state).unwrap();
+        assert_eq!(engine.take(), ["mute 0 false", "volume 1 0.5"]);
+        // The journal is empty now, so undo falls back to the engine.
+        journal.undo(&engine, &state).unwrap();
+        assert_eq!(engine.take(), ["native undo"]);
+    }
+
+    #[test]
+    fn adopted_children_undo_together_and_rollback_reverts() {
+        let (journal, engine, state) = setup();
+        let child = journal.child();
+        child.apply(&engine, &state, Edit::TrackMute(0, true)).unwrap();
+        child.record_native();
+        journal.adopt(&child);
+        assert_eq!(child.steps(), 0);
+        engine.take();
+
+        journal.undo(&engine, &state).unwrap();
+        assert_eq!(engine.take(), ["native undo", "mute 0 false"]);
+
+        let child = journal.child();
+        child.apply(&engine, &state, Edit::TrackVolume(1, 0.9)).unwrap();
+        // Edits made outside the child meanwhile aren't rolled back.
+        journal.apply(&engine, &state, Edit::TrackPan(0, 0.3)).unwrap();
+        assert_eq!(child.steps(), 1);
+        assert_eq!(child.rollback(&engine, &state).unwrap(), Vec::<usize>::new());
+        assert_eq!(state.get().tracks[1].volume, 0.5);
+        assert_eq!(state.get().tracks[0].panning, 0.3);
+        // Rolled-back steps are gone.
+        assert_eq!(child.steps(), 0);
+    }
+
+    #[test]
+    fn children_keep_every_step_and_report_stale_native_ones() {
+        let (journal, engine, state) = setup();
+        state.set_tempo(120.0);
+        let child = journal.child();
+        child.record_native();
+        for i in 0..MAX_UNDO_STEPS {
+            child.apply(&engine, &state, Edit::Tempo(60.0 + i as f64)).unwrap();
+        }
+        assert_eq!(child.steps(), MAX_UNDO_STEPS + 1);
+        engine.take();
+
+        // The tempo steps went onto the engine's stack above the native one.
+        assert_eq!(child.rollback(&engine, &state).unwrap(), [0]);
+        let calls = engine.take();
+        assert_eq!(calls.len(), MAX_UNDO_STEPS);
+        assert!(!calls.contains(&"native undo".to_string()));
+    }
+
+    #[test]
+    fn native_steps_use_the_engine_undo_and_new_edits_clear_redo() {
+        let (journal, engine, state) = setup();
+        journal.apply(&engine, &state, Edit::Loop(true)).unwrap();
+        journal.record_native();
+        engine.take();
+
+        journal.undo(&engine, &state).unwrap();
+        assert_eq!(engine.take(), ["native undo"]);
+
+        journal.apply(&engine, &state, Edit::Loop(true)).unwrap();
+        // Recorder has no native redo, and there is nothing left to redo.
+        assert!(journal.redo(&engine, &state).is_err());
+    }
+
+    #[test]
+    fn native_steps_with_sets_on_top_are_refused() {
+        let (journal, engine, state) = setup();
+        journal.apply(&engine, &state, Edit::Loop(true)).unwrap();
+        journal.record_native();
+        journal.apply(&engine, &state, Edit::TrackVolume(1, 0.9)).unwrap();
+        engine.take();
+
+        journal.undo(&engine, &state).unwrap();
+        assert_eq!(engine.take(), ["volume 1 0.5"]);
+        // The volume undo went onto the engine's stack above the native step.
+        let refused = journal.undo(&engine, &state).unwrap_err();
+        assert!(refused.starts_with("Can't undo that from Maestro"), "{refused}");
+        journal.undo(&engine, &state).unwrap();
+        assert_eq!(engine.take(), ["loop false"]);
+
+        journal.record_native();
+        journal.undo(&engine, &state).unwrap();
+        journal.send(&engine, &state, Edit::TrackPan(0, 0.2)).unwrap();
+        engine.take();
+        let refused = journal.redo(&engine, &state).unwrap_err();
+        assert!(refused.starts_with("Can't redo that from Maestro"), "{refused}");
+        assert!(engine.take().is_empty());
+    }
+
+    #[test]
+    fn negative_indices_are_not_mirrored() {
+        let (journal, engine, state) = setup();
+        journal.apply(&engine, &state, Edit::TrackSend(0, -1, 0.5)).unwrap();
+        journal.apply(&engine, &state, Edit::TrackVolume(-1, 0.5)).unwrap();
+        journal.apply(&engine, &state, Edit::ReturnVolume(-1, 0.5)).unwrap();
+        assert!(state.get().tracks[0].sends.is_empty());
+        assert_eq!(engine.take().len(), 3);
+    }
+}
@@ -0,0 +1,578 @@
+//! Keyboard shortcuts: key sequences mapped to skill invocations.
+//!
+//! Defaults are overridden by `~/.config/maestro/keymap.toml`:
+//!
+//! ```toml
+//! leader = "Space"              # what `<leader>` stands for (default `\`)
+//! unbind = ["Ctrl+L"]           # drop default bindings
+//!
+//! [[bind]]
+//! keys   = "<leader> m d"       # chords separated by spaces
+//! skill  = "track.mute"
+//! params = { track = 0 }        # every required param of the skill
+//! ```
+//!
+//! A chord is `Ctrl`/`Alt`/`Shift`/`Meta` modifiers plus one key, named as
+//! in `KeyboardEvent.key` (`Space` for the space bar).  When two bindings
+//! clash — the same keys, or one a prefix of the other, which would make
+//! the shorter one unreachable — the later one wins and the clash is
+//! reported in [`EffectiveKeymap::conflicts`].  User bindings come after the
+//! defaults, so they always win over them.
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::skills::{Params, SkillDescriptor};
+
+/// How long a partly typed sequence waits for its next chord.
+pub const SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+const DEFAULT_LEADER: &str = "\\";
+
+const DEFAULTS: &[(&str, &str)] = &[
+    ("Space", "transport.play"),
+    ("Shift+Space", "transport.stop"),
+    ("Ctrl+R", "transport.record"),
+    ("Ctrl+L", "transport.loop"),
+    ("Ctrl+Z", "utility.undo"),
+    ("Ctrl+Shift+Z", "utility.redo"),
+    ("Ctrl+Y", "utility.redo"),
+    ("<leader> t", "track.create"),
+];
+
+pub fn keymap_path() -> PathBuf {
+    crate::config::config_dir().join("keymap.toml")
+}
+
+// ── Keys ───────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyChord {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+    pub key: String,
+}
+
+impl KeyChord {
+    /// Parse `Ctrl+Shift+K`-style text.  Modifier and key names are
+    /// case-insensitive.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        // `+` and `Ctrl++` bind the plus key.
+        let (mods, key) = match text.strip_suffix('+') {
+            Some(mods) if mods.is_empty() || mods.ends_with('+') => (mods, "+"),
+            _ => text.rsplit_once('+').unwrap_or(("", text)),
+        };
+        let mut chord = KeyChord {
+            ctrl: false,
+            alt: false,
+            shift: false,
+            meta: false,
+            key: normalize_key(key).ok_or_else(|| format!("unknown key '{key}' in '{text}'"))?,
+        };
+        for m in mods.split('+').filter(|m| !m.is_empty()) {
+            let flag = match m.to_lowercase().as_str() {
+                "ctrl" | "control" => &mut chord.ctrl,
+                "alt" | "option" => &mut chord.alt,
+                "shift" => &mut chord.shift,
+                "meta" | "cmd" | "super" | "win" => &mut chord.meta,
+                _ => return Err(format!("unknown modifier '{m}' in '{text}'")),
+            };
+            *flag = true;
+        }
+        Ok(chord)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (on, name) in [
+            (self.ctrl, "Ctrl+"),
+            (self.alt, "Alt+"),
+            (self.shift, "Shift+"),
+            (self.meta, "Meta+"),
+        ] {
+            if on {
+                f.write_str(name)?;
+            }
+        }
+        f.write_str(&self.key)
+    }
+}
+
+const NAMED_KEYS: &[&str] = &[
+    "Space",
+    "Enter",
+    "Escape",
+    "Tab",
+    "Backspace",
+    "Delete",
+    "Insert",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+    "ArrowUp",
+    "ArrowDown",
+    "ArrowLeft",
+    "ArrowRight",
+];
+
+/// Canonical key name: single characters upper-cased, named keys as in
+/// [`NAMED_KEYS`] (plus a few aliases) and `F1`–`F24`.
+fn normalize_key(key: &str) -> Option<String> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return (!c.is_whitespace()).then(|| c.to_uppercase().collect());
+    }
+    let lower = key.to_lowercase();
+    let alias = match lower.as_str() {
+        "esc" => "Escape",
+        "return" => "Enter",
+        "del" => "Delete",
+        "up" => "ArrowUp",
+        "down" => "ArrowDown",
+        "left" => "ArrowLeft",
+        "right" => "ArrowRight",
+        _ => "",
+    };
+    if !alias.is_empty() {
+        return Some(alias.into());
+    }
+    if let Some(named) = NAMED_KEYS.iter().find(|k| k.to_lowercase() == lower) {
+        return Some(named.to_string());
+    }
+    let n: u8 = lower.strip_prefix('f')?.parse().ok()?;
+    (1..=24).contains(&n).then(|| format!("F{n}"))
+}
+
+/// Parse space-separated chords, expanding `<leader>`.
+fn parse_sequence(text: &str, leader: &[KeyChord]) -> Result<Vec<KeyChord>, String> {
+    let mut chords = Vec::new();
+    for token in text.split_whitespace() {
+        if token.eq_ignore_ascii_case("<leader>") {
+            chords.extend_from_slice(leader);
+        } else {
+            chords.push(KeyChord::parse(token)?);
+        }
+    }
+    if chords.is_empty() {
+        return Err("empty key sequence".into());
+    }
+    Ok(chords)
+}
+
+fn sequence_text(chords: &[KeyChord]) -> String {
+    chords
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// ── Bindings ───────────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KeymapFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leader: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unbind: Vec<String>,
+    #[serde(default)]
+    pub bind: Vec<BindSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BindSpec {
+    pub keys: String,
+    pub skill: String,
+    #[serde(default, skip_serializing_if = "Params::is_empty")]
+    pub params: Params,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BindingSource {
+    Default,
+    User,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Binding {
+    /// Normalized, with `<leader>` expanded.
+    pub keys: String,
+    pub skill: String,
+    pub params: Params,
+    pub source: BindingSource,
+    #[serde(skip)]
+    chords: Vec<KeyChord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KeyConflict {
+    pub kept: Binding,
+    pub dropped: Binding,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct EffectiveKeymap {
+    pub leader: String,
+    pub bindings: Vec<Binding>,
+    pub conflicts: Vec<KeyConflict>,
+    /// Invalid entries of the keymap file, which were skipped.
+    pub errors: Vec<String>,
+}
+
+impl EffectiveKeymap {
+    /// Resolve the defaults plus `file` against the skills that exist.
+    pub fn resolve(file: &KeymapFile, skills: &[SkillDescriptor]) -> Self {
+        let mut map = Self::default();
+        let leader = file.leader.as_deref().unwrap_or(DEFAULT_LEADER);
+        let leader = parse_sequence(leader, &[]).unwrap_or_else(|e| {
+            map.errors.push(format!("leader: {e}"));
+            parse_sequence(DEFAULT_LEADER, &[]).unwrap()
+        });
+        map.leader = sequence_text(&leader);
+
+        for (keys, skill) in DEFAULTS {
+            let chords = parse_sequence(keys, &leader).expect("default keys parse");
+            map.add(chords, skill, Params::new(), BindingSource::Default);
+        }
+
+        for keys in &file.unbind {
+            match parse_sequence(keys, &leader) {
+                Ok(chords) => {
+                    let before = map.bindings.len();
+                    map.bindings.retain(|b| b.chords != chords);
+                    if map.bindings.len() == before {
+                        map.errors
+                            .push(format!("unbind '{keys}': nothing is bound to it"));
+                    }
+                }
+                Err(e) => map.errors.push(format!("unbind '{keys}': {e}")),
+            }
+        }
+
+        for spec in &file.bind {
+            let checked = parse_sequence(&spec.keys, &leader).and_then(|chords| {
+                let skill = skills
+                    .iter()
+                    .find(|s| s.id == spec.skill)
+                    .ok_or_else(|| format!("unknown skill '{}'", spec.skill))?;
+                if let Some(k) = spec
+                    .params
+                    .keys()
+                    .find(|k| !skill.params.iter().any(|p| &p.name == *k))
+                {
+                    return Err(format!("'{}' has no parameter '{}'", skill.id, k));
+                }
+                // A key press has nowhere to ask for a missing value.
+                match skill
+                    .params
+                    .iter()
+                    .find(|p| p.required && !spec.params.contains_key(&p.name))
+                {
+                    Some(p) => Err(format!("'{}' requires parameter '{}'", skill.id, p.name)),
+                    None => Ok(chords),
+                }
+            });
+            match checked {
+                Ok(chords) => map.add(
+                    chords,
+                    &spec.skill,
+                    spec.params.clone(),
+                    BindingSource::User,
+                ),
+                Err(e) => map.errors.push(format!("bind '{}': {}", spec.keys, e)),
+            }
+        }
+        map
+    }
+
+    fn add(&mut self, chords: Vec<KeyChord>, skill: &str, params: Params, source: BindingSource) {
+        let binding = Binding {
+            keys: sequence_text(&chords),
+            skill: skill.into(),
+            params,
+            source,
+            chords,
+        };
+        let clashes = |b: &Binding| {
+            b.chords.starts_with(&binding.chords) || binding.chords.starts_with(&b.chords)
+        };
+        let (dropped, kept): (Vec<_>, Vec<_>) = self.bindings.drain(..).partition(clashes);
+        self.bindings = kept;
+        self.conflicts
+            .extend(dropped.into_iter().map(|dropped| KeyConflict {
+                kept: binding.clone(),
+                dropped,
+            }));
+        self.bindings.push(binding);
+    }
+
+    /// The keys of the first parameterless binding for `skill`.
+    pub fn shortcut_for(&self, skill: &str) -> Option<String> {
+        self.bindings
+            .iter()
+            .find(|b| b.skill == skill && b.params.is_empty())
+            .map(|b| b.keys.clone())
+    }
+
+    /// The bindings as a keymap file, e.g. as a starting point for edits.
+    pub fn to_file(&self) -> KeymapFile {
+        KeymapFile {
+            leader: Some(self.leader.clone()),
+            unbind: Vec::new(),
+            bind: self
+                .bindings
+                .iter()
+                .map(|b| BindSpec {
+                    keys: b.keys.clone(),
+                    skill: b.skill.clone(),
+                    params: b.params.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+// ── Key handling ───────────────────────────────────────────────────────────
+
+/// The outcome of one key press.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum KeyPress {
+    /// `binding` completed; run its skill.
+    Run {
+        binding: Binding,
+    },
+    /// `keys` start one or more sequences; waiting for the next chord.
+    Pending {
+        keys: String,
+    },
+    Unbound {
+        keys: String,
+    },
+}
+
+struct Pending {
+    chords: Vec<KeyChord>,
+    at: Instant,
+}
+
+/// The effective keymap plus the sequence typed so far.
+pub struct Keymap {
+    effective: RwLock<EffectiveKeymap>,
+    pending: Mutex<Pending>,
+}
+
+impl Keymap {
+    /// Default bindings only; call [`reload`](Self::reload) to add the
+    /// user's keymap file.
+    pub fn new(skills: &[SkillDescriptor]) -> Self {
+        Self {
+            effective: RwLock::new(EffectiveKeymap::resolve(&KeymapFile::default(), skills)),
+            pending: Mutex::new(Pending {
+                chords: Vec::new(),
+                at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Re-read the keymap file.  Returns (and logs) its errors and conflicts.
+    pub fn reload(&self, skills: &[SkillDescriptor]) -> Vec<String> {
+        let path = keymap_path();
+        let file = match std::fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content).map_err(|e| e.to_string()),
+            Err(_) => Ok(KeymapFile::default()),
+        };
+        let map = match file {
+            Ok(file) => EffectiveKeymap::resolve(&file, skills),
+            Err(e) => {
+                let mut map = EffectiveKeymap::resolve(&KeymapFile::default(), skills);
+                map.errors.push(e);
+                map
+            }
+        };
+        let mut problems: Vec<String> = map
+            .errors
+            .iter()
+            .map(|e| format!("{}: {}", path.display(), e))
+            .collect();
+        problems.extend(
+            map.conflicts
+                .iter()
+                .filter(|c| c.dropped.source == BindingSource::User)
+                .map(|c| {
+                    format!(
+                        "'{}' ({}) replaces '{}' ({})",
+                        c.kept.keys, c.kept.skill, c.dropped.keys, c.dropped.skill
+                    )
+                }),
+        );
+        for p in &problems {
+            log::warn!("Keymap: {}", p);
+        }
+        log::info!(
+            "Keymap: {} binding(s), leader {}",
+            map.bindings.len(),
+            map.leader
+        );
+        *self.effective.write().unwrap() = map;
+        problems
+    }
+
+    pub fn effective(&self) -> EffectiveKeymap {
+        self.effective.read().unwrap().clone()
+    }
+
+    /// Feed one chord (e.g. `Ctrl+Z`) into the current sequence.
+    pub fn press(&self, chord: &str) -> Result<KeyPress, String> {
+        self.press_at(KeyChord::parse(chord)?, Instant::now())
+    }
+
+    fn press_at(&self, chord: KeyChord, now: Instant) -> Result<KeyPress, String> {
+        let map = self.effective.read().unwrap();
+        let mut pending = self.pending.lock().unwrap();
+        if now.duration_since(pending.at) > SEQUENCE_TIMEOUT {
+            pending.chords.clear();
+        }
+        pending.at = now;
+
+        // A chord that doesn't continue the pending sequence starts a new one.
+        let mut candidates = [pending.chords.clone(), Vec::new()];
+        for typed in &mut candidates {
+            typed.push(chord.clone());
+            let mut matches = map.bindings.iter().filter(|b| b.chords.starts_with(typed));
+            let Some(first) = matches.next() else {
+                continue;
+            };
+            if first.chords == *typed {
+                pending.chords.clear();
+                return Ok(KeyPress::Run {
+                    binding: first.clone(),
+                });
+            }
+            pending.chords = typed.clone();
+            return Ok(KeyPress::Pending {
+                keys: sequence_text(typed),
+            });
+        }
+        pending.chords.push(chord);
+        let keys = sequence_text(&pending.chords);
+        pending.chords.clear();
+        Ok(KeyPress::Unbound { keys })
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skills::builtin;
+
+    fn resolve(toml: &str) -> EffectiveKeymap {
+        EffectiveKeymap::resolve(&toml::from_str(toml).unwrap(), &builtin::descriptors())
+    }
+
+    #[test]
+    fn chords_normalize() {
+        let chord = KeyChord::parse("shift+CTRL+z").unwrap();
+        assert_eq!(chord.to_string(), "Ctrl+Shift+Z");
+        assert_eq!(KeyChord::parse("Ctrl++").unwrap().key, "+");
+        assert_eq!(KeyChord::parse("+").unwrap().to_string(), "+");
+        assert!(KeyChord::parse("Ctrl+").is_err());
+        assert_eq!(KeyChord::parse("esc").unwrap().key, "Escape");
+        assert_eq!(KeyChord::parse("f12").unwrap().key, "F12");
+        assert!(KeyChord::parse("Hyper+K").is_err());
+        assert!(KeyChord::parse("F25").is_err());
+    }
+
+    #[test]
+    fn user_bindings_override_and_clashes_are_reported() {
+        let map = resolve(
+            r#"
+            leader = "Space"
+            unbind = ["Ctrl+Y"]
+
+            [[bind]]
+            keys = "<leader> m"
+            skill = "track.mute"
+            params = { track = 0 }
+
+            [[bind]]
+            keys = "ctrl+z"
+            skill = "utility.redo"
+
+            [[bind]]
+            keys = "x"
+            skill = "nope"
+
+            [[bind]]
+            keys = "<leader> d"
+            skill = "track.duplicate"
+            "#,
+        );
+        assert_eq!(map.leader, "Space");
+        let keys: Vec<_> = map.bindings.iter().map(|b| b.keys.as_str()).collect();
+        assert!(keys.contains(&"Space M") && !keys.contains(&"Ctrl+Y"));
+        // `Space` (play) is a prefix of `Space M`, so it is dropped.
+        assert!(!keys.contains(&"Space"));
+        assert_eq!(
+            map.shortcut_for("utility.redo").as_deref(),
+            Some("Ctrl+Shift+Z")
+        );
+        assert!(map
+            .conflicts
+            .iter()
+            .any(|c| c.dropped.skill == "utility.undo" && c.kept.skill == "utility.redo"));
+        assert_eq!(map.errors.len(), 2);
+        assert!(map.errors[0].contains("unknown skill 'nope'"));
+        assert!(map.errors[1].contains("requires parameter 'track'"));
+    }
+
+    #[test]
+    fn defaults_run_without_params() {
+        let skills = builtin::descriptors();
+        for (keys, id) in DEFAULTS {
+            let skill = skills.iter().find(|s| &s.id == id).unwrap();
+            assert!(!skill.params.iter().any(|p| p.required), "{keys}: {id}");
+        }
+    }
+
+    #[test]
+    fn sequences_resolve_across_presses_and_time_out() {
+        let keymap = Keymap::new(&builtin::descriptors());
+        let t0 = Instant::now();
+        let press =
+            |key: &str, at: Duration| keymap.press_at(KeyChord::parse(key).unwrap(), t0 + at);
+
+        assert_eq!(
+            press("\\", Duration::ZERO).unwrap(),
+            KeyPress::Pending { keys: "\\".into() }
+        );
+        match press("t", Duration::from_millis(100)).unwrap() {
+            KeyPress::Run { binding } => assert_eq!(binding.skill, "track.create"),
+            other => panic!("{other:?}"),
+        }
+
+        press("\\", Duration::from_secs(1)).unwrap();
+        let late = press("t", Duration::from_secs(3)).unwrap();
+        assert_eq!(late, KeyPress::Unbound { keys: "T".into() });
+
+        // An unbound continuation restarts from the new chord.
+        press("\\", Duration::from_secs(4)).unwrap();
+        match press("Ctrl+Z", Duration::from_secs(4)).unwrap() {
+            KeyPress::Run { binding } => assert_eq!(binding.skill, "utility.undo"),
+            other => panic!("{other:?}"),
+        }
+    }
+}
@@ -1,17 +1,30 @@
+mod automation;
 mod commands;
+mod config;
 mod engine;
+mod journal;
+mod keymap;
 mod osc;
+mod palette;
+mod ramp;
+mod scripting;
 mod sidecar;
+mod skills;
+mod snapshot;
+mod workspace;
 
 use std::sync::Arc;
 
 use tauri::Manager;
 
-use engine::{EngineAdapter, EngineKind, StateManager};
+use engine::{midi::MidiEngine, EngineAdapter, EngineKind, StateManager};
 use osc::{adapter::AbletonOscEngine, client::OscClient};
 use sidecar::api::SidecarClient;
 
 const ABLETON_OSC_TARGET_PORT: u16 = 11000;
+const REAPER_OSC_TARGET_PORT: u16 = 8000;
+const ARDOUR_OSC_TARGET_PORT: u16 = 3819;
+const BITWIG_OSC_TARGET_PORT: u16 = 8000;
 const SIDECAR_PORT: u16 = 9400;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -19,6 +32,8 @@ pub fn run() {
     let engine_kind = EngineKind::from_env();
     let state_manager = StateManager::new();
     let sidecar_client = SidecarClient::new(SIDECAR_PORT);
+    let skill_registry = skills::SkillRegistry::new();
+    let workspaces = workspace::Workspaces::new(workspace::workspaces_dir());
 
     // Build the engine adapter selected by the MAESTRO_ENGINE env var.
     // Defaults to AbletonOsc when the variable is unset.
@@ -28,7 +43,23 @@ pub fn run() {
                 .expect("Failed to create OSC client");
             Arc::new(AbletonOscEngine::new(osc_client))
         }
-        EngineKind::Zrythm => Arc::new(engine::zrythm::ZrythmEngine::new()),
+        EngineKind::Zrythm => Arc::new(MidiEngine::zrythm()),
+        EngineKind::Midi => Arc::new(MidiEngine::new()),
+        EngineKind::Reaper => {
+            let osc_client = OscClient::new(REAPER_OSC_TARGET_PORT)
+                .expect("Failed to create OSC client");
+            Arc::new(engine::reaper::ReaperEngine::new(osc_client))
+        }
+        EngineKind::Ardour => {
+            let osc_client = OscClient::new(ARDOUR_OSC_TARGET_PORT)
+                .expect("Failed to create OSC client");
+            Arc::new(engine::ardour::ArdourEngine::new(osc_client))
+        }
+        EngineKind::Bitwig => {
+            let osc_client = OscClient::new(BITWIG_OSC_TARGET_PORT)
+                .expect("Failed to create OSC client");
+            Arc::new(engine::bitwig::BitwigEngine::new(osc_client))
+        }
         EngineKind::Mock => Arc::new(engine::mock::MockEngine),
     };
 
@@ -49,9 +80,27 @@ pub fn run() {
 
                 log::info!("Starting Maestro with engine: {}", engine.name());
 
+                let skills = app.handle().state::<skills::SkillRegistry>();
+                skills.reload_macros();
+                app.handle().state::<keymap::Keymap>().reload(&skills.list());
+
+                // Forward state changes to the frontend.
+                tauri::async_runtime::spawn(engine::events::bridge(
+                    app.handle().clone(),
+                    state_manager.clone(),
+                    state_manager.subscribe(),
+                    engine::events::frame_rate_from_env(),
+                ));
+
                 // Start the engine: launch listeners and subscribe to state updates.
                 engine.start(app.handle().clone(), state_manager.clone());
 
+                // Follow the engine's project and autosave its workspace.
+                tauri::async_runtime::spawn(workspace::watch(app.handle().clone()));
+
+                // Play back automation lanes in sync with the transport.
+                tauri::async_runtime::spawn(automation::follow(app.handle().clone()));
+
                 // Start polling for sidecar readiness in background.
                 let handle = app.handle().clone();
                 tauri::async_runtime::spawn(async move {
@@ -64,24 +113,99 @@ pub fn run() {
         .manage(engine)
         .manage(state_manager)
         .manage(sidecar_client)
+        .manage(journal::Journal::new())
+        .manage(keymap::Keymap::new(&skill_registry.list()))
+        .manage(skill_registry)
+        .manage(palette::Palette::new())
+        .manage(commands::snapshots::MorphGeneration::default())
+        .manage(ramp::Ramps::default())
+        .manage(automation::Automation::new(workspaces.clone()))
+        .manage(workspaces)
         .invoke_handler(tauri::generate_handler![
             // Transport
             commands::transport::play,
             commands::transport::stop,
             commands::transport::toggle_record,
             commands::transport::set_tempo,
+            commands::transport::set_song_time,
             commands::transport::toggle_loop,
             commands::transport::get_transport_state,
+            commands::state::get_engine_state,
+            // Undo
+            commands::journal::undo,
+            commands::journal::redo,
+            commands::journal::begin_transaction,
+            commands::journal::commit_transaction,
             // Tracks
             commands::tracks::set_track_volume,
             commands::tracks::set_track_mute,
             commands::tracks::set_track_solo,
             commands::tracks::set_track_pan,
+            commands::tracks::set_track_send,
+            commands::tracks::set_return_track_volume,
+            commands::tracks::set_return_track_pan,
+            commands::tracks::set_master_volume,
+            commands::tracks::set_master_pan,
+            commands::tracks::create_track,
+            commands::tracks::delete_track,
+            commands::tracks::duplicate_track,
+            commands::tracks::rename_track,
+            commands::tracks::set_track_color,
+            commands::tracks::set_track_arm,
+            // Clips
+            commands::clips::fire_clip,
+            commands::clips::stop_clip,
+            // Notes
+            commands::notes::get_clip_notes,
+            commands::notes::add_notes,
+            commands::notes::remove_notes,
+            commands::notes::transform_clip_notes,
+            commands::notes::import_clip_midi,
+            commands::notes::export_clip_midi,
+            // Scenes
+            commands::scenes::get_scenes,
+            commands::scenes::fire_scene,
+            commands::scenes::stop_scene,
+            commands::scenes::create_scene,
+            commands::scenes::duplicate_scene,
+            commands::scenes::delete_scene,
             // Search
             commands::search::search_by_text,
             commands::search::search_by_similarity,
             commands::search::index_directory,
             commands::search::insert_sample,
+            // Skills
+            commands::skills::list_skills,
+            commands::skills::execute_skill,
+            commands::skills::reload_skills,
+            // Keymap
+            commands::keymap::press_key,
+            commands::keymap::reload_keymap,
+            commands::keymap::export_keymap,
+            // Snapshots
+            commands::snapshots::list_snapshots,
+            commands::snapshots::save_snapshot,
+            commands::snapshots::recall_snapshot,
+            commands::snapshots::morph_snapshots,
+            // Workspace
+            commands::workspace::get_workspace,
+            commands::workspace::open_workspace,
+            commands::workspace::set_selected_tracks,
+            // Automation
+            commands::automation::get_automation,
+            commands::automation::set_automation_recording,
+            commands::automation::set_automation_playback,
+            commands::automation::clear_automation,
+            // Ramps
+            commands::ramps::start_ramp,
+            commands::ramps::cancel_ramp,
+            commands::ramps::list_ramps,
+            // Command palette
+            commands::palette::palette_query,
+            commands::palette::palette_record_use,
+            // Scripts
+            commands::scripts::list_scripts,
+            commands::scripts::run_script,
             // Engine params
             commands::params::get_engine_params,
             commands::params::set_engine_param,
@@ -1,23 +1,85 @@
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+
+use rosc::OscType;
 use tauri::AppHandle;
 
-use crate::engine::{EngineAdapter, StateManager};
+use crate::engine::{EngineAdapter, Note, NoteRegion, ParamDef, StateManager, TrackKind};
+use crate::osc::devices::{self, DeviceRegistry};
+use crate::osc::notes::NoteReplies;
 use crate::osc::{client::OscClient, listener, messages::OscMessages};
 
+/// How long `get_clip_notes` waits for Live to answer.
+const NOTE_REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Ableton Live engine adapter using the AbletonOSC control surface.
 ///
 /// Communicates with Ableton Live via the AbletonOSC MIDI Remote Script:
 /// - **Send** commands on UDP port 11000
 /// - **Receive** state updates on UDP port 11001
 ///
+/// Every device parameter on every track is exposed through `custom_params`
+/// (see [`devices`]).  Note queries wait for Live's reply (see
+/// [`crate::osc::notes`]).
+///
 /// References:
 /// - <https://github.com/ideoforms/AbletonOSC>
 pub struct AbletonOscEngine {
-    client: OscClient,
+    client: Arc<OscClient>,
+    devices: Arc<DeviceRegistry>,
+    notes: Arc<NoteReplies>,
+    /// Set in `start`; track edits are mirrored into it and `stop_scene`
+    /// reads the track count from it.
+    state: OnceLock<StateManager>,
 }
 
 impl AbletonOscEngine {
     pub fn new(client: OscClient) -> Self {
-        Self { client }
+        Self {
+            client: Arc::new(client),
+            devices: Arc::new(DeviceRegistry::new()),
+            notes: Arc::new(NoteReplies::new()),
+            state: OnceLock::new(),
+        }
+    }
+
+    fn send(&self, (addr, args): (&str, Vec<OscType>)) -> Result<(), String> {
+        self.client.send(addr, args).map_err(|e| e.to_string())
+    }
+
+    /// Apply a scene list change, then re-read the scene list so
+    /// `StateManager` indices match Live's.
+    fn edit_scenes(&self, msg: (&str, Vec<OscType>)) -> Result<(), String> {
+        self.send(msg)?;
+        self.send(OscMessages::get_num_scenes())
+    }
+
+    /// The `StateManager` index of Live track `track_index`.
+    fn slot(track_index: i32) -> Result<usize, String> {
+        usize::try_from(track_index).map_err(|_| format!("Invalid track index {track_index}"))
+    }
+
+    /// Apply a track list change in Live and mirror it in `StateManager`,
+    /// then re-read the track list so names, clips and devices follow the
+    /// new indices.
+    fn edit_tracks(
+        &self,
+        msg: (&str, Vec<OscType>),
+        mirror: impl FnOnce(&StateManager),
+    ) -> Result<(), String> {
+        self.send(msg)?;
+        if let Some(state) = self.state.get() {
+            mirror(state);
+        }
+        self.send(OscMessages::get_num_tracks())
+    }
+
+    /// Start (or restart) device discovery; replies are handled by the listener.
+    fn discover_devices(&self) {
+        let (addr, args) = OscMessages::get_num_tracks();
+        if let Err(e) = self.client.send(addr, args) {
+            log::warn!("Failed to start Ableton device discovery: {}", e);
+        }
     }
 }
 
@@ -27,8 +89,16 @@ impl EngineAdapter for AbletonOscEngine {
     }
 
     fn start(&self, app: AppHandle, state_manager: StateManager) {
+        let _ = self.state.set(state_manager.clone());
+
         // Start the OSC listener thread (receives from AbletonOSC on port 11001)
-        listener::start_listener(app.clone(), state_manager);
+        listener::start_listener(
+            app.clone(),
+            state_manager,
+            Arc::clone(&self.client),
+            Arc::clone(&self.devices),
+            Arc::clone(&self.notes),
+        );
 
         // Send a test ping — AbletonOSC responds with /live/test confirming connection
         let (addr, args) = OscMessages::test();
@@ -40,12 +110,32 @@ impl EngineAdapter for AbletonOscEngine {
         let subscriptions = [
             OscMessages::start_listen_tempo(),
             OscMessages::start_listen_is_playing(),
+            OscMessages::start_listen_song_time(),
         ];
         for (addr, args) in subscriptions {
             if let Err(e) = self.client.send(addr, args) {
                 log::warn!("Failed to subscribe to Ableton update '{}': {}", addr, e);
             }
         }
+
+        self.discover_devices();
+        if let Err(e) = self.send(OscMessages::get_num_scenes()) {
+            log::warn!("Failed to query Ableton scenes: {}", e);
+        }
+
+        // Return and master tracks (AbletonOSC extension — see `messages`).
+        let mixer = [
+            OscMessages::get_num_return_tracks(),
+            OscMessages::get_master_volume(),
+            OscMessages::get_master_panning(),
+            OscMessages::start_listen_master_volume(),
+            OscMessages::start_listen_master_panning(),
+        ];
+        for msg in mixer {
+            if let Err(e) = self.send(msg) {
+                log::warn!("Failed to query Ableton return/master tracks: {}", e);
+            }
+        }
     }
 
     // ── Transport ───────────────────────────────────────────────────────────
@@ -75,6 +165,11 @@ impl EngineAdapter for AbletonOscEngine {
         self.client.send(addr, args).map_err(|e| e.to_string())
     }
 
+    fn set_song_time(&self, beats: f64) -> Result<(), String> {
+        let (addr, args) = OscMessages::set_song_time(beats as f32);
+        self.client.send(addr, args).map_err(|e| e.to_string())
+    }
+
     // ── Tracks ──────────────────────────────────────────────────────────────
 
     fn set_track_volume(&self, track_index: i32, volume: f32) -> Result<(), String> {
@@ -97,6 +192,172 @@ impl EngineAdapter for AbletonOscEngine {
         self.client.send(addr, args).map_err(|e| e.to_string())
     }
 
+    // ── Sends, returns and master ───────────────────────────────────────────
+
+    fn set_track_send(&self, track_index: i32, send_index: i32, value: f32) -> Result<(), String> {
+        self.send(OscMessages::set_track_send(track_index, send_index, value))
+    }
+
+    fn set_return_track_volume(&self, return_index: i32, volume: f32) -> Result<(), String> {
+        self.send(OscMessages::set_return_track_volume(return_index, volume))
+    }
+
+    fn set_return_track_pan(&self, return_index: i32, pan: f32) -> Result<(), String> {
+        self.send(OscMessages::set_return_track_panning(return_index, pan))
+    }
+
+    fn set_master_volume(&self, volume: f32) -> Result<(), String> {
+        self.send(OscMessages::set_master_volume(volume))
+    }
+
+    fn set_master_pan(&self, pan: f32) -> Result<(), String> {
+        self.send(OscMessages::set_master_panning(pan))
+    }
+
+    // ── Track structure ─────────────────────────────────────────────────────
+
+    fn create_track(&self, kind: TrackKind, track_index: i32) -> Result<(), String> {
+        // Live keeps return tracks in a separate list Maestro doesn't mirror.
+        if kind == TrackKind::Return {
+            return self.send(OscMessages::create_return_track());
+        }
+        let msg = match kind {
+            TrackKind::Midi => OscMessages::create_midi_track(track_index),
+            _ => OscMessages::create_audio_track(track_index),
+        };
+        self.edit_tracks(msg, |state| {
+            let index = usize::try_from(track_index).unwrap_or(usize::MAX);
+            state.insert_track(index);
+        })
+    }
+
+    fn delete_track(&self, track_index: i32) -> Result<(), String> {
+        let index = Self::slot(track_index)?;
+        self.edit_tracks(OscMessages::delete_track(track_index), |state| {
+            state.remove_track(index)
+        })
+    }
+
+    fn duplicate_track(&self, track_index: i32) -> Result<(), String> {
+        let index = Self::slot(track_index)?;
+        self.edit_tracks(OscMessages::duplicate_track(track_index), |state| {
+            state.insert_track(index + 1)
+        })
+    }
+
+    fn rename_track(&self, track_index: i32, name: &str) -> Result<(), String> {
+        let index = Self::slot(track_index)?;
+        self.send(OscMessages::set_track_name(track_index, name))?;
+        if let Some(state) = self.state.get() {
+            state.set_track_name(index, name.to_string());
+        }
+        Ok(())
+    }
+
+    fn set_track_color(&self, track_index: i32, color: u32) -> Result<(), String> {
+        let index = Self::slot(track_index)?;
+        self.send(OscMessages::set_track_color(track_index, color))?;
+        if let Some(state) = self.state.get() {
+            state.set_track_color(index, color);
+        }
+        Ok(())
+    }
+
+    fn set_track_arm(&self, track_index: i32, arm: bool) -> Result<(), String> {
+        let index = Self::slot(track_index)?;
+        self.send(OscMessages::set_track_arm(track_index, arm))?;
+        if let Some(state) = self.state.get() {
+            state.set_track_arm(index, arm);
+        }
+        Ok(())
+    }
+
+    // ── Clips ───────────────────────────────────────────────────────────────
+
+    fn fire_clip(&self, track_index: i32, scene_index: i32) -> Result<(), String> {
+        let (addr, args) = OscMessages::fire_clip(track_index, scene_index);
+        self.client.send(addr, args).map_err(|e| e.to_string())
+    }
+
+    fn stop_clip(&self, track_index: i32, scene_index: i32) -> Result<(), String> {
+        let (addr, args) = OscMessages::stop_clip(track_index, scene_index);
+        self.client.send(addr, args).map_err(|e| e.to_string())
+    }
+
+    fn create_clip(&self, track_index: i32, scene_index: i32, length: f64) -> Result<(), String> {
+        let index = Self::slot(track_index)?;
+        let scene =
+            usize::try_from(scene_index).map_err(|_| format!("Invalid scene index {scene_index}"))?;
+        self.send(OscMessages::create_clip(track_index, scene_index, length as f32))?;
+        if let Some(state) = self.state.get() {
+            state.set_clip_length(index, scene, length);
+        }
+        Ok(())
+    }
+
+    // ── Notes ───────────────────────────────────────────────────────────────
+
+    fn get_clip_notes(&self, track_index: i32, scene_index: i32) -> Result<Vec<Note>, String> {
+        let reply = self.notes.expect(track_index, scene_index);
+        self.send(OscMessages::get_clip_notes(track_index, scene_index))?;
+        reply.recv_timeout(NOTE_REPLY_TIMEOUT).map_err(|_| {
+            format!(
+                "No notes received for clip {}/{} (is there a MIDI clip in the slot?)",
+                track_index, scene_index
+            )
+        })
+    }
+
+    fn add_notes(&self, track_index: i32, scene_index: i32, notes: &[Note]) -> Result<(), String> {
+        self.send(OscMessages::add_clip_notes(track_index, scene_index, notes))
+    }
+
+    fn remove_notes(
+        &self,
+        track_index: i32,
+        scene_index: i32,
+        region: Option<NoteRegion>,
+    ) -> Result<(), String> {
+        self.send(OscMessages::remove_clip_notes(track_index, scene_index, region))
+    }
+
+    // ── Scenes ──────────────────────────────────────────────────────────────
+
+    fn fire_scene(&self, scene_index: i32) -> Result<(), String> {
+        self.send(OscMessages::fire_scene(scene_index))
+    }
+
+    /// Live has no per-scene stop, so stop the scene's slot on every track.
+    fn stop_scene(&self, scene_index: i32) -> Result<(), String> {
+        let num_tracks = self.state.get().map(|s| s.get().num_tracks).unwrap_or(0);
+        for track in 0..num_tracks as i32 {
+            self.send(OscMessages::stop_clip(track, scene_index))?;
+        }
+        Ok(())
+    }
+
+    fn create_scene(&self, scene_index: i32) -> Result<(), String> {
+        self.edit_scenes(OscMessages::create_scene(scene_index))
+    }
+
+    fn duplicate_scene(&self, scene_index: i32) -> Result<(), String> {
+        self.edit_scenes(OscMessages::duplicate_scene(scene_index))
+    }
+
+    fn delete_scene(&self, scene_index: i32) -> Result<(), String> {
+        self.edit_scenes(OscMessages::delete_scene(scene_index))
+    }
+
+    // ── Undo ────────────────────────────────────────────────────────────────
+
+    fn undo(&self) -> Result<(), String> {
+        self.send(OscMessages::undo())
+    }
+
+    fn redo(&self) -> Result<(), String> {
+        self.send(OscMessages::redo())
+    }
+
     // ── Media ───────────────────────────────────────────────────────────────
 
     fn load_sample(
@@ -108,4 +369,24 @@ impl EngineAdapter for AbletonOscEngine {
         let (addr, args) = OscMessages::load_sample(track_index, scene_index, file_path);
         self.client.send(addr, args).map_err(|e| e.to_string())
     }
+    // ── Custom params ───────────────────────────────────────────────────────
+
+    /// Device parameters discovered on every track.  If discovery hasn't
+    /// produced anything yet (Live wasn't running at start), kick it off
+    /// again; `engine-params-changed` fires once the replies arrive.
+    fn custom_params(&self) -> Vec<ParamDef> {
+        if self.devices.is_empty() {
+            self.discover_devices();
+        }
+        self.devices.param_defs()
+    }
+
+    fn set_custom_param(&self, id: &str, value: f32) -> Result<(), String> {
+        let (track, device, param) = devices::parse_param_id(id)
+            .ok_or_else(|| format!("Unknown custom param id: '{id}'"))?;
+        let (addr, args) = OscMessages::set_device_parameter_value(track, device, param, value);
+        self.client.send(addr, args).map_err(|e| e.to_string())?;
+        self.devices.set_value(track, device, param, value);
+        Ok(())
+    }
 }
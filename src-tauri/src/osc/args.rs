@@ -0,0 +1,58 @@
+//! Lenient OSC argument accessors.
+//!
+//! DAWs disagree on argument types for the same value (Reaper sends floats
+//! for toggles, Ardour ints, Bitwig sometimes strings), so feedback handlers
+//! read arguments through these instead of matching a single `OscType`.
+
+use rosc::OscType;
+
+pub fn as_f64(arg: &OscType) -> Option<f64> {
+    match arg {
+        OscType::Float(f) => Some(*f as f64),
+        OscType::Double(d) => Some(*d),
+        OscType::Int(i) => Some(*i as f64),
+        OscType::Long(l) => Some(*l as f64),
+        OscType::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+pub fn as_i32(arg: &OscType) -> Option<i32> {
+    match arg {
+        OscType::Int(i) => Some(*i),
+        OscType::Long(l) => i32::try_from(*l).ok(),
+        OscType::Float(f) => Some(f.round() as i32),
+        OscType::Double(d) => Some(d.round() as i32),
+        OscType::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+pub fn as_bool(arg: &OscType) -> Option<bool> {
+    match arg {
+        OscType::Bool(b) => Some(*b),
+        other => as_f64(other).map(|v| v >= 0.5),
+    }
+}
+
+pub fn as_str(arg: &OscType) -> Option<&str> {
+    match arg {
+        OscType::String(s) => Some(s),
+        _ => None,
+    }
+}
+
+/// First argument of a message as `f64`.
+pub fn first_f64(args: &[OscType]) -> Option<f64> {
+    args.first().and_then(as_f64)
+}
+
+/// First argument of a message as `bool`.
+pub fn first_bool(args: &[OscType]) -> Option<bool> {
+    args.first().and_then(as_bool)
+}
+
+/// First argument of a message as a string slice.
+pub fn first_str(args: &[OscType]) -> Option<&str> {
+    args.first().and_then(as_str)
+}
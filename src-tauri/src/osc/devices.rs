@@ -0,0 +1,262 @@
+//! Device and parameter discovery for AbletonOSC.
+//!
+//! The adapter asks Live for its track count, then walks each track's
+//! device chain:
+//!
+//! 1. `/live/song/get/num_tracks` → `/live/track/get/devices/name` per track
+//!    (sent by the listener, which also tracks the track count)
+//! 2. → `/live/device/get/parameters/{name,min,max,value}` per device
+//!
+//! Replies fill a [`DeviceRegistry`], which backs the adapter's
+//! `custom_params`.  Parameter ids are `track/<t>/device/<d>/param/<p>`,
+//! using AbletonOSC's 0-based indices.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use rosc::OscType;
+
+use crate::engine::ParamDef;
+use crate::osc::{args, messages::OscMessages};
+
+#[derive(Debug, Default)]
+pub struct DeviceParam {
+    pub name: String,
+    pub min: f32,
+    pub max: f32,
+    pub value: Option<f32>,
+}
+
+#[derive(Debug, Default)]
+pub struct Device {
+    pub name: String,
+    pub params: Vec<DeviceParam>,
+}
+
+/// What the listener should do after a device reply.
+#[derive(Debug, PartialEq)]
+pub enum Update {
+    /// Send these follow-up queries to Live.
+    Query(Vec<(&'static str, Vec<OscType>)>),
+    /// Parameter names, ranges or values changed.
+    Params,
+}
+
+/// Devices discovered on each track, keyed by `(track, device)`.
+#[derive(Default)]
+pub struct DeviceRegistry {
+    devices: Mutex<BTreeMap<(i32, i32), Device>>,
+}
+
+impl DeviceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.devices.lock().unwrap().is_empty()
+    }
+
+    /// Apply a discovery reply.  Returns `None` for messages the listener
+    /// should handle itself.
+    pub fn handle(&self, addr: &str, osc_args: &[OscType]) -> Option<Update> {
+        match addr {
+            "/live/song/get/num_tracks" => {
+                // Drop devices of deleted tracks; the listener re-lists the rest.
+                let n = osc_args.first().and_then(args::as_i32)?;
+                self.devices.lock().unwrap().retain(|(t, _), _| *t < n);
+                None
+            }
+            "/live/track/get/devices/name" => {
+                let track = osc_args.first().and_then(args::as_i32)?;
+                let names: Vec<&str> = osc_args[1..].iter().filter_map(args::as_str).collect();
+                Some(self.set_track_devices(track, &names))
+            }
+            "/live/device/get/parameter/value" => {
+                let [t, d, p, v] = osc_args else {
+                    return None;
+                };
+                let (t, d, p) = (args::as_i32(t)?, args::as_i32(d)?, args::as_i32(p)?);
+                self.set_value(t, d, p, args::as_f64(v)? as f32);
+                Some(Update::Params)
+            }
+            _ => {
+                let field = addr.strip_prefix("/live/device/get/parameters/")?;
+                let (t, d) = match osc_args {
+                    [t, d, ..] => (args::as_i32(t)?, args::as_i32(d)?),
+                    _ => return None,
+                };
+                self.set_params_field(t, d, field, &osc_args[2..])?;
+                Some(Update::Params)
+            }
+        }
+    }
+
+    /// Replace the device list of `track` and query each device's parameters.
+    fn set_track_devices(&self, track: i32, names: &[&str]) -> Update {
+        let mut devices = self.devices.lock().unwrap();
+        devices.retain(|(t, _), _| *t != track);
+        let mut queries = Vec::new();
+        for (d, name) in names.iter().enumerate() {
+            let d = d as i32;
+            devices.insert(
+                (track, d),
+                Device {
+                    name: name.to_string(),
+                    params: vec![],
+                },
+            );
+            queries.push(OscMessages::get_device_parameter_names(track, d));
+            queries.push(OscMessages::get_device_parameter_mins(track, d));
+            queries.push(OscMessages::get_device_parameter_maxs(track, d));
+            queries.push(OscMessages::get_device_parameter_values(track, d));
+        }
+        if queries.is_empty() {
+            // The track's devices were removed — nothing left to query.
+            Update::Params
+        } else {
+            Update::Query(queries)
+        }
+    }
+
+    /// Fill one field of every parameter of a device from a bulk reply.
+    fn set_params_field(
+        &self,
+        track: i32,
+        device: i32,
+        field: &str,
+        values: &[OscType],
+    ) -> Option<()> {
+        let mut devices = self.devices.lock().unwrap();
+        let params = &mut devices.get_mut(&(track, device))?.params;
+        if params.len() < values.len() {
+            params.resize_with(values.len(), DeviceParam::default);
+        }
+        for (param, v) in params.iter_mut().zip(values) {
+            match field {
+                "name" => param.name = args::as_str(v).unwrap_or_default().to_string(),
+                "min" => param.min = args::as_f64(v).unwrap_or(0.0) as f32,
+                "max" => param.max = args::as_f64(v).unwrap_or(1.0) as f32,
+                "value" => param.value = args::as_f64(v).map(|v| v as f32),
+                _ => return None,
+            }
+        }
+        Some(())
+    }
+
+    /// Record a value Maestro just sent, so `custom_params` reflects it.
+    pub fn set_value(&self, track: i32, device: i32, param: i32, value: f32) {
+        let mut devices = self.devices.lock().unwrap();
+        if let Some(p) = devices
+            .get_mut(&(track, device))
+            .and_then(|d| d.params.get_mut(param as usize))
+        {
+            p.value = Some(value);
+        }
+    }
+
+    /// One `ParamDef` per discovered parameter, ordered by track and device.
+    pub fn param_defs(&self) -> Vec<ParamDef> {
+        let devices = self.devices.lock().unwrap();
+        devices
+            .iter()
+            .flat_map(|(&(t, d), device)| {
+                device
+                    .params
+                    .iter()
+                    .enumerate()
+                    .map(move |(p, param)| ParamDef {
+                        id: param_id(t, d, p as i32),
+                        label: format!("Track {} / {}: {}", t + 1, device.name, param.name),
+                        min: param.min,
+                        max: param.max,
+                        value: param.value,
+                    })
+            })
+            .collect()
+    }
+}
+
+pub fn param_id(track: i32, device: i32, param: i32) -> String {
+    format!("track/{track}/device/{device}/param/{param}")
+}
+
+/// Inverse of [`param_id`].
+pub fn parse_param_id(id: &str) -> Option<(i32, i32, i32)> {
+    let rest = id.strip_prefix("track/")?;
+    let (t, rest) = rest.split_once("/device/")?;
+    let (d, p) = rest.split_once("/param/")?;
+    Some((t.parse().ok()?, d.parse().ok()?, p.parse().ok()?))
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(v: &str) -> OscType {
+        OscType::String(v.into())
+    }
+
+    #[test]
+    fn device_list_queries_each_device() {
+        let registry = DeviceRegistry::new();
+
+        let Some(Update::Query(q)) = registry.handle(
+            "/live/track/get/devices/name",
+            &[OscType::Int(1), s("EQ Eight"), s("Reverb")],
+        ) else {
+            panic!("expected parameter queries");
+        };
+        assert_eq!(q.len(), 8);
+        assert_eq!(q[4], OscMessages::get_device_parameter_names(1, 1));
+    }
+
+    #[test]
+    fn parameter_replies_become_param_defs() {
+        let registry = DeviceRegistry::new();
+        registry.handle(
+            "/live/track/get/devices/name",
+            &[OscType::Int(0), s("Operator")],
+        );
+
+        let reply = |field: &str, values: Vec<OscType>| {
+            let mut a = vec![OscType::Int(0), OscType::Int(0)];
+            a.extend(values);
+            registry.handle(&format!("/live/device/get/parameters/{field}"), &a)
+        };
+        assert_eq!(
+            reply("name", vec![s("Device On"), s("Filter Freq")]),
+            Some(Update::Params)
+        );
+        reply("min", vec![OscType::Float(0.0), OscType::Float(30.0)]);
+        reply("max", vec![OscType::Float(1.0), OscType::Float(18500.0)]);
+        reply("value", vec![OscType::Float(1.0), OscType::Float(440.0)]);
+
+        let defs = registry.param_defs();
+        assert_eq!(defs.len(), 2);
+        assert_eq!(defs[1].id, "track/0/device/0/param/1");
+        assert_eq!(defs[1].label, "Track 1 / Operator: Filter Freq");
+        assert_eq!(
+            (defs[1].min, defs[1].max, defs[1].value),
+            (30.0, 18500.0, Some(440.0))
+        );
+
+        registry.set_value(0, 0, 1, 1000.0);
+        assert_eq!(registry.param_defs()[1].value, Some(1000.0));
+
+        // Re-listing the track's devices drops the old parameters.
+        assert_eq!(
+            registry.handle("/live/track/get/devices/name", &[OscType::Int(0)]),
+            Some(Update::Params)
+        );
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn param_ids_round_trip() {
+        assert_eq!(parse_param_id(&param_id(3, 1, 12)), Some((3, 1, 12)));
+        assert_eq!(parse_param_id("track/3/trim"), None);
+    }
+}
@@ -0,0 +1,105 @@
+//! Shared plumbing for OSC engines that report state through feedback
+//! messages (Reaper, Ardour, Bitwig): each adapter applies a message to the
+//! `StateManager` and returns a [`Change`] saying what it touched.  The
+//! `StateManager` publishes the changes themselves.
+
+use crate::engine::{ClipState, StateManager};
+
+/// Tracks a feedback message may address.  Feedback arrives from the
+/// network, and every track up to the one addressed is allocated.
+pub const MAX_TRACKS: usize = 1024;
+
+/// Scenes a clip feedback message may address, for the same reason.
+pub const MAX_SCENES: usize = 1024;
+
+/// What a feedback message changed.
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    Transport,
+    Tempo(f64),
+    SongTime(f64),
+    Tracks,
+    /// One clip slot changed.
+    Clip(ClipState),
+    /// The engine reported the open project's name.
+    Project(String),
+    None,
+}
+
+/// A mixer value reported for one track.
+#[derive(Debug)]
+pub enum TrackValue<'a> {
+    /// Normalized fader position, 0–1.
+    Volume(f64),
+    /// −1 (left) to 1 (right).
+    Pan(f64),
+    Mute(bool),
+    Solo(bool),
+    Arm(bool),
+    Name(&'a str),
+    /// `0xRRGGBB`.
+    Color(u32),
+}
+
+/// Split `/track/<n>/<param>` into a 0-based track index and the parameter
+/// path.  Reaper and DrivenByMoss both number tracks from 1.  Tracks past
+/// [`MAX_TRACKS`] are ignored.
+pub fn parse_track_addr(addr: &str) -> Option<(usize, &str)> {
+    let rest = addr.strip_prefix("/track/")?;
+    let (num, param) = rest.split_once('/')?;
+    let n: usize = num.parse().ok()?;
+    let index = n.checked_sub(1).filter(|i| *i < MAX_TRACKS)?;
+    Some((index, param))
+}
+
+/// Maestro's 0-based `index` as the 1-based number these surfaces address
+/// tracks, sends and scenes by.  Negative indices are refused: `/track/0`
+/// is Reaper's master track.
+pub fn one_based(index: i32, what: &str) -> Result<i32, String> {
+    usize::try_from(index)
+        .ok()
+        .and(index.checked_add(1))
+        .ok_or_else(|| format!("Invalid {what} index {index}"))
+}
+
+/// Store `value` on track `index`, growing the track list if the engine
+/// reports a track Maestro hasn't seen yet (up to [`MAX_TRACKS`]).
+pub fn apply_track_value(state: &StateManager, index: usize, value: TrackValue) -> Change {
+    if index >= MAX_TRACKS {
+        return Change::None;
+    }
+    state.ensure_track(index);
+    match value {
+        TrackValue::Volume(v) => state.set_track_volume(index, v),
+        TrackValue::Pan(p) => state.set_track_pan(index, p),
+        TrackValue::Mute(m) => state.set_track_mute(index, m),
+        TrackValue::Solo(s) => state.set_track_solo(index, s),
+        TrackValue::Arm(a) => state.set_track_arm(index, a),
+        TrackValue::Name(n) => state.set_track_name(index, n.to_string()),
+        TrackValue::Color(c) => state.set_track_color(index, c),
+    }
+    Change::Tracks
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn out_of_range_tracks_are_ignored() {
+        assert_eq!(parse_track_addr("/track/4000000000/name"), None);
+        assert_eq!(
+            parse_track_addr(&format!("/track/{MAX_TRACKS}/name")),
+            Some((MAX_TRACKS - 1, "name"))
+        );
+
+        let state = StateManager::new();
+        let change = apply_track_value(&state, MAX_TRACKS, TrackValue::Name("x"));
+        assert_eq!(change, Change::None);
+        assert!(state.get().tracks.is_empty());
+        apply_track_value(&state, 2, TrackValue::Mute(true));
+        assert_eq!(state.get().tracks.len(), 3);
+    }
+}
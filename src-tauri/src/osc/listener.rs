@@ -1,27 +1,75 @@
-use rosc::{OscPacket, OscType};
+use rosc::{OscMessage, OscPacket, OscType};
 use std::net::UdpSocket;
+use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
 
-use crate::engine::StateManager;
+use crate::engine::{StateManager, TrackState};
+use crate::osc::args as osc_args;
+use crate::osc::client::OscClient;
+use crate::osc::devices::{DeviceRegistry, Update};
+use crate::osc::messages::OscMessages;
+use crate::osc::notes::NoteReplies;
 
-pub fn start_listener(app: AppHandle, state_manager: StateManager) {
+/// Port AbletonOSC sends replies and listener updates to.
+const ABLETON_OSC_LISTEN_PORT: u16 = 11001;
+
+/// Start the AbletonOSC listener.  Replies that need follow-up queries
+/// (track and scene counts, device lists) are answered through `client`;
+/// note replies go to whoever is waiting on `notes`.
+pub fn start_listener(
+    app: AppHandle,
+    state_manager: StateManager,
+    client: Arc<OscClient>,
+    devices: Arc<DeviceRegistry>,
+    notes: Arc<NoteReplies>,
+) {
+    listen(ABLETON_OSC_LISTEN_PORT, move |msg| {
+        if notes.handle(&msg.addr, &msg.args) {
+            return;
+        }
+        match devices.handle(&msg.addr, &msg.args) {
+            Some(Update::Query(queries)) => send_all(&client, queries),
+            Some(Update::Params) => {
+                let _ = app.emit("engine-params-changed", ());
+            }
+            None => handle_message(&app, &state_manager, &client, &msg.addr, &msg.args),
+        }
+    });
+}
+
+fn send_all(client: &OscClient, queries: impl IntoIterator<Item = (&'static str, Vec<OscType>)>) {
+    for (addr, args) in queries {
+        if let Err(e) = client.send(addr, args) {
+            log::warn!("Failed to query Ableton '{}': {}", addr, e);
+        }
+    }
+}
+
+/// Bind a UDP socket on `port` in a background thread and call `on_message`
+/// for every OSC message received.  Bundles are flattened.
+///
+/// Shared by all OSC-based engine adapters.
+pub fn listen<F>(port: u16, on_message: F)
+where
+    F: Fn(&OscMessage) + Send + 'static,
+{
     std::thread::spawn(move || {
-        let socket = match UdpSocket::bind("0.0.0.0:11001") {
+        let socket = match UdpSocket::bind(("0.0.0.0", port)) {
             Ok(s) => s,
             Err(e) => {
-                log::error!("Failed to bind OSC listener on port 11001: {}", e);
+                log::error!("Failed to bind OSC listener on port {}: {}", port, e);
                 return;
             }
         };
 
-        log::info!("OSC listener started on port 11001");
+        log::info!("OSC listener started on port {}", port);
         let mut buf = [0u8; 65535];
 
         loop {
             match socket.recv_from(&mut buf) {
                 Ok((size, _addr)) => {
                     if let Ok((_remaining, packet)) = rosc::decoder::decode_udp(&buf[..size]) {
-                        handle_packet(&app, &state_manager, &packet);
+                        handle_packet(&packet, &on_message);
                     }
                 }
                 Err(e) => {
@@ -32,29 +80,29 @@ pub fn start_listener(app: AppHandle, state_manager: StateManager) {
     });
 }
 
-fn handle_packet(app: &AppHandle, state: &StateManager, packet: &OscPacket) {
+fn handle_packet<F: Fn(&OscMessage)>(packet: &OscPacket, on_message: &F) {
     match packet {
-        OscPacket::Message(msg) => {
-            handle_message(app, state, &msg.addr, &msg.args);
-        }
+        OscPacket::Message(msg) => on_message(msg),
         OscPacket::Bundle(bundle) => {
             for p in &bundle.content {
-                handle_packet(app, state, p);
+                handle_packet(p, on_message);
             }
         }
     }
 }
 
-fn handle_message(app: &AppHandle, state: &StateManager, addr: &str, args: &[OscType]) {
+fn handle_message(
+    app: &AppHandle,
+    state: &StateManager,
+    client: &OscClient,
+    addr: &str,
+    args: &[OscType],
+) {
     match addr {
         "/live/song/get/is_playing" => {
             if let Some(OscType::Int(val)) = args.first() {
                 let playing = *val != 0;
                 state.set_playing(playing);
-                let _ = app.emit(
-                    "transport-state",
-                    serde_json::json!({ "is_playing": playing }),
-                );
             }
         }
         "/live/song/get/tempo" => {
@@ -65,7 +113,6 @@ fn handle_message(app: &AppHandle, state: &StateManager, addr: &str, args: &[Osc
                     _ => return,
                 };
                 state.set_tempo(tempo);
-                let _ = app.emit("tempo-changed", tempo);
             }
         }
         "/live/song/get/current_song_time" => {
@@ -76,7 +123,167 @@ fn handle_message(app: &AppHandle, state: &StateManager, addr: &str, args: &[Osc
                     _ => return,
                 };
                 state.set_current_time(time);
-                let _ = app.emit("song-time", time);
+            }
+        }
+        "/live/song/get/num_tracks" => {
+            if let Some(n) = args.first().and_then(osc_args::as_i32) {
+                let n = n.max(0);
+                state.set_track_count(n as usize);
+                // Walk each track's device chain (see `devices`).
+                send_all(client, (0..n).map(OscMessages::get_track_device_names));
+                // Read and follow each track's properties, fill the clip
+                // grid and follow each track's launch state.
+                send_all(
+                    client,
+                    (0..n).flat_map(|t| {
+                        [
+                            OscMessages::get_track_name(t),
+                            OscMessages::get_track_color(t),
+                            OscMessages::get_track_arm(t),
+                            OscMessages::get_track_volume(t),
+                            OscMessages::get_track_panning(t),
+                            OscMessages::get_track_mute(t),
+                            OscMessages::get_track_solo(t),
+                            OscMessages::start_listen_track_volume(t),
+                            OscMessages::start_listen_track_panning(t),
+                            OscMessages::start_listen_track_mute(t),
+                            OscMessages::start_listen_track_solo(t),
+                            OscMessages::get_track_clip_names(t),
+                            OscMessages::get_track_clip_lengths(t),
+                            OscMessages::get_track_clip_colors(t),
+                            OscMessages::start_listen_playing_slot_index(t),
+                            OscMessages::start_listen_fired_slot_index(t),
+                        ]
+                    }),
+                );
+                let returns = state.get().return_tracks.len() as i32;
+                send_all(client, send_queries(0..n, 0..returns));
+            }
+        }
+        "/live/song/get/num_return_tracks" => {
+            if let Some(n) = args.first().and_then(osc_args::as_i32) {
+                let n = n.max(0);
+                state.set_return_track_count(n as usize);
+                send_all(
+                    client,
+                    (0..n).flat_map(|r| {
+                        [
+                            OscMessages::get_return_track_name(r),
+                            OscMessages::get_return_track_volume(r),
+                            OscMessages::get_return_track_panning(r),
+                            OscMessages::start_listen_return_track_volume(r),
+                            OscMessages::start_listen_return_track_panning(r),
+                        ]
+                    }),
+                );
+                let tracks = state.get().num_tracks as i32;
+                send_all(client, send_queries(0..tracks, 0..n));
+            }
+        }
+        "/live/track/get/send" => {
+            if let [track, send, level] = args {
+                if let (Some(t), Some(s), Some(level)) = (
+                    osc_args::as_i32(track),
+                    osc_args::as_i32(send),
+                    osc_args::as_f64(level),
+                ) {
+                    if let (Ok(t), Ok(s)) = (usize::try_from(t), usize::try_from(s)) {
+                        state.set_track_send(t, s, level);
+                    }
+                }
+            }
+        }
+        "/live/return_track/get/name"
+        | "/live/return_track/get/volume"
+        | "/live/return_track/get/panning" => {
+            if let [index, value] = args {
+                let Some(r) = osc_args::as_i32(index).and_then(|r| usize::try_from(r).ok())
+                else {
+                    return;
+                };
+                state.update_return_track(r, |t| apply_mixer_value(t, addr, value));
+            }
+        }
+        "/live/master_track/get/volume" | "/live/master_track/get/panning" => {
+            if let Some(value) = args.first() {
+                state.update_master(|t| apply_mixer_value(t, addr, value));
+            }
+        }
+        "/live/track/get/name"
+        | "/live/track/get/color"
+        | "/live/track/get/arm"
+        | "/live/track/get/volume"
+        | "/live/track/get/panning"
+        | "/live/track/get/mute"
+        | "/live/track/get/solo" => {
+            if let [track, value] = args {
+                let Some(t) = osc_args::as_i32(track).and_then(|t| usize::try_from(t).ok())
+                else {
+                    return;
+                };
+                match addr.rsplit('/').next() {
+                    Some("name") => {
+                        let name = osc_args::as_str(value).unwrap_or_default();
+                        state.set_track_name(t, name.to_string());
+                    }
+                    Some("color") => {
+                        state.set_track_color(t, osc_args::as_i32(value).unwrap_or(0) as u32);
+                    }
+                    Some("volume") => {
+                        state.set_track_volume(t, osc_args::as_f64(value).unwrap_or(0.0));
+                    }
+                    Some("panning") => {
+                        state.set_track_pan(t, osc_args::as_f64(value).unwrap_or(0.0));
+                    }
+                    Some("mute") => state.set_track_mute(t, osc_args::as_bool(value) == Some(true)),
+                    Some("solo") => state.set_track_solo(t, osc_args::as_bool(value) == Some(true)),
+                    _ => state.set_track_arm(t, osc_args::as_bool(value) == Some(true)),
+                }
+            }
+        }
+        "/live/track/get/clips/name"
+        | "/live/track/get/clips/length"
+        | "/live/track/get/clips/color" => {
+            apply_clip_list(state, addr, args);
+        }
+        "/live/track/get/playing_slot_index" | "/live/track/get/fired_slot_index" => {
+            if let [track, slot] = args {
+                let t = osc_args::as_i32(track).and_then(|t| usize::try_from(t).ok());
+                if let (Some(t), Some(slot)) = (t, osc_args::as_i32(slot)) {
+                    // Negative indices mean no slot (−2: the track's stop button).
+                    let slot = usize::try_from(slot).ok();
+                    if addr.ends_with("playing_slot_index") {
+                        state.set_playing_slot(t, slot);
+                    } else {
+                        state.set_triggered_slot(t, slot);
+                    }
+                }
+            }
+        }
+        "/live/song/get/num_scenes" => {
+            if let Some(n) = args.first().and_then(osc_args::as_i32) {
+                let n = n.max(0);
+                state.set_scene_count(n as usize);
+                send_all(
+                    client,
+                    (0..n).flat_map(|i| {
+                        [OscMessages::get_scene_name(i), OscMessages::get_scene_color(i)]
+                    }),
+                );
+            }
+        }
+        "/live/scene/get/name" => {
+            if let [index, name] = args {
+                if let (Some(i), Some(name)) = (osc_args::as_i32(index), osc_args::as_str(name)) {
+                    state.set_scene_name(i as usize, name.to_string());
+                }
+            }
+        }
+        "/live/scene/get/color" => {
+            if let [index, color] = args {
+                if let (Some(i), Some(color)) = (osc_args::as_i32(index), osc_args::as_i32(color)) {
+                    state.set_scene_color(i as usize, color as u32);
+                }
             }
         }
         "/live/test" => {
@@ -88,3 +295,55 @@ fn handle_message(app: &AppHandle, state: &StateManager, addr: &str, args: &[Osc
         }
     }
 }
+
+/// Apply a bulk `/live/track/get/clips/<field>` reply: `[track, value per
+/// slot...]`.  Malformed replies are ignored.
+fn apply_clip_list(state: &StateManager, addr: &str, args: &[OscType]) {
+    let Some((track, values)) = args.split_first() else {
+        return;
+    };
+    let Some(t) = osc_args::as_i32(track).and_then(|t| usize::try_from(t).ok()) else {
+        return;
+    };
+    for (scene, value) in values.iter().enumerate() {
+        match addr.rsplit('/').next() {
+            Some("name") => {
+                let name = osc_args::as_str(value).unwrap_or_default().to_string();
+                state.set_clip_name(t, scene, name);
+            }
+            Some("length") => {
+                state.set_clip_length(t, scene, osc_args::as_f64(value).unwrap_or(0.0));
+            }
+            Some("color") => {
+                let color = osc_args::as_i32(value).unwrap_or(0) as u32;
+                state.set_clip_color(t, scene, color);
+            }
+            _ => return,
+        }
+    }
+}
+
+/// Read and subscribe to every send of every track.
+fn send_queries(
+    tracks: std::ops::Range<i32>,
+    sends: std::ops::Range<i32>,
+) -> impl Iterator<Item = (&'static str, Vec<OscType>)> {
+    tracks.flat_map(move |t| {
+        sends.clone().flat_map(move |s| {
+            [
+                OscMessages::get_track_send(t, s),
+                OscMessages::start_listen_track_send(t, s),
+            ]
+        })
+    })
+}
+
+/// Store a return/master `name`, `volume` or `panning` reply on `track`.
+fn apply_mixer_value(track: &mut TrackState, addr: &str, value: &OscType) {
+    match addr.rsplit('/').next() {
+        Some("name") => track.name = osc_args::as_str(value).unwrap_or_default().to_string(),
+        Some("volume") => track.volume = osc_args::as_f64(value).unwrap_or(track.volume),
+        Some("panning") => track.panning = osc_args::as_f64(value).unwrap_or(track.panning),
+        _ => {}
+    }
+}
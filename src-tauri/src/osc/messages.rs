@@ -1,5 +1,7 @@
 use rosc::OscType;
 
+use crate::engine::{Note, NoteRegion};
+
 /// Typed OSC message builders for the AbletonOSC protocol.
 pub struct OscMessages;
 
@@ -45,6 +47,18 @@ impl OscMessages {
         ("/live/song/get/current_song_time", vec![])
     }
 
+    pub fn set_song_time(beats: f32) -> (&'static str, Vec<OscType>) {
+        ("/live/song/set/current_song_time", vec![OscType::Float(beats)])
+    }
+
+    pub fn undo() -> (&'static str, Vec<OscType>) {
+        ("/live/song/undo", vec![])
+    }
+
+    pub fn redo() -> (&'static str, Vec<OscType>) {
+        ("/live/song/redo", vec![])
+    }
+
     // ── Listeners (subscribe to live updates) ──────────────
 
     pub fn start_listen_tempo() -> (&'static str, Vec<OscType>) {
@@ -55,6 +69,10 @@ impl OscMessages {
         ("/live/song/start_listen/is_playing", vec![])
     }
 
+    pub fn start_listen_song_time() -> (&'static str, Vec<OscType>) {
+        ("/live/song/start_listen/current_song_time", vec![])
+    }
+
     pub fn start_listen_beat() -> (&'static str, Vec<OscType>) {
         ("/live/song/start_listen/beat", vec![])
     }
@@ -113,6 +131,165 @@ impl OscMessages {
         )
     }
 
+    pub fn get_track_name(track: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/track/get/name", vec![OscType::Int(track)])
+    }
+
+    pub fn get_track_color(track: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/track/get/color", vec![OscType::Int(track)])
+    }
+
+    pub fn get_track_arm(track: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/track/get/arm", vec![OscType::Int(track)])
+    }
+
+    pub fn get_track_volume(track: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/track/get/volume", vec![OscType::Int(track)])
+    }
+
+    pub fn get_track_panning(track: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/track/get/panning", vec![OscType::Int(track)])
+    }
+
+    pub fn get_track_mute(track: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/track/get/mute", vec![OscType::Int(track)])
+    }
+
+    pub fn get_track_solo(track: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/track/get/solo", vec![OscType::Int(track)])
+    }
+
+    pub fn set_track_name(track: i32, name: &str) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/track/set/name",
+            vec![OscType::Int(track), OscType::String(name.into())],
+        )
+    }
+
+    pub fn set_track_color(track: i32, color: u32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/track/set/color",
+            vec![OscType::Int(track), OscType::Int(color as i32)],
+        )
+    }
+
+    pub fn set_track_arm(track: i32, arm: bool) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/track/set/arm",
+            vec![OscType::Int(track), OscType::Int(if arm { 1 } else { 0 })],
+        )
+    }
+
+    /// `index` −1 appends the track at the end.
+    pub fn create_audio_track(index: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/song/create_audio_track", vec![OscType::Int(index)])
+    }
+
+    /// `index` −1 appends the track at the end.
+    pub fn create_midi_track(index: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/song/create_midi_track", vec![OscType::Int(index)])
+    }
+
+    pub fn create_return_track() -> (&'static str, Vec<OscType>) {
+        ("/live/song/create_return_track", vec![])
+    }
+
+    pub fn delete_track(index: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/song/delete_track", vec![OscType::Int(index)])
+    }
+
+    pub fn duplicate_track(index: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/song/duplicate_track", vec![OscType::Int(index)])
+    }
+
+    // ── Sends, returns and master ──────────────────────────
+
+    pub fn get_track_send(track: i32, send: i32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/track/get/send",
+            vec![OscType::Int(track), OscType::Int(send)],
+        )
+    }
+
+    pub fn set_track_send(track: i32, send: i32, value: f32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/track/set/send",
+            vec![OscType::Int(track), OscType::Int(send), OscType::Float(value)],
+        )
+    }
+
+    pub fn start_listen_track_send(track: i32, send: i32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/track/start_listen/send",
+            vec![OscType::Int(track), OscType::Int(send)],
+        )
+    }
+
+    // Return and master track addresses are AbletonOSC extensions — they
+    // require the forked control script, like `load_sample`.
+
+    pub fn get_num_return_tracks() -> (&'static str, Vec<OscType>) {
+        ("/live/song/get/num_return_tracks", vec![])
+    }
+
+    pub fn get_return_track_name(index: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/return_track/get/name", vec![OscType::Int(index)])
+    }
+
+    pub fn get_return_track_volume(index: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/return_track/get/volume", vec![OscType::Int(index)])
+    }
+
+    pub fn get_return_track_panning(index: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/return_track/get/panning", vec![OscType::Int(index)])
+    }
+
+    pub fn set_return_track_volume(index: i32, vol: f32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/return_track/set/volume",
+            vec![OscType::Int(index), OscType::Float(vol)],
+        )
+    }
+
+    pub fn set_return_track_panning(index: i32, pan: f32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/return_track/set/panning",
+            vec![OscType::Int(index), OscType::Float(pan)],
+        )
+    }
+
+    pub fn start_listen_return_track_volume(index: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/return_track/start_listen/volume", vec![OscType::Int(index)])
+    }
+
+    pub fn start_listen_return_track_panning(index: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/return_track/start_listen/panning", vec![OscType::Int(index)])
+    }
+
+    pub fn get_master_volume() -> (&'static str, Vec<OscType>) {
+        ("/live/master_track/get/volume", vec![])
+    }
+
+    pub fn get_master_panning() -> (&'static str, Vec<OscType>) {
+        ("/live/master_track/get/panning", vec![])
+    }
+
+    pub fn set_master_volume(vol: f32) -> (&'static str, Vec<OscType>) {
+        ("/live/master_track/set/volume", vec![OscType::Float(vol)])
+    }
+
+    pub fn set_master_panning(pan: f32) -> (&'static str, Vec<OscType>) {
+        ("/live/master_track/set/panning", vec![OscType::Float(pan)])
+    }
+
+    pub fn start_listen_master_volume() -> (&'static str, Vec<OscType>) {
+        ("/live/master_track/start_listen/volume", vec![])
+    }
+
+    pub fn start_listen_master_panning() -> (&'static str, Vec<OscType>) {
+        ("/live/master_track/start_listen/panning", vec![])
+    }
+
     pub fn start_listen_track_volume(track: i32) -> (&'static str, Vec<OscType>) {
         (
             "/live/track/start_listen/volume",
@@ -120,6 +297,21 @@ impl OscMessages {
         )
     }
 
+    pub fn start_listen_track_panning(track: i32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/track/start_listen/panning",
+            vec![OscType::Int(track)],
+        )
+    }
+
+    pub fn start_listen_track_mute(track: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/track/start_listen/mute", vec![OscType::Int(track)])
+    }
+
+    pub fn start_listen_track_solo(track: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/track/start_listen/solo", vec![OscType::Int(track)])
+    }
+
     pub fn start_listen_track_meter(track: i32) -> (&'static str, Vec<OscType>) {
         (
             "/live/track/start_listen/output_meter_level",
@@ -129,6 +321,33 @@ impl OscMessages {
 
     // ── Clip ───────────────────────────────────────────────
 
+    /// Replies `[track, name per slot...]`; empty slots are nil.
+    pub fn get_track_clip_names(track: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/track/get/clips/name", vec![OscType::Int(track)])
+    }
+
+    pub fn get_track_clip_lengths(track: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/track/get/clips/length", vec![OscType::Int(track)])
+    }
+
+    pub fn get_track_clip_colors(track: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/track/get/clips/color", vec![OscType::Int(track)])
+    }
+
+    pub fn start_listen_playing_slot_index(track: i32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/track/start_listen/playing_slot_index",
+            vec![OscType::Int(track)],
+        )
+    }
+
+    pub fn start_listen_fired_slot_index(track: i32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/track/start_listen/fired_slot_index",
+            vec![OscType::Int(track)],
+        )
+    }
+
     pub fn fire_clip(track: i32, scene: i32) -> (&'static str, Vec<OscType>) {
         (
             "/live/clip/fire",
@@ -143,6 +362,13 @@ impl OscMessages {
         )
     }
 
+    pub fn create_clip(track: i32, scene: i32, length: f32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/clip_slot/create_clip",
+            vec![OscType::Int(track), OscType::Int(scene), OscType::Float(length)],
+        )
+    }
+
     pub fn delete_clip(track: i32, scene: i32) -> (&'static str, Vec<OscType>) {
         (
             "/live/clip_slot/delete_clip",
@@ -183,6 +409,130 @@ impl OscMessages {
         )
     }
 
+    // ── Notes ──────────────────────────────────────────────
+
+    /// Replies `[track, clip, (pitch, start, duration, velocity, mute)...]`.
+    pub fn get_clip_notes(track: i32, clip: i32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/clip/get/notes",
+            vec![OscType::Int(track), OscType::Int(clip)],
+        )
+    }
+
+    pub fn add_clip_notes(track: i32, clip: i32, notes: &[Note]) -> (&'static str, Vec<OscType>) {
+        let mut args = vec![OscType::Int(track), OscType::Int(clip)];
+        for note in notes {
+            args.extend([
+                OscType::Int(note.pitch as i32),
+                OscType::Float(note.start as f32),
+                OscType::Float(note.duration as f32),
+                OscType::Int(note.velocity as i32),
+                OscType::Int(if note.mute { 1 } else { 0 }),
+            ]);
+        }
+        ("/live/clip/add/notes", args)
+    }
+
+    /// Without a region, AbletonOSC removes every note of the clip.
+    pub fn remove_clip_notes(
+        track: i32,
+        clip: i32,
+        region: Option<NoteRegion>,
+    ) -> (&'static str, Vec<OscType>) {
+        let mut args = vec![OscType::Int(track), OscType::Int(clip)];
+        if let Some(r) = region {
+            args.extend([
+                OscType::Int(r.start_pitch as i32),
+                OscType::Int(r.pitch_span as i32),
+                OscType::Float(r.start_time as f32),
+                OscType::Float(r.time_span as f32),
+            ]);
+        }
+        ("/live/clip/remove/notes", args)
+    }
+
+    // ── Scene ──────────────────────────────────────────────
+
+    pub fn get_num_scenes() -> (&'static str, Vec<OscType>) {
+        ("/live/song/get/num_scenes", vec![])
+    }
+
+    pub fn get_scene_name(scene: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/scene/get/name", vec![OscType::Int(scene)])
+    }
+
+    pub fn get_scene_color(scene: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/scene/get/color", vec![OscType::Int(scene)])
+    }
+
+    pub fn fire_scene(scene: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/scene/fire", vec![OscType::Int(scene)])
+    }
+
+    /// `index` −1 appends the scene at the end.
+    pub fn create_scene(index: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/song/create_scene", vec![OscType::Int(index)])
+    }
+
+    pub fn duplicate_scene(index: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/song/duplicate_scene", vec![OscType::Int(index)])
+    }
+
+    pub fn delete_scene(index: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/song/delete_scene", vec![OscType::Int(index)])
+    }
+
+    // ── Device ─────────────────────────────────────────────
+
+    pub fn get_track_device_names(track: i32) -> (&'static str, Vec<OscType>) {
+        ("/live/track/get/devices/name", vec![OscType::Int(track)])
+    }
+
+    pub fn get_device_parameter_names(track: i32, device: i32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/device/get/parameters/name",
+            vec![OscType::Int(track), OscType::Int(device)],
+        )
+    }
+
+    pub fn get_device_parameter_mins(track: i32, device: i32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/device/get/parameters/min",
+            vec![OscType::Int(track), OscType::Int(device)],
+        )
+    }
+
+    pub fn get_device_parameter_maxs(track: i32, device: i32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/device/get/parameters/max",
+            vec![OscType::Int(track), OscType::Int(device)],
+        )
+    }
+
+    pub fn get_device_parameter_values(track: i32, device: i32) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/device/get/parameters/value",
+            vec![OscType::Int(track), OscType::Int(device)],
+        )
+    }
+
+    pub fn set_device_parameter_value(
+        track: i32,
+        device: i32,
+        param: i32,
+        value: f32,
+    ) -> (&'static str, Vec<OscType>) {
+        (
+            "/live/device/set/parameter/value",
+            vec![
+                OscType::Int(track),
+                OscType::Int(device),
+                OscType::Int(param),
+                OscType::Float(value),
+            ],
+        )
+    }
+
     // ── Test / Ping ────────────────────────────────────────
 
     pub fn test() -> (&'static str, Vec<OscType>) {
@@ -1,5 +1,9 @@
 pub mod adapter;
+pub mod args;
 pub mod client;
+pub mod devices;
+pub mod feedback;
 pub mod listener;
 pub mod messages;
+pub mod notes;
 pub mod state;
@@ -0,0 +1,120 @@
+//! Request/reply matching for AbletonOSC note queries.
+//!
+//! `get_clip_notes` has to return the notes, but replies arrive on the
+//! listener thread.  The adapter registers a waiter for `(track, clip)`
+//! before sending `/live/clip/get/notes`; the listener hands the reply to
+//! [`NoteReplies::handle`], which wakes every waiter for that clip.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Mutex;
+
+use rosc::OscType;
+
+use crate::engine::Note;
+use crate::osc::args;
+
+const NOTE_REPLY_ADDR: &str = "/live/clip/get/notes";
+
+/// Waiters for each `(track, clip)`.
+type Waiters = HashMap<(i32, i32), Vec<Sender<Vec<Note>>>>;
+
+#[derive(Default)]
+pub struct NoteReplies {
+    pending: Mutex<Waiters>,
+}
+
+impl NoteReplies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register interest in the next note reply for a clip.
+    pub fn expect(&self, track: i32, clip: i32) -> Receiver<Vec<Note>> {
+        let (tx, rx) = mpsc::channel();
+        self.pending
+            .lock()
+            .unwrap()
+            .entry((track, clip))
+            .or_default()
+            .push(tx);
+        rx
+    }
+
+    /// Deliver a note reply to its waiters.  Returns `false` for any other
+    /// message, which the listener then handles itself.
+    pub fn handle(&self, addr: &str, osc_args: &[OscType]) -> bool {
+        if addr != NOTE_REPLY_ADDR {
+            return false;
+        }
+        let Some((track, clip, notes)) = parse_notes(osc_args) else {
+            log::warn!("Malformed AbletonOSC note reply: {:?}", osc_args);
+            return true;
+        };
+        let waiters = self.pending.lock().unwrap().remove(&(track, clip));
+        for tx in waiters.into_iter().flatten() {
+            // The waiter may have timed out already.
+            let _ = tx.send(notes.clone());
+        }
+        true
+    }
+}
+
+/// Parse `[track, clip, (pitch, start, duration, velocity, mute)...]`.
+pub fn parse_notes(osc_args: &[OscType]) -> Option<(i32, i32, Vec<Note>)> {
+    let [track, clip, rest @ ..] = osc_args else {
+        return None;
+    };
+    if rest.len() % 5 != 0 {
+        return None;
+    }
+    let notes = rest
+        .chunks_exact(5)
+        .map(|n| {
+            Some(Note {
+                pitch: args::as_i32(&n[0])?.clamp(0, 127) as u8,
+                start: args::as_f64(&n[1])?,
+                duration: args::as_f64(&n[2])?,
+                velocity: args::as_f64(&n[3])?.round().clamp(0.0, 127.0) as u8,
+                mute: args::as_bool(&n[4]).unwrap_or(false),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some((args::as_i32(track)?, args::as_i32(clip)?, notes))
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::osc::messages::OscMessages;
+
+    #[test]
+    fn note_replies_reach_waiters_of_that_clip() {
+        let replies = NoteReplies::new();
+        let rx = replies.expect(0, 2);
+        let other = replies.expect(1, 2);
+
+        let notes = [Note {
+            pitch: 60,
+            start: 0.5,
+            duration: 0.25,
+            velocity: 100,
+            mute: false,
+        }];
+        // A reply is laid out exactly like an add_notes request.
+        let (_, reply) = OscMessages::add_clip_notes(0, 2, &notes);
+        assert!(replies.handle(NOTE_REPLY_ADDR, &reply));
+        assert!(!replies.handle("/live/song/get/tempo", &[]));
+
+        assert_eq!(rx.try_recv().unwrap(), notes);
+        assert!(other.try_recv().is_err());
+    }
+
+    #[test]
+    fn truncated_note_lists_are_rejected() {
+        let args = [OscType::Int(0), OscType::Int(0), OscType::Int(60)];
+        assert_eq!(parse_notes(&args), None);
+    }
+}
@@ -0,0 +1,442 @@
+//! Command palette: fuzzy search over everything the user can act on.
+//!
+//! [`Palette::query`] ranks skills, tracks, named clips, scenes, scripts and
+//! recent sample search results against the typed text.  Items the user
+//! picked often or recently (reported back through [`Palette::record_use`])
+//! rank higher.  Each item carries a [`PaletteAction`] for the UI to run.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::engine::EngineState;
+use crate::sidecar::api::SearchResultItem;
+use crate::skills::{Params, SkillDescriptor};
+
+const MAX_RECENT_RESULTS: usize = 50;
+
+/// Recency bonus halves every this long.
+const RECENCY_HALF_LIFE: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PaletteAction {
+    /// Run a skill; missing required params are for the UI to ask for.
+    RunSkill { skill: String, params: Params },
+    SelectTrack { track: usize },
+    FireClip { track: usize, scene: usize },
+    FireScene { scene: usize },
+    RunScript { name: String },
+    /// Load a sample; the UI picks the clip slot.
+    InsertSample { path: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteItem {
+    /// Stable key for [`Palette::record_use`].
+    pub id: String,
+    pub title: String,
+    pub subtitle: String,
+    /// Keyboard shortcut, for skills that have one.
+    pub shortcut: Option<String>,
+    pub action: PaletteAction,
+    pub score: f64,
+    /// Character positions in `title` that matched, for highlighting.
+    pub matches: Vec<usize>,
+    /// Also searched, but not shown (e.g. a skill's id).
+    #[serde(skip)]
+    keywords: String,
+}
+
+impl PaletteItem {
+    fn new(id: String, title: String, subtitle: String, action: PaletteAction) -> Self {
+        Self {
+            id,
+            title,
+            subtitle,
+            shortcut: None,
+            action,
+            score: 0.0,
+            matches: Vec::new(),
+            keywords: String::new(),
+        }
+    }
+}
+
+// ── Fuzzy matching ─────────────────────────────────────────────────────────
+
+/// Score `query` as a case-insensitive subsequence of `text`; `None` if it
+/// isn't one.  Consecutive matches, word starts and prefixes score higher,
+/// gaps lower; the best-scoring alignment wins.  Also returns the matched
+/// character positions.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(f64, Vec<usize>)> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let chars: Vec<char> = text.chars().collect();
+    if query.is_empty() {
+        return Some((0.0, Vec::new()));
+    }
+    let hit = |q: char, i: usize| chars[i].to_lowercase().eq([q]);
+    let base = |i: usize| 1.0 + if is_word_start(&chars, i) { 3.0 } else { 0.0 };
+    let gap = |from: usize, to: usize| 0.1 * (to - from).min(10) as f64;
+
+    // best[j][i]: the best score for query[..=j] with query[j] at text[i],
+    // and where query[j - 1] went.
+    let mut best = vec![vec![None::<(f64, usize)>; chars.len()]; query.len()];
+    for i in (0..chars.len()).filter(|&i| hit(query[0], i)) {
+        best[0][i] = Some((base(i) - gap(0, i), 0));
+    }
+    for j in 1..query.len() {
+        for i in (j..chars.len()).filter(|&i| hit(query[j], i)) {
+            best[j][i] = (0..i)
+                .filter_map(|k| best[j - 1][k].map(|(s, _)| (s, k)))
+                .map(|(s, k)| {
+                    let link = if k + 1 == i { 2.0 } else { -gap(k + 1, i) };
+                    (s + link + base(i), k)
+                })
+                .max_by(|a, b| a.0.total_cmp(&b.0));
+        }
+    }
+
+    let last = query.len() - 1;
+    let (end, (mut score, _)) = best[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(i, b)| b.map(|b| (i, b)))
+        .max_by(|a, b| a.1 .0.total_cmp(&b.1 .0))?;
+    let mut positions = vec![end];
+    for j in (1..=last).rev() {
+        let prev = best[j][positions[0]].unwrap().1;
+        positions.insert(0, prev);
+    }
+    if positions.iter().enumerate().all(|(i, &p)| i == p) {
+        score += 5.0;
+    }
+    Some((score, positions))
+}
+
+fn is_word_start(chars: &[char], i: usize) -> bool {
+    let Some(prev) = i.checked_sub(1).map(|p| chars[p]) else {
+        return true;
+    };
+    !prev.is_alphanumeric() || (prev.is_lowercase() && chars[i].is_uppercase())
+}
+
+// ── Palette ────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy)]
+struct Usage {
+    count: u32,
+    last: Instant,
+}
+
+#[derive(Debug, Clone)]
+struct RecentResult {
+    query: String,
+    item: SearchResultItem,
+}
+
+/// Usage statistics and recent search results backing the palette.
+#[derive(Default)]
+pub struct Palette {
+    usage: Mutex<HashMap<String, Usage>>,
+    recent: Mutex<VecDeque<RecentResult>>,
+}
+
+/// What the palette searches, gathered by the caller.
+pub struct PaletteSources<'a> {
+    pub skills: &'a [SkillDescriptor],
+    pub state: &'a EngineState,
+    pub scripts: &'a [String],
+}
+
+impl Palette {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Note that the user picked item `id`.
+    pub fn record_use(&self, id: &str) {
+        self.record_use_at(id, Instant::now());
+    }
+
+    fn record_use_at(&self, id: &str, now: Instant) {
+        let mut usage = self.usage.lock().unwrap();
+        let entry = usage.entry(id.to_string()).or_insert(Usage {
+            count: 0,
+            last: now,
+        });
+        entry.count += 1;
+        entry.last = now;
+    }
+
+    /// Remember sample search results; newest first, one entry per file.
+    pub fn record_search(&self, query: &str, results: &[SearchResultItem]) {
+        let mut recent = self.recent.lock().unwrap();
+        for item in results.iter().rev() {
+            recent.retain(|r| r.item.file_path != item.file_path);
+            recent.push_front(RecentResult {
+                query: query.to_string(),
+                item: item.clone(),
+            });
+        }
+        recent.truncate(MAX_RECENT_RESULTS);
+    }
+
+    /// Forget recent search results, e.g. when another project opens.
+    pub fn clear_searches(&self) {
+        self.recent.lock().unwrap().clear();
+    }
+
+    /// The best `limit` items for `text`.  Empty text lists items by usage.
+    pub fn query(
+        &self,
+        text: &str,
+        sources: &PaletteSources<'_>,
+        limit: usize,
+    ) -> Vec<PaletteItem> {
+        self.query_at(text, sources, limit, Instant::now())
+    }
+
+    fn query_at(
+        &self,
+        text: &str,
+        sources: &PaletteSources<'_>,
+        limit: usize,
+        now: Instant,
+    ) -> Vec<PaletteItem> {
+        let text = text.trim();
+        let usage = self.usage.lock().unwrap();
+        let mut items: Vec<PaletteItem> = self
+            .candidates(sources)
+            .into_iter()
+            .filter_map(|mut item| {
+                if !text.is_empty() {
+                    let title = fuzzy_match(text, &item.title);
+                    let keywords = fuzzy_match(text, &item.keywords).map(|(s, _)| s * 0.8);
+                    let (score, matches) = match (title, keywords) {
+                        (Some((s, m)), Some(k)) if s >= k => (s, m),
+                        (Some((s, m)), None) => (s, m),
+                        (_, Some(k)) => (k, Vec::new()),
+                        (None, None) => return None,
+                    };
+                    item.score = score;
+                    item.matches = matches;
+                }
+                if let Some(u) = usage.get(&item.id) {
+                    item.score += usage_bonus(u, now);
+                }
+                Some(item)
+            })
+            .collect();
+        // Stable, so equal scores keep the source order (skills first).
+        items.sort_by(|a, b| b.score.total_cmp(&a.score));
+        items.truncate(limit);
+        items
+    }
+
+    fn candidates(&self, sources: &PaletteSources<'_>) -> Vec<PaletteItem> {
+        let mut items = Vec::new();
+        for skill in sources.skills {
+            let mut item = PaletteItem::new(
+                format!("skill:{}", skill.id),
+                skill.name.clone(),
+                skill.description.clone(),
+                PaletteAction::RunSkill {
+                    skill: skill.id.clone(),
+                    params: Params::new(),
+                },
+            );
+            item.shortcut = skill.keyboard_shortcut.clone();
+            item.keywords = skill.id.clone();
+            items.push(item);
+        }
+
+        let state = sources.state;
+        for (i, track) in state.tracks.iter().enumerate() {
+            let mut item = PaletteItem::new(
+                format!("track:{i}"),
+                label(&track.name, "Track", i),
+                format!("Track {}", i + 1),
+                PaletteAction::SelectTrack { track: i },
+            );
+            item.keywords = "track".into();
+            items.push(item);
+        }
+        for (t, track) in state.tracks.iter().enumerate() {
+            for clip in track.clips.iter().filter(|c| !c.name.is_empty()) {
+                items.push(PaletteItem::new(
+                    format!("clip:{t}:{}", clip.scene_index),
+                    clip.name.clone(),
+                    format!("Clip on {}", label(&track.name, "Track", t)),
+                    PaletteAction::FireClip {
+                        track: t,
+                        scene: clip.scene_index,
+                    },
+                ));
+            }
+        }
+        for scene in 0..state.num_scenes.max(state.scenes.len()) {
+            let name = state.scenes.get(scene).map_or("", |s| s.name.as_str());
+            let mut item = PaletteItem::new(
+                format!("scene:{scene}"),
+                label(name, "Scene", scene),
+                "Launch scene".into(),
+                PaletteAction::FireScene { scene },
+            );
+            item.keywords = "scene".into();
+            items.push(item);
+        }
+
+        for name in sources.scripts {
+            let mut item = PaletteItem::new(
+                format!("script:{name}"),
+                name.clone(),
+                "Run script".into(),
+                PaletteAction::RunScript { name: name.clone() },
+            );
+            item.keywords = "script".into();
+            items.push(item);
+        }
+
+        for recent in self.recent.lock().unwrap().iter() {
+            items.push(PaletteItem::new(
+                format!("sample:{}", recent.item.file_path),
+                recent.item.file_name.clone(),
+                format!("Search result for “{}”", recent.query),
+                PaletteAction::InsertSample {
+                    path: recent.item.file_path.clone(),
+                },
+            ));
+        }
+        items
+    }
+}
+
+fn label(name: &str, kind: &str, index: usize) -> String {
+    if name.is_empty() {
+        format!("{} {}", kind, index + 1)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Frequency counts logarithmically; recency decays by half-life.
+fn usage_bonus(usage: &Usage, now: Instant) -> f64 {
+    let age = now.saturating_duration_since(usage.last).as_secs_f64();
+    let recency = 0.5f64.powf(age / RECENCY_HALF_LIFE.as_secs_f64());
+    2.0 * (1.0 + usage.count as f64).ln() + 4.0 * recency
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{ClipState, TrackState};
+    use crate::skills::builtin;
+
+    fn state() -> EngineState {
+        let track = |name: &str| TrackState {
+            name: name.into(),
+            ..Default::default()
+        };
+        let mut state = EngineState {
+            tracks: vec![track("Drums"), track("Bass"), track("")],
+            num_scenes: 2,
+            ..Default::default()
+        };
+        state.tracks[1].clips.push(ClipState {
+            track_index: 1,
+            scene_index: 1,
+            name: "Bassline A".into(),
+            ..Default::default()
+        });
+        state
+    }
+
+    fn titles(items: &[PaletteItem]) -> Vec<&str> {
+        items.iter().map(|i| i.title.as_str()).collect()
+    }
+
+    #[test]
+    fn fuzzy_matching_prefers_word_starts_and_runs() {
+        let (prefix, m) = fuzzy_match("tra", "Transpose clip").unwrap();
+        assert_eq!(m, [0, 1, 2]);
+        let (scattered, _) = fuzzy_match("tra", "Set track pan").unwrap();
+        let (inside, _) = fuzzy_match("tra", "Extra").unwrap();
+        assert!(prefix > scattered && scattered > inside);
+        assert!(fuzzy_match("xyz", "Transpose").is_none());
+        assert_eq!(fuzzy_match("sc", "setClip").unwrap().1, [0, 3]);
+    }
+
+    #[test]
+    fn queries_cover_every_source_with_typed_actions() {
+        let palette = Palette::new();
+        let skills = builtin::descriptors();
+        let state = state();
+        let scripts = vec!["quiet-drums".to_string()];
+        let sources = PaletteSources {
+            skills: &skills,
+            state: &state,
+            scripts: &scripts,
+        };
+        palette.record_search(
+            "kick",
+            &[SearchResultItem {
+                id: "1".into(),
+                file_path: "/s/kick_808.wav".into(),
+                file_name: "kick_808.wav".into(),
+                distance: 0.1,
+                duration_seconds: 1.0,
+                metadata: serde_json::Value::Null,
+            }],
+        );
+
+        let items = palette.query("bass", &sources, 3);
+        assert_eq!(titles(&items)[..2], ["Bass", "Bassline A"]);
+        assert_eq!(
+            items[1].action,
+            PaletteAction::FireClip { track: 1, scene: 1 }
+        );
+
+        let items = palette.query("drums", &sources, 2);
+        assert_eq!(titles(&items), ["Drums", "quiet-drums"]);
+        assert_eq!(
+            palette.query("kick", &sources, 1)[0].id,
+            "sample:/s/kick_808.wav"
+        );
+        assert_eq!(palette.query("track 3", &sources, 1)[0].title, "Track 3");
+        assert_eq!(palette.query("scene 2", &sources, 1)[0].title, "Scene 2");
+        // Skill ids are searchable too.
+        assert_eq!(palette.query("utility.redo", &sources, 1)[0].title, "Redo");
+    }
+
+    #[test]
+    fn frequent_and_recent_items_rank_higher() {
+        let palette = Palette::new();
+        let skills = builtin::descriptors();
+        let state = EngineState::default();
+        let sources = PaletteSources {
+            skills: &skills,
+            state: &state,
+            scripts: &[],
+        };
+        let now = Instant::now();
+        let first = |text: &str, at: Instant| palette.query_at(text, &sources, 1, at)[0].id.clone();
+
+        assert_eq!(first("s", now), "skill:transport.stop");
+        palette.record_use_at("skill:track.solo", now);
+        assert_eq!(first("s", now), "skill:track.solo");
+        assert_eq!(first("", now), "skill:track.solo");
+
+        // An old single use fades behind a fresh one.
+        palette.record_use_at("skill:scene.fire", now + Duration::from_secs(3600));
+        assert_eq!(
+            first("", now + Duration::from_secs(3600)),
+            "skill:scene.fire"
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Ramps: smooth moves of a track or custom param to a new value.
+//!
+//! A [`Ramp`] glides its target from `from` (by default the current value)
+//! to `to` over `duration_ms` along a [`Curve`], sending a value every
+//! [`RAMP_FRAME`] through the engine adapter, so it works the same on every
+//! engine.  Engines that don't report values back (MIDI, Zrythm, Ardour's
+//! custom params) need `from`: Maestro can't tell where the control is.  One
+//! ramp runs per target: starting another on the same target, or
+//! [`Ramps::cancel`], stops it where it is.  A ramp is one undo step, from
+//! where it started to where it stopped.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::time::MissedTickBehavior;
+
+use crate::automation::Target;
+use crate::engine::{EngineAdapter, StateManager};
+use crate::journal::Journal;
+
+/// Time between ramp steps (50 per second).
+pub const RAMP_FRAME: Duration = Duration::from_millis(20);
+
+/// How a ramp moves from start to end.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Curve {
+    #[default]
+    Linear,
+    /// Slow at first, then faster.
+    Exponential,
+    /// S-curve: eases in and out.
+    #[serde(rename = "s_curve")]
+    Smooth,
+}
+
+impl Curve {
+    /// Progress of the value at time `t`, both 0–1.
+    pub fn shape(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Curve::Linear => t,
+            Curve::Exponential => (4.0 * t).exp_m1() / 4f64.exp_m1(),
+            Curve::Smooth => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ramp {
+    pub target: Target,
+    /// Where to start; the current value when `None`.
+    #[serde(default)]
+    pub from: Option<f64>,
+    pub to: f64,
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub curve: Curve,
+}
+
+impl Ramp {
+    /// The value at time `t` (0–1) of a ramp that started at `from`.
+    pub fn value_at(&self, from: f64, t: f64) -> f64 {
+        from + (self.to - from) * self.curve.shape(t)
+    }
+}
+
+/// The running ramps, one per target.
+#[derive(Default)]
+pub struct Ramps {
+    running: Mutex<HashMap<Target, u64>>,
+    next_id: AtomicU64,
+}
+
+impl Ramps {
+    /// Targets currently ramping.
+    pub fn running(&self) -> Vec<Target> {
+        self.running.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// Stop the ramp on `target`, or every ramp.  Returns how many stopped.
+    pub fn cancel(&self, target: Option<&Target>) -> usize {
+        let mut running = self.running.lock().unwrap();
+        match target {
+            Some(target) => usize::from(running.remove(target).is_some()),
+            None => running.drain().count(),
+        }
+    }
+
+    /// Take over `target`, stopping any ramp already on it.
+    fn claim(&self, target: &Target) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        self.running.lock().unwrap().insert(target.clone(), id);
+        id
+    }
+
+    fn holds(&self, target: &Target, id: u64) -> bool {
+        self.running.lock().unwrap().get(target) == Some(&id)
+    }
+
+    fn release(&self, target: &Target, id: u64) {
+        let mut running = self.running.lock().unwrap();
+        if running.get(target) == Some(&id) {
+            running.remove(target);
+        }
+    }
+
+    /// Run `ramp` to its end.  Resolves to `true` when it got there and
+    /// `false` when it was cancelled or replaced.
+    pub async fn run(
+        &self,
+        engine: &dyn EngineAdapter,
+        state: &StateManager,
+        journal: &Journal,
+        ramp: &Ramp,
+    ) -> Result<bool, String> {
+        let target = &ramp.target;
+        if target.is_toggle() {
+            return Err("Mutes and solos switch; they can't be ramped".into());
+        }
+        let from = ramp
+            .from
+            .or_else(|| {
+                let current = target.edit(ramp.to).current(&state.get(), engine)?;
+                Target::of(&current).map(|(_, value)| value)
+            })
+            .ok_or_else(|| {
+                format!("Can't ramp {target:?}: its current value is unknown; give a start value")
+            })?;
+
+        let id = self.claim(target);
+        let duration = Duration::from_millis(ramp.duration_ms).as_secs_f64();
+        let start = Instant::now();
+        let mut tick = tokio::time::interval(RAMP_FRAME);
+        tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let mut reached = from;
+        let result = loop {
+            tick.tick().await;
+            if !self.holds(target, id) {
+                break Ok(false);
+            }
+            let t = match duration {
+                d if d > 0.0 => start.elapsed().as_secs_f64() / d,
+                _ => 1.0,
+            };
+            let value = ramp.value_at(from, t);
+            if let Err(e) = journal.send(engine, state, target.edit(value)) {
+                break Err(e);
+            }
+            reached = value;
+            if t >= 1.0 {
+                break Ok(true);
+            }
+        };
+        self.release(target, id);
+
+        if reached != from {
+            journal.record(target.edit(reached), target.edit(from));
+        }
+        result
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves_run_from_start_to_end() {
+        for curve in [Curve::Linear, Curve::Exponential, Curve::Smooth] {
+            assert_eq!(curve.shape(0.0), 0.0);
+            assert!((curve.shape(1.0) - 1.0).abs() < 1e-12);
+            assert_eq!(curve.shape(2.0), curve.shape(1.0));
+            let samples: Vec<f64> = (0..=10).map(|i| curve.shape(i as f64 / 10.0)).collect();
+            assert!(samples.windows(2).all(|w| w[0] < w[1]), "{curve:?}");
+        }
+        assert!(Curve::Exponential.shape(0.5) < 0.5);
+        assert_eq!(Curve::Smooth.shape(0.5), 0.5);
+
+        let fade = Ramp {
+            target: Target::TrackVolume { track: 0 },
+            from: None,
+            to: 0.0,
+            duration_ms: 1000,
+            curve: Curve::Linear,
+        };
+        assert!((fade.value_at(0.8, 0.25) - 0.6).abs() < 1e-12);
+    }
+
+    #[test]
+    fn a_new_ramp_on_a_target_replaces_the_old_one() {
+        let ramps = Ramps::default();
+        let volume = Target::TrackVolume { track: 0 };
+        let pan = Target::TrackPan { track: 0 };
+        let first = ramps.claim(&volume);
+        let second = ramps.claim(&volume);
+        ramps.claim(&pan);
+        assert!(!ramps.holds(&volume, first));
+        assert!(ramps.holds(&volume, second));
+
+        // The replaced ramp finishing must not release its successor.
+        ramps.release(&volume, first);
+        assert!(ramps.holds(&volume, second));
+
+        assert_eq!(ramps.cancel(Some(&pan)), 1);
+        assert_eq!(ramps.running(), vec![volume.clone()]);
+        assert_eq!(ramps.cancel(None), 1);
+        assert!(!ramps.holds(&volume, second));
+    }
+
+    #[test]
+    fn ramps_deserialize_from_camel_case() {
+        let ramp: Ramp = serde_json::from_str(
+            r#"{"target":{"kind":"custom_param","id":"cutoff"},"to":0.5,"durationMs":250}"#,
+        )
+        .unwrap();
+        assert_eq!(ramp.curve, Curve::Linear);
+        assert_eq!(ramp.duration_ms, 250);
+        assert_eq!(ramp.from, None);
+        let curve: Curve = serde_json::from_str(r#""s_curve""#).unwrap();
+        assert_eq!(curve, Curve::Smooth);
+    }
+}
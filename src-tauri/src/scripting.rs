@@ -0,0 +1,313 @@
+//! Rhai scripts for automation that macros can't express.
+//!
+//! Scripts live in `~/.config/maestro/scripts/<name>.rhai` and run with the
+//! `run_script` command.  Besides the Rhai language they get:
+//!
+//! - transport: `play()`, `stop()`, `record()`, `set_tempo(bpm)`,
+//!   `set_loop(on)`, `jump(beats)`
+//! - tracks: `set_volume(t, v)`, `set_pan(t, p)`, `mute(t, on)`,
+//!   `solo(t, on)`, `arm(t, on)`, `rename(t, name)`
+//! - clips: `fire_clip(t, s)`, `stop_clip(t, s)`, `fire_scene(s)`
+//! - `state()`: a copy of the engine state, with the same camelCase fields
+//!   as `get_engine_state` — changing it changes nothing
+//! - `search(query, n)`, `search_similar(path, n)`: sidecar results
+//! - `sleep(ms)`
+//!
+//! Value edits go through the undo journal, and a run undoes as one step.
+//! Scripts can't import modules or `eval`, and are stopped once they exceed
+//! the [`ScriptLimits`].
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rhai::{Dynamic, Engine, EvalAltResult, ImmutableString};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::engine::{EngineAdapter, StateManager};
+use crate::journal::{Edit, Journal};
+use crate::sidecar::api::SidecarClient;
+
+/// Where scripts are loaded from.
+pub fn scripts_dir() -> PathBuf {
+    crate::config::config_dir().join("scripts")
+}
+
+/// Names (without `.rhai`) of the scripts in [`scripts_dir`], sorted.
+pub fn list() -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(scripts_dir())
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "rhai"))
+                .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().into_owned()))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Read script `name` from [`scripts_dir`].
+pub fn load(name: &str) -> Result<String, String> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return Err(format!("Invalid script name '{name}'"));
+    }
+    let path = scripts_dir().join(format!("{name}.rhai"));
+    std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+pub struct ScriptLimits {
+    pub max_operations: u64,
+    pub timeout: Duration,
+    pub max_call_levels: usize,
+    /// Longest string, array and map a script may build.
+    pub max_size: usize,
+}
+
+impl Default for ScriptLimits {
+    fn default() -> Self {
+        Self {
+            max_operations: 1_000_000,
+            timeout: Duration::from_secs(10),
+            max_call_levels: 32,
+            max_size: 10_000,
+        }
+    }
+}
+
+/// What a script run produced.  `error` is set when the script failed to
+/// compile, threw, or hit a limit; `output` still holds what it printed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScriptOutput {
+    pub output: Vec<String>,
+    /// The value of the script's last expression.
+    pub value: Value,
+    pub error: Option<String>,
+}
+
+/// What a script can reach.  Owned so scripts can run on a blocking thread.
+#[derive(Clone)]
+pub struct ScriptHost {
+    pub engine: Arc<dyn EngineAdapter>,
+    pub state: StateManager,
+    pub journal: Journal,
+    pub sidecar: SidecarClient,
+}
+
+impl ScriptHost {
+    fn edit(&self, edit: Edit) -> Result<(), String> {
+        self.journal.apply(&*self.engine, &self.state, edit)
+    }
+}
+
+/// Run `source` to completion.  Blocks, so call it off the async runtime.
+pub fn run(host: ScriptHost, source: &str, limits: &ScriptLimits) -> ScriptOutput {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let deadline = Instant::now() + limits.timeout;
+    let mut rhai = sandbox(limits, deadline, &output);
+    let parent = host.journal.clone();
+    let journal = parent.child();
+    let host = ScriptHost {
+        journal: journal.clone(),
+        ..host
+    };
+    register(&mut rhai, Arc::new(host), deadline);
+
+    let result = rhai.eval::<Dynamic>(source);
+    parent.adopt(&journal);
+
+    let output = std::mem::take(&mut *output.lock().unwrap());
+    match result {
+        Ok(value) => ScriptOutput {
+            output,
+            value: rhai::serde::from_dynamic(&value).unwrap_or(Value::Null),
+            error: None,
+        },
+        Err(e) => ScriptOutput {
+            output,
+            value: Value::Null,
+            error: Some(match *e {
+                EvalAltResult::ErrorTerminated(..) => {
+                    format!("Script timed out after {:?}", limits.timeout)
+                }
+                e => e.to_string(),
+            }),
+        },
+    }
+}
+
+fn sandbox(limits: &ScriptLimits, deadline: Instant, output: &Arc<Mutex<Vec<String>>>) -> Engine {
+    let mut rhai = Engine::new();
+    rhai.set_max_operations(limits.max_operations)
+        .set_max_call_levels(limits.max_call_levels)
+        .set_max_string_size(limits.max_size)
+        .set_max_array_size(limits.max_size)
+        .set_max_map_size(limits.max_size)
+        .set_module_resolver(rhai::module_resolvers::DummyModuleResolver::new())
+        .disable_symbol("eval")
+        .on_progress(move |_| (Instant::now() > deadline).then(|| "timeout".into()));
+
+    let print = Arc::clone(output);
+    rhai.on_print(move |s| print.lock().unwrap().push(s.to_string()));
+    let debug = Arc::clone(output);
+    rhai.on_debug(move |s, _, _| debug.lock().unwrap().push(s.to_string()));
+    rhai
+}
+
+fn number(v: Dynamic) -> Result<f64, String> {
+    v.as_float()
+        .or_else(|_| v.as_int().map(|i| i as f64))
+        .map_err(|t| format!("expected a number, got {t}"))
+}
+
+fn index(i: i64) -> Result<i32, String> {
+    i32::try_from(i)
+        .ok()
+        .filter(|i| *i >= 0)
+        .ok_or_else(|| format!("{i} is not a valid index"))
+}
+
+fn search_results(
+    response: Result<crate::sidecar::api::SearchResponse, reqwest::Error>,
+) -> Result<Dynamic, String> {
+    let response = response.map_err(|e| format!("search failed: {e}"))?;
+    rhai::serde::to_dynamic(&response.results).map_err(|e| e.to_string())
+}
+
+fn register(rhai: &mut Engine, host: Arc<ScriptHost>, deadline: Instant) {
+    // `func!("name", |h, args..| body)`: `h` is the host, `body` a
+    // `Result<_, String>`.
+    macro_rules! func {
+        ($name:literal, |$h:ident $(, $arg:ident: $ty:ty)*| $body:expr) => {{
+            let $h = Arc::clone(&host);
+            rhai.register_fn($name, move |$($arg: $ty),*| -> Result<_, Box<EvalAltResult>> {
+                let $h = &*$h;
+                $body.map_err(|e: String| e.into())
+            });
+        }};
+    }
+
+    // Transport
+    func!("play", |h| h.engine.play());
+    func!("stop", |h| h.engine.stop());
+    func!("record", |h| h.engine.toggle_record());
+    func!("set_tempo", |h, bpm: Dynamic| h.edit(Edit::Tempo(number(bpm)?)));
+    func!("set_loop", |h, on: bool| h.edit(Edit::Loop(on)));
+    func!("jump", |h, beats: Dynamic| h.engine.set_song_time(number(beats)?));
+
+    // Tracks
+    func!("set_volume", |h, t: i64, v: Dynamic| {
+        h.edit(Edit::TrackVolume(index(t)?, number(v)?))
+    });
+    func!("set_pan", |h, t: i64, p: Dynamic| h.edit(Edit::TrackPan(index(t)?, number(p)?)));
+    func!("mute", |h, t: i64, on: bool| h.edit(Edit::TrackMute(index(t)?, on)));
+    func!("solo", |h, t: i64, on: bool| h.edit(Edit::TrackSolo(index(t)?, on)));
+    func!("arm", |h, t: i64, on: bool| h.edit(Edit::TrackArm(index(t)?, on)));
+    func!("rename", |h, t: i64, name: ImmutableString| {
+        h.edit(Edit::TrackName(index(t)?, name.to_string()))
+    });
+
+    // Clips
+    func!("fire_clip", |h, t: i64, s: i64| h.engine.fire_clip(index(t)?, index(s)?));
+    func!("stop_clip", |h, t: i64, s: i64| h.engine.stop_clip(index(t)?, index(s)?));
+    func!("fire_scene", |h, s: i64| h.engine.fire_scene(index(s)?));
+
+    // Queries
+    func!("state", |h| rhai::serde::to_dynamic(h.state.get()).map_err(|e| e.to_string()));
+    func!("search", |h, query: ImmutableString, n: i64| {
+        let n = n.clamp(1, 100) as usize;
+        search_results(tauri::async_runtime::block_on(h.sidecar.search_text(&query, n)))
+    });
+    func!("search_similar", |h, path: ImmutableString, n: i64| {
+        let n = n.clamp(1, 100) as usize;
+        search_results(tauri::async_runtime::block_on(h.sidecar.search_similar(&path, n)))
+    });
+
+    rhai.register_fn("sleep", move |ms: i64| -> Result<(), Box<EvalAltResult>> {
+        let wake = Instant::now() + Duration::from_millis(ms.max(0) as u64);
+        if wake > deadline {
+            return Err("sleep would run past the script timeout".into());
+        }
+        std::thread::sleep(wake - Instant::now());
+        Ok(())
+    });
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::mock::MockEngine;
+    use serde_json::json;
+
+    fn host() -> ScriptHost {
+        let state = StateManager::new();
+        state.set_track_count(3);
+        ScriptHost {
+            engine: Arc::new(MockEngine),
+            state,
+            journal: Journal::new(),
+            sidecar: SidecarClient::new(0),
+        }
+    }
+
+    #[test]
+    fn scripts_edit_through_the_journal_and_read_state() {
+        let host = host();
+        let out = run(
+            host.clone(),
+            r#"
+                let n = state().tracks.len();
+                for t in 0..n { set_volume(t, 0.5); }
+                mute(1, true);
+                print(`${n} tracks`);
+                state().tracks[1].mute
+            "#,
+            &ScriptLimits::default(),
+        );
+        assert_eq!(out.error, None);
+        assert_eq!(out.output, ["3 tracks"]);
+        assert_eq!(out.value, json!(true));
+        assert!(host.state.get().tracks.iter().all(|t| t.volume == 0.5));
+
+        // The whole run is one undo step.
+        host.journal.undo(&*host.engine, &host.state).unwrap();
+        let tracks = host.state.get().tracks;
+        assert!(!tracks[1].mute && tracks.iter().all(|t| t.volume != 0.5));
+    }
+
+    #[test]
+    fn runaway_scripts_are_stopped() {
+        let limits = ScriptLimits {
+            max_operations: 1_000,
+            ..Default::default()
+        };
+        let out = run(host(), "loop {}", &limits);
+        assert!(out.error.unwrap().contains("operations"));
+
+        // No operation limit, so only the clock can stop the loop.
+        let limits = ScriptLimits {
+            max_operations: 0,
+            timeout: Duration::from_millis(50),
+            ..Default::default()
+        };
+        let out = run(host(), "loop {}", &limits);
+        assert!(out.error.unwrap().contains("timed out"));
+        assert!(run(host(), "sleep(60000)", &limits).error.is_some());
+    }
+
+    #[test]
+    fn scripts_are_sandboxed() {
+        let limits = ScriptLimits::default();
+        assert!(run(host(), r#"import "x" as x;"#, &limits).error.is_some());
+        assert!(run(host(), r#"eval("1")"#, &limits).error.is_some());
+        assert!(run(host(), "set_volume(-1, 0.5)", &limits)
+            .error
+            .unwrap()
+            .contains("index"));
+        assert!(load("../secrets").is_err());
+    }
+}
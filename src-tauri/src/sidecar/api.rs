@@ -6,7 +6,7 @@ pub struct SidecarClient {
     base_url: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct SearchResultItem {
     pub id: String,
     pub file_path: String,
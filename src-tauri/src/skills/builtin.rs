@@ -0,0 +1,443 @@
+//! Built-in skills.
+//!
+//! [`descriptors`] lists them; [`execute`] runs one with already validated
+//! params.  Ids are `<area>.<action>`.
+
+use serde_json::Value;
+
+use super::{
+    boolean, index, number, string, ParamType, Params, SkillCategory, SkillContext,
+    SkillDescriptor, SkillParam, SkillResult,
+};
+use crate::automation::Target;
+use crate::engine::notes::NoteTransform;
+use crate::engine::TrackKind;
+use crate::journal::Edit;
+use crate::ramp::{Curve, Ramp};
+
+fn skill(
+    id: &str,
+    name: &str,
+    category: SkillCategory,
+    description: &str,
+    params: Vec<SkillParam>,
+) -> SkillDescriptor {
+    SkillDescriptor {
+        id: id.into(),
+        name: name.into(),
+        description: description.into(),
+        category,
+        params,
+        keyboard_shortcut: None,
+    }
+}
+
+fn range(min: f64, max: f64) -> ParamType {
+    ParamType::Number {
+        min: Some(min),
+        max: Some(max),
+    }
+}
+
+fn track() -> SkillParam {
+    SkillParam::new("track", ParamType::TrackIndex, "Track (0-based)")
+}
+
+fn clip() -> SkillParam {
+    SkillParam::new("clip", ParamType::ClipIndex, "Clip slot / scene (0-based)")
+}
+
+fn flag(name: &str, description: &str) -> SkillParam {
+    SkillParam::new(name, ParamType::Bool, description).optional(true)
+}
+
+/// Start value, duration and curve of a ramp.
+fn glide() -> [SkillParam; 3] {
+    [
+        SkillParam::new(
+            "from",
+            ParamType::Number {
+                min: None,
+                max: None,
+            },
+            "Start value; needed on engines that don't report values",
+        )
+        .optional(Value::Null),
+        SkillParam::new("seconds", range(0.0, 600.0), "Ramp time").optional(4),
+        SkillParam::new("curve", ParamType::String, "linear, exponential or s_curve")
+            .optional("linear"),
+    ]
+}
+
+fn count() -> SkillParam {
+    SkillParam::new("count", range(1.0, 100.0), "Number of results").optional(10)
+}
+
+pub fn descriptors() -> Vec<SkillDescriptor> {
+    use SkillCategory::*;
+    vec![
+        // Transport
+        skill("transport.play", "Play", Transport, "Start playback", vec![]),
+        skill("transport.stop", "Stop", Transport, "Stop playback", vec![]),
+        skill("transport.record", "Record", Transport, "Toggle recording", vec![]),
+        skill("transport.loop", "Loop", Transport, "Toggle the loop", vec![]),
+        skill(
+            "transport.set_tempo",
+            "Set tempo",
+            Transport,
+            "Set the song tempo",
+            vec![SkillParam::new("bpm", range(20.0, 999.0), "Tempo in BPM")],
+        ),
+        skill(
+            "transport.jump",
+            "Jump to position",
+            Transport,
+            "Move the playhead",
+            vec![SkillParam::new(
+                "beats",
+                ParamType::Number {
+                    min: Some(0.0),
+                    max: None,
+                },
+                "Position in beats",
+            )],
+        ),
+        // Track
+        skill(
+            "track.volume",
+            "Set track volume",
+            Track,
+            "Set a track's fader",
+            vec![track(), SkillParam::new("volume", range(0.0, 1.0), "Fader position")],
+        ),
+        skill(
+            "track.pan",
+            "Set track pan",
+            Track,
+            "Pan a track",
+            vec![track(), SkillParam::new("pan", range(-1.0, 1.0), "−1 left to 1 right")],
+        ),
+        skill(
+            "track.mute",
+            "Mute track",
+            Track,
+            "Mute or unmute a track",
+            vec![track(), flag("mute", "Mute (false unmutes)")],
+        ),
+        skill(
+            "track.solo",
+            "Solo track",
+            Track,
+            "Solo or unsolo a track",
+            vec![track(), flag("solo", "Solo (false unsolos)")],
+        ),
+        skill(
+            "track.arm",
+            "Arm track",
+            Track,
+            "Arm or disarm a track for recording",
+            vec![track(), flag("arm", "Arm (false disarms)")],
+        ),
+        skill(
+            "track.fade",
+            "Fade track",
+            Track,
+            "Glide a track's fader to a level",
+            [
+                vec![track(), SkillParam::new("volume", range(0.0, 1.0), "Target fader position")],
+                glide().to_vec(),
+            ]
+            .concat(),
+        ),
+        skill(
+            "param.ramp",
+            "Ramp param",
+            Param,
+            "Glide a custom engine param to a value",
+            [
+                vec![
+                    SkillParam::new("id", ParamType::String, "Param id"),
+                    SkillParam::new(
+                        "value",
+                        ParamType::Number {
+                            min: None,
+                            max: None,
+                        },
+                        "Target value",
+                    ),
+                ],
+                glide().to_vec(),
+            ]
+            .concat(),
+        ),
+        skill(
+            "track.create",
+            "New track",
+            Track,
+            "Append a track",
+            vec![
+                SkillParam::new("kind", ParamType::String, "audio, midi or return")
+                    .optional("midi"),
+            ],
+        ),
+        skill("track.duplicate", "Duplicate track", Track, "Duplicate a track", vec![track()]),
+        skill("track.delete", "Delete track", Track, "Delete a track", vec![track()]),
+        skill(
+            "track.rename",
+            "Rename track",
+            Track,
+            "Rename a track",
+            vec![track(), SkillParam::new("name", ParamType::String, "New name")],
+        ),
+        // Clip
+        skill("clip.fire", "Launch clip", Clip, "Launch a clip", vec![track(), clip()]),
+        skill("clip.stop", "Stop clip", Clip, "Stop a clip", vec![track(), clip()]),
+        skill(
+            "scene.fire",
+            "Launch scene",
+            Clip,
+            "Launch every clip in a scene",
+            vec![SkillParam::new("scene", ParamType::ClipIndex, "Scene (0-based)")],
+        ),
+        skill(
+            "clip.quantize",
+            "Quantize clip",
+            Clip,
+            "Move a MIDI clip's notes onto a grid",
+            vec![
+                track(),
+                clip(),
+                SkillParam::new("grid", range(1.0 / 64.0, 4.0), "Grid in beats").optional(0.25),
+                SkillParam::new("strength", range(0.0, 1.0), "1 snaps fully").optional(1.0),
+            ],
+        ),
+        skill(
+            "clip.transpose",
+            "Transpose clip",
+            Clip,
+            "Shift a MIDI clip's notes",
+            vec![
+                track(),
+                clip(),
+                SkillParam::new("semitones", range(-48.0, 48.0), "Semitones"),
+            ],
+        ),
+        skill(
+            "clip.velocity",
+            "Scale velocities",
+            Clip,
+            "Multiply a MIDI clip's velocities",
+            vec![
+                track(),
+                clip(),
+                SkillParam::new("factor", range(0.0, 4.0), "Velocity multiplier"),
+            ],
+        ),
+        // Search
+        skill(
+            "search.text",
+            "Search samples",
+            Search,
+            "Find samples by description",
+            vec![SkillParam::new("query", ParamType::String, "What to look for"), count()],
+        ),
+        skill(
+            "search.similar",
+            "Find similar",
+            Search,
+            "Find samples that sound like a file",
+            vec![
+                SkillParam::new("path", ParamType::FilePath, "Reference audio file"),
+                count(),
+            ],
+        ),
+        skill(
+            "search.insert",
+            "Insert sample",
+            Search,
+            "Load a sample into a clip slot",
+            vec![
+                SkillParam::new("path", ParamType::FilePath, "Audio file"),
+                track(),
+                clip(),
+            ],
+        ),
+        // Utility
+        skill("utility.undo", "Undo", Utility, "Undo the last edit", vec![]),
+        skill("utility.redo", "Redo", Utility, "Redo the last undone edit", vec![]),
+    ]
+}
+
+fn track_label(ctx: &SkillContext<'_>, track: i32) -> String {
+    ctx.state
+        .get()
+        .tracks
+        .get(track as usize)
+        .map(|t| t.name.clone())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| format!("Track {}", track + 1))
+}
+
+fn on_off(on: bool, yes: &str, no: &str) -> String {
+    if on { yes } else { no }.to_string()
+}
+
+/// Run a ramp skill's ramp to its end.  Returns whether it got there.
+async fn ramp(ctx: &SkillContext<'_>, p: &Params, target: Target, to: f64) -> Result<bool, String> {
+    let curve: Curve = serde_json::from_value(Value::from(string(p, "curve")?))
+        .map_err(|_| "curve must be linear, exponential or s_curve".to_string())?;
+    let ramp = Ramp {
+        target,
+        from: p.get("from").and_then(Value::as_f64),
+        to,
+        duration_ms: (number(p, "seconds")? * 1000.0).round() as u64,
+        curve,
+    };
+    ctx.ramps.run(ctx.engine, ctx.state, ctx.journal, &ramp).await
+}
+
+/// Run built-in skill `id`.  `p` must have been validated against its
+/// descriptor.
+pub async fn execute(id: &str, p: &Params, ctx: &SkillContext<'_>) -> Result<SkillResult, String> {
+    let engine = ctx.engine;
+    let edit = |e: Edit| ctx.journal.apply(engine, ctx.state, e);
+    let rewrite = |transform: NoteTransform| {
+        let (track, clip) = (index(p, "track")?, index(p, "clip")?);
+        // Reading the clip waits for the engine; keep the async worker free.
+        let notes =
+            tokio::task::block_in_place(|| transform.rewrite_clip(engine, track, clip))?;
+        ctx.journal.record_native();
+        Ok::<_, String>(format!("{} notes updated", notes.len()))
+    };
+
+    let message = match id {
+        "transport.play" => engine.play().map(|_| "Playing".to_string())?,
+        "transport.stop" => engine.stop().map(|_| "Stopped".to_string())?,
+        "transport.record" => engine.toggle_record().map(|_| "Record toggled".to_string())?,
+        "transport.loop" => {
+            let on = !ctx.state.get().loop_enabled;
+            edit(Edit::Loop(on))?;
+            on_off(on, "Loop on", "Loop off")
+        }
+        "transport.set_tempo" => {
+            let bpm = number(p, "bpm")?;
+            edit(Edit::Tempo(bpm))?;
+            format!("Tempo {bpm} BPM")
+        }
+        "transport.jump" => {
+            let beats = number(p, "beats")?;
+            engine.set_song_time(beats)?;
+            format!("Jumped to beat {beats}")
+        }
+
+        "track.volume" => {
+            let t = index(p, "track")?;
+            edit(Edit::TrackVolume(t, number(p, "volume")?))?;
+            format!("{} volume set", track_label(ctx, t))
+        }
+        "track.pan" => {
+            let t = index(p, "track")?;
+            edit(Edit::TrackPan(t, number(p, "pan")?))?;
+            format!("{} pan set", track_label(ctx, t))
+        }
+        "track.mute" => {
+            let (t, on) = (index(p, "track")?, boolean(p, "mute")?);
+            edit(Edit::TrackMute(t, on))?;
+            format!("{} {}", track_label(ctx, t), on_off(on, "muted", "unmuted"))
+        }
+        "track.solo" => {
+            let (t, on) = (index(p, "track")?, boolean(p, "solo")?);
+            edit(Edit::TrackSolo(t, on))?;
+            format!("{} {}", track_label(ctx, t), on_off(on, "soloed", "unsoloed"))
+        }
+        "track.arm" => {
+            let (t, on) = (index(p, "track")?, boolean(p, "arm")?);
+            edit(Edit::TrackArm(t, on))?;
+            format!("{} {}", track_label(ctx, t), on_off(on, "armed", "disarmed"))
+        }
+        "track.fade" => {
+            let t = index(p, "track")?;
+            let target = Target::TrackVolume { track: t as usize };
+            let done = ramp(ctx, p, target, number(p, "volume")?).await?;
+            format!("{} {}", track_label(ctx, t), on_off(done, "faded", "fade stopped"))
+        }
+        "param.ramp" => {
+            let id = string(p, "id")?;
+            let target = Target::CustomParam { id: id.to_string() };
+            let done = ramp(ctx, p, target, number(p, "value")?).await?;
+            format!("{id} {}", on_off(done, "ramped", "ramp stopped"))
+        }
+        "track.create" => {
+            let kind: TrackKind = serde_json::from_value(Value::from(string(p, "kind")?))
+                .map_err(|_| "kind must be audio, midi or return".to_string())?;
+            engine.create_track(kind, -1)?;
+            ctx.journal.record_native();
+            "Track created".to_string()
+        }
+        "track.duplicate" => {
+            let t = index(p, "track")?;
+            engine.duplicate_track(t)?;
+            ctx.journal.record_native();
+            format!("{} duplicated", track_label(ctx, t))
+        }
+        "track.delete" => {
+            let t = index(p, "track")?;
+            let label = track_label(ctx, t);
+            engine.delete_track(t)?;
+            ctx.journal.record_native();
+            format!("{label} deleted")
+        }
+        "track.rename" => {
+            let (t, name) = (index(p, "track")?, string(p, "name")?);
+            edit(Edit::TrackName(t, name.to_string()))?;
+            format!("Renamed to {name}")
+        }
+
+        "clip.fire" => {
+            engine.fire_clip(index(p, "track")?, index(p, "clip")?)?;
+            "Clip launched".to_string()
+        }
+        "clip.stop" => {
+            engine.stop_clip(index(p, "track")?, index(p, "clip")?)?;
+            "Clip stopped".to_string()
+        }
+        "scene.fire" => {
+            engine.fire_scene(index(p, "scene")?)?;
+            "Scene launched".to_string()
+        }
+        "clip.quantize" => rewrite(NoteTransform::Quantize {
+            grid: number(p, "grid")?,
+            strength: number(p, "strength")?,
+        })?,
+        "clip.transpose" => rewrite(NoteTransform::Transpose {
+            semitones: number(p, "semitones")?.round() as i32,
+        })?,
+        "clip.velocity" => rewrite(NoteTransform::ScaleVelocity {
+            factor: number(p, "factor")?,
+        })?,
+
+        "search.text" | "search.similar" => {
+            let n = number(p, "count")? as usize;
+            let resp = if id == "search.text" {
+                ctx.sidecar.search_text(string(p, "query")?, n).await
+            } else {
+                ctx.sidecar.search_similar(string(p, "path")?, n).await
+            }
+            .map_err(|e| e.to_string())?;
+            let data = serde_json::to_value(&resp.results).map_err(|e| e.to_string())?;
+            return Ok(SkillResult::ok(format!("{} results", resp.results.len())).with_data(data));
+        }
+        "search.insert" => {
+            let path = string(p, "path")?;
+            engine.load_sample(index(p, "track")?, index(p, "clip")?, path)?;
+            ctx.journal.record_native();
+            format!("Inserted {path}")
+        }
+
+        "utility.undo" => ctx.journal.undo(engine, ctx.state).map(|_| "Undone".to_string())?,
+        "utility.redo" => ctx.journal.redo(engine, ctx.state).map(|_| "Redone".to_string())?,
+
+        _ => return Err(format!("Unknown skill '{id}'")),
+    };
+    Ok(SkillResult::ok(message))
+}
@@ -0,0 +1,563 @@
+//! Macro skills: user-defined sequences of built-in skills.
+//!
+//! Every `~/.config/maestro/skills/<name>.toml` becomes skill `macro.<name>`:
+//!
+//! ```toml
+//! name = "Drums down"
+//! description = "Mute the drums and slow down"
+//!
+//! [[params]]
+//! name = "bpm"
+//! type = "number"
+//! min = 20
+//! max = 999
+//! default = 90
+//!
+//! [[steps]]
+//! skill = "track.mute"
+//! tracks = "drum*"          # once per track whose name matches
+//! params = { mute = true }
+//!
+//! [[steps]]
+//! skill = "transport.set_tempo"
+//! delay_ms = 500            # wait before this step
+//! params = { bpm = "{bpm}" }
+//! ```
+//!
+//! A string that is exactly `"{param}"` takes the param's typed value;
+//! placeholders inside longer strings are replaced by its text.  Files are
+//! checked against the built-in skills when loaded, and every step is
+//! validated before the first one runs.  Undoable edits made by a macro are
+//! one undo step, and are rolled back if a later step fails.  Steps that
+//! can't be undone (starting playback, firing a clip) stay done, and the
+//! failure names them.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{
+    builtin, check_type, validate, ParamType, Params, SkillCategory, SkillContext, SkillDescriptor,
+    SkillParam, SkillResult,
+};
+use crate::engine::EngineState;
+use crate::journal::Journal;
+
+/// Where macro files are loaded from.
+pub fn skills_dir() -> PathBuf {
+    crate::config::config_dir().join("skills")
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MacroFile {
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default = "utility")]
+    category: SkillCategory,
+    #[serde(default)]
+    params: Vec<MacroParam>,
+    steps: Vec<MacroStep>,
+}
+
+fn utility() -> SkillCategory {
+    SkillCategory::Utility
+}
+
+#[derive(Debug, Deserialize)]
+struct MacroParam {
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(flatten)]
+    param_type: ParamType,
+    /// Makes the param optional.
+    #[serde(default)]
+    default: Option<Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MacroStep {
+    /// Built-in skill id; a step without one only waits.
+    #[serde(default)]
+    skill: Option<String>,
+    #[serde(default)]
+    params: Params,
+    /// Track name pattern (`*` and `?`, case-insensitive).  The step runs
+    /// once per matching track, which fills its `track` param.
+    #[serde(default)]
+    tracks: Option<String>,
+    #[serde(default)]
+    delay_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct MacroSkill {
+    pub descriptor: SkillDescriptor,
+    steps: Vec<MacroStep>,
+}
+
+impl MacroSkill {
+    /// Parse a macro file and check it against `builtins`.
+    pub fn parse(id: &str, content: &str, builtins: &[SkillDescriptor]) -> Result<Self, String> {
+        let file: MacroFile = toml::from_str(content).map_err(|e| e.to_string())?;
+        let empty = EngineState::default();
+
+        let mut params = Vec::new();
+        for p in file.params {
+            if params.iter().any(|q: &SkillParam| q.name == p.name) {
+                return Err(format!("parameter '{}' is declared twice", p.name));
+            }
+            let mut param = SkillParam::new(&p.name, p.param_type, &p.description);
+            if let Some(default) = p.default {
+                check_type(&param.param_type, &default, &empty)
+                    .map_err(|e| format!("parameter '{}' default: {}", p.name, e))?;
+                param = param.optional(default);
+            }
+            params.push(param);
+        }
+
+        if file.steps.is_empty() {
+            return Err("a macro needs at least one step".into());
+        }
+        for (i, step) in file.steps.iter().enumerate() {
+            check_step(step, &params, builtins, &empty)
+                .map_err(|e| format!("step {}: {}", i + 1, e))?;
+        }
+
+        Ok(Self {
+            descriptor: SkillDescriptor {
+                id: id.into(),
+                name: file.name,
+                description: file.description,
+                category: file.category,
+                params,
+                keyboard_shortcut: None,
+            },
+            steps: file.steps,
+        })
+    }
+
+    /// Run the macro with params already validated against its descriptor.
+    pub async fn execute(
+        &self,
+        params: &Params,
+        ctx: &SkillContext<'_>,
+        builtins: &[SkillDescriptor],
+    ) -> Result<SkillResult, String> {
+        let plan = self.plan(params, builtins, &ctx.state.get())?;
+
+        let parent = ctx.journal;
+        let journal = parent.child();
+        let ctx = SkillContext {
+            journal: &journal,
+            ..*ctx
+        };
+        // The skill behind each journal step, and the skills that left none.
+        let mut owners = Vec::new();
+        let mut unjournaled = Vec::new();
+        let mut ran = 0;
+        for (delay_ms, call) in plan {
+            if delay_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            }
+            let Some((id, args)) = call else { continue };
+            let before = journal.steps();
+            let result = builtin::execute(&id, &args, &ctx).await;
+            let added = journal.steps() - before;
+            owners.extend(std::iter::repeat(id.clone()).take(added));
+            if let Err(e) = result {
+                let outcome = roll_back(&journal, &ctx, &owners, unjournaled);
+                return Err(format!("{id} failed: {e}{outcome}"));
+            }
+            if added == 0 {
+                unjournaled.push(id);
+            }
+            ran += 1;
+        }
+        parent.adopt(&journal);
+        Ok(SkillResult::ok(format!(
+            "{}: {} steps run",
+            self.descriptor.name, ran
+        )))
+    }
+
+    /// Resolve every step into `(delay, skill call)` against the current
+    /// state, so nothing runs unless all of them are valid.
+    #[allow(clippy::type_complexity)]
+    fn plan(
+        &self,
+        params: &Params,
+        builtins: &[SkillDescriptor],
+        state: &EngineState,
+    ) -> Result<Vec<(u64, Option<(String, Params)>)>, String> {
+        let mut plan = Vec::new();
+        for (i, step) in self.steps.iter().enumerate() {
+            let fail = |e: String| format!("step {}: {}", i + 1, e);
+            let Some(id) = &step.skill else {
+                plan.push((step.delay_ms, None));
+                continue;
+            };
+            let target = builtins
+                .iter()
+                .find(|s| &s.id == id)
+                .ok_or_else(|| fail(format!("unknown skill '{id}'")))?;
+            let args = match substitute(&Value::Object(step.params.clone()), params) {
+                Value::Object(args) => args,
+                _ => unreachable!(),
+            };
+            let tracks = match &step.tracks {
+                Some(pattern) => {
+                    let matched = matching_tracks(pattern, state);
+                    if matched.is_empty() {
+                        return Err(fail(format!("no track matches '{pattern}'")));
+                    }
+                    matched.into_iter().map(Some).collect()
+                }
+                None => vec![None],
+            };
+            for (n, track) in tracks.into_iter().enumerate() {
+                let mut args = args.clone();
+                if let Some(t) = track {
+                    args.insert("track".into(), Value::from(t));
+                }
+                let args = validate(target, &args, state).map_err(fail)?;
+                let delay = if n == 0 { step.delay_ms } else { 0 };
+                plan.push((delay, Some((id.clone(), args))));
+            }
+        }
+        Ok(plan)
+    }
+}
+
+/// Roll back a failed macro's `journal` and describe what happened.
+/// `owners` names the skill behind each journal step; `kept` holds the
+/// skills that couldn't be undone to begin with.
+fn roll_back(
+    journal: &Journal,
+    ctx: &SkillContext<'_>,
+    owners: &[String],
+    mut kept: Vec<String>,
+) -> String {
+    if owners.is_empty() && kept.is_empty() {
+        return String::new();
+    }
+    match journal.rollback(ctx.engine, ctx.state) {
+        Ok(stale) => {
+            kept.extend(stale.into_iter().filter_map(|i| owners.get(i).cloned()));
+            kept.dedup();
+            match kept.is_empty() {
+                true => " (changes rolled back)".into(),
+                false => format!(" (changes rolled back, except {})", kept.join(", ")),
+            }
+        }
+        Err(r) => format!("; rolling back also failed: {r}"),
+    }
+}
+
+fn check_step(
+    step: &MacroStep,
+    params: &[SkillParam],
+    builtins: &[SkillDescriptor],
+    empty: &EngineState,
+) -> Result<(), String> {
+    let Some(id) = &step.skill else {
+        if !step.params.is_empty() || step.tracks.is_some() {
+            return Err("'params' and 'tracks' need a 'skill'".into());
+        }
+        if step.delay_ms == 0 {
+            return Err("a step needs a 'skill' or a 'delay_ms'".into());
+        }
+        return Ok(());
+    };
+    let target = builtins
+        .iter()
+        .find(|s| &s.id == id)
+        .ok_or_else(|| format!("unknown skill '{id}'"))?;
+    // Search results have nowhere to go, and undo/redo would fight the
+    // macro's own rollback.
+    if matches!(
+        target.category,
+        SkillCategory::Search | SkillCategory::Utility
+    ) {
+        return Err(format!("'{id}' can't be used in a macro"));
+    }
+
+    let takes_track = target.params.iter().any(|p| p.name == "track");
+    if step.tracks.is_some() {
+        if !takes_track {
+            return Err(format!("'{id}' has no track to select"));
+        }
+        if step.params.contains_key("track") {
+            return Err("use either 'tracks' or a 'track' param, not both".into());
+        }
+    }
+
+    for (key, value) in &step.params {
+        let param = target
+            .params
+            .iter()
+            .find(|p| &p.name == key)
+            .ok_or_else(|| format!("'{id}' has no parameter '{key}'"))?;
+        let names = placeholders(value);
+        if let Some(unknown) = names.iter().find(|n| !params.iter().any(|p| &p.name == *n)) {
+            return Err(format!("'{{{unknown}}}' is not a parameter of this macro"));
+        }
+        if names.is_empty() {
+            check_type(&param.param_type, value, empty)
+                .map_err(|e| format!("'{id}' parameter '{key}': {e}"))?;
+        }
+    }
+
+    if let Some(missing) = target.params.iter().find(|p| {
+        p.required
+            && !step.params.contains_key(&p.name)
+            && !(p.name == "track" && step.tracks.is_some())
+    }) {
+        return Err(format!("'{id}' requires parameter '{}'", missing.name));
+    }
+    Ok(())
+}
+
+/// `{name}` placeholders used anywhere in `value`.
+fn placeholders(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => {
+            let mut names = Vec::new();
+            let mut rest = s.as_str();
+            while let Some(open) = rest.find('{') {
+                rest = &rest[open + 1..];
+                let Some(close) = rest.find('}') else { break };
+                let name = &rest[..close];
+                if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                    names.push(name.to_string());
+                    rest = &rest[close + 1..];
+                }
+            }
+            names
+        }
+        Value::Array(items) => items.iter().flat_map(placeholders).collect(),
+        Value::Object(map) => map.values().flat_map(placeholders).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn substitute(value: &Value, params: &Params) -> Value {
+    match value {
+        Value::String(s) => {
+            let whole = s.strip_prefix('{').and_then(|r| r.strip_suffix('}'));
+            if let Some(v) = whole.and_then(|name| params.get(name)) {
+                return v.clone();
+            }
+            let mut out = s.clone();
+            for (name, v) in params {
+                let text = match v {
+                    Value::String(s) => s.clone(),
+                    Value::Null => String::new(),
+                    other => other.to_string(),
+                };
+                out = out.replace(&format!("{{{name}}}"), &text);
+            }
+            Value::String(out)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|v| substitute(v, params)).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), substitute(v, params)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn matching_tracks(pattern: &str, state: &EngineState) -> Vec<usize> {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    state
+        .tracks
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| glob_match(&pattern, &t.name.to_lowercase().chars().collect::<Vec<_>>()))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some(('*', rest)) => (0..=text.len()).any(|i| glob_match(rest, &text[i..])),
+        Some((&c, rest)) => match text.split_first() {
+            Some((&t, text)) if c == '?' || c == t => glob_match(rest, text),
+            _ => false,
+        },
+    }
+}
+
+/// Load every `*.toml` in `dir`, in file name order.  Files that fail to
+/// parse are skipped and reported as `"<file>: <error>"`.
+pub fn load_dir(dir: &Path, builtins: &[SkillDescriptor]) -> (Vec<MacroSkill>, Vec<String>) {
+    let mut paths: Vec<PathBuf> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+            .collect(),
+        Err(_) => return (Vec::new(), Vec::new()),
+    };
+    paths.sort();
+
+    let mut macros = Vec::new();
+    let mut errors = Vec::new();
+    for path in paths {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let result = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| MacroSkill::parse(&format!("macro.{stem}"), &content, builtins));
+        match result {
+            Ok(skill) => macros.push(skill),
+            Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    (macros, errors)
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const DRUMS: &str = r#"
+        name = "Drums down"
+
+        [[params]]
+        name = "bpm"
+        type = "number"
+        min = 20
+        max = 999
+        default = 90
+
+        [[steps]]
+        skill = "track.mute"
+        tracks = "drum*"
+        params = { mute = true }
+
+        [[steps]]
+        skill = "transport.set_tempo"
+        delay_ms = 10
+        params = { bpm = "{bpm}" }
+    "#;
+
+    fn parse(content: &str) -> Result<MacroSkill, String> {
+        MacroSkill::parse("macro.test", content, &builtin::descriptors())
+    }
+
+    #[test]
+    fn macro_files_are_checked_against_builtins() {
+        let skill = parse(DRUMS).unwrap();
+        assert_eq!(skill.descriptor.params[0].default_value, json!(90));
+        assert!(!skill.descriptor.params[0].required);
+
+        let err = |steps: &str| parse(&format!("name = \"x\"\n{steps}")).unwrap_err();
+        assert!(err("[[steps]]\nskill = \"nope\"").contains("unknown skill"));
+        assert!(err("[[steps]]\nskill = \"utility.undo\"").contains("can't be used"));
+        assert!(err("[[steps]]\nskill = \"track.mute\"").contains("requires parameter 'track'"));
+        assert!(err("[[steps]]\nskill = \"transport.play\"\ntracks = \"*\"")
+            .contains("no track to select"));
+        assert!(
+            err("[[steps]]\nskill = \"transport.set_tempo\"\nparams = { bpm = 5 }")
+                .contains("outside")
+        );
+        assert!(
+            err("[[steps]]\nskill = \"transport.set_tempo\"\nparams = { bpm = \"{x}\" }")
+                .contains("'{x}' is not a parameter")
+        );
+        assert!(err("[[steps]]\nskil = \"transport.play\"").contains("unknown field"));
+    }
+
+    #[test]
+    fn plans_expand_selectors_and_substitute_params() {
+        let skill = parse(DRUMS).unwrap();
+        let mut state = EngineState::default();
+        state.tracks.resize_with(3, Default::default);
+        for (t, name) in state.tracks.iter_mut().zip(["Drums", "Bass", "drum bus"]) {
+            t.name = name.into();
+        }
+        let params = validate(&skill.descriptor, &Params::new(), &state).unwrap();
+        let plan = skill
+            .plan(&params, &builtin::descriptors(), &state)
+            .unwrap();
+
+        let tracks: Vec<_> = plan[..2]
+            .iter()
+            .map(|(_, c)| c.as_ref().unwrap().1["track"].clone())
+            .collect();
+        assert_eq!(tracks, [json!(0), json!(2)]);
+        let (delay, call) = &plan[2];
+        assert_eq!((*delay, &call.as_ref().unwrap().1["bpm"]), (10, &json!(90)));
+
+        state.tracks.truncate(2);
+        state.tracks[0].name = "Keys".into();
+        assert!(skill
+            .plan(&params, &builtin::descriptors(), &state)
+            .unwrap_err()
+            .contains("no track"));
+    }
+
+    #[test]
+    fn substitution_keeps_types_for_whole_placeholders() {
+        let params = json!({ "n": 3, "name": "Lead" })
+            .as_object()
+            .unwrap()
+            .clone();
+        assert_eq!(substitute(&json!("{n}"), &params), json!(3));
+        assert_eq!(substitute(&json!("{name} {n}"), &params), json!("Lead 3"));
+        assert_eq!(placeholders(&json!(["{a} {b}", "{}", "x"])), ["a", "b"]);
+    }
+
+    #[test]
+    fn failures_name_the_steps_left_in_place() {
+        let engine = crate::engine::mock::MockEngine;
+        let state = crate::engine::StateManager::new();
+        state.set_track_count(1);
+        let (sidecar, ramps) = (crate::sidecar::api::SidecarClient::new(0), Default::default());
+        let parent = Journal::new();
+        let journal = parent.child();
+        let ctx = SkillContext {
+            engine: &engine,
+            state: &state,
+            journal: &journal,
+            sidecar: &sidecar,
+            ramps: &ramps,
+        };
+        assert_eq!(roll_back(&journal, &ctx, &[], Vec::new()), "");
+
+        journal.record_native();
+        journal
+            .apply(&engine, &state, crate::journal::Edit::TrackVolume(0, 0.3))
+            .unwrap();
+        let owners = ["track.duplicate".to_string(), "track.volume".to_string()];
+        let outcome = roll_back(&journal, &ctx, &owners, vec!["transport.play".into()]);
+        assert_eq!(
+            outcome,
+            " (changes rolled back, except transport.play, track.duplicate)"
+        );
+        assert_eq!(journal.steps(), 0);
+    }
+
+    #[test]
+    fn globs_match_wildcards() {
+        let m = |p: &str, t: &str| {
+            glob_match(
+                &p.chars().collect::<Vec<_>>(),
+                &t.chars().collect::<Vec<_>>(),
+            )
+        };
+        assert!(m("dr?m*", "drums 2"));
+        assert!(m("*", ""));
+        assert!(!m("drum", "drums"));
+    }
+}
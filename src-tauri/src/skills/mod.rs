@@ -0,0 +1,371 @@
+//! Skills: named quick operations the UI can list and run.
+//!
+//! Each skill is described by a [`SkillDescriptor`] (mirrored by
+//! `src/lib/types/skills.ts`) whose params are validated against their
+//! [`ParamType`] before the skill runs.  Built-in skills live in
+//! [`builtin`] and dispatch to the `EngineAdapter` (value edits go through
+//! the undo journal) or the search sidecar.  User macros from the config
+//! directory ([`macros`]) chain built-in skills together.
+
+pub mod builtin;
+pub mod macros;
+
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::engine::{EngineAdapter, EngineState, StateManager};
+use crate::journal::Journal;
+use crate::ramp::Ramps;
+use crate::sidecar::api::SidecarClient;
+
+/// Skill arguments by param name.
+pub type Params = Map<String, Value>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ParamType {
+    Number {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<f64>,
+    },
+    String,
+    Bool,
+    TrackIndex,
+    /// Clip slot (= scene) index.
+    ClipIndex,
+    FilePath,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillParam {
+    pub name: String,
+    pub param_type: ParamType,
+    pub description: String,
+    pub required: bool,
+    /// Used when an optional param is omitted; `null` for none.
+    #[serde(default)]
+    pub default_value: Value,
+}
+
+impl SkillParam {
+    pub fn new(name: &str, param_type: ParamType, description: &str) -> Self {
+        Self {
+            name: name.into(),
+            param_type,
+            description: description.into(),
+            required: true,
+            default_value: Value::Null,
+        }
+    }
+
+    pub fn optional(mut self, default_value: impl Into<Value>) -> Self {
+        self.required = false;
+        self.default_value = default_value.into();
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SkillCategory {
+    Transport,
+    Track,
+    Clip,
+    /// Custom engine params.
+    Param,
+    Search,
+    Utility,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillDescriptor {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub category: SkillCategory,
+    pub params: Vec<SkillParam>,
+    pub keyboard_shortcut: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillResult {
+    pub success: bool,
+    pub message: String,
+    /// Skill output, e.g. search results.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl SkillResult {
+    pub fn ok(message: impl Into<String>) -> Self {
+        Self {
+            success: true,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn failed(message: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+/// What a running skill can reach.
+pub struct SkillContext<'a> {
+    pub engine: &'a dyn EngineAdapter,
+    pub state: &'a StateManager,
+    pub journal: &'a Journal,
+    pub sidecar: &'a SidecarClient,
+    pub ramps: &'a Ramps,
+}
+
+// ── Validation ─────────────────────────────────────────────────────────────
+
+/// Check `params` against the skill's declared params and fill in defaults.
+///
+/// Track and clip indices are checked against the current engine state when
+/// it knows any tracks/scenes (engines that don't report them accept any
+/// non-negative index).
+pub fn validate(
+    skill: &SkillDescriptor,
+    params: &Params,
+    state: &EngineState,
+) -> Result<Params, String> {
+    if let Some(unknown) = params.keys().find(|k| !skill.params.iter().any(|p| &p.name == *k)) {
+        return Err(format!("'{}' has no parameter '{}'", skill.id, unknown));
+    }
+    let mut out = Params::new();
+    for param in &skill.params {
+        let value = match params.get(&param.name).filter(|v| !v.is_null()) {
+            Some(v) => v.clone(),
+            None if param.required => {
+                return Err(format!("'{}' requires parameter '{}'", skill.id, param.name));
+            }
+            None => param.default_value.clone(),
+        };
+        if !value.is_null() {
+            check_type(&param.param_type, &value, state)
+                .map_err(|e| format!("'{}' parameter '{}': {}", skill.id, param.name, e))?;
+        }
+        out.insert(param.name.clone(), value);
+    }
+    Ok(out)
+}
+
+fn check_type(param_type: &ParamType, value: &Value, state: &EngineState) -> Result<(), String> {
+    let index_below = |limit: usize, what: &str| {
+        let i = value
+            .as_u64()
+            .ok_or_else(|| format!("expected a {what} index, got {value}"))?;
+        if limit > 0 && i as usize >= limit {
+            return Err(format!("{what} {i} does not exist (there are {limit})"));
+        }
+        Ok(())
+    };
+    match param_type {
+        ParamType::Number { min, max } => {
+            let n = value
+                .as_f64()
+                .ok_or_else(|| format!("expected a number, got {value}"))?;
+            if min.is_some_and(|min| n < min) || max.is_some_and(|max| n > max) {
+                return Err(format!(
+                    "{n} is outside {}..{}",
+                    min.map_or("".into(), |v| v.to_string()),
+                    max.map_or("".into(), |v| v.to_string())
+                ));
+            }
+            Ok(())
+        }
+        ParamType::String | ParamType::FilePath => match value.as_str() {
+            Some(s) if param_type == &ParamType::FilePath && s.trim().is_empty() => {
+                Err("expected a file path".into())
+            }
+            Some(_) => Ok(()),
+            None => Err(format!("expected a string, got {value}")),
+        },
+        ParamType::Bool => match value {
+            Value::Bool(_) => Ok(()),
+            _ => Err(format!("expected true or false, got {value}")),
+        },
+        ParamType::TrackIndex => index_below(state.tracks.len(), "track"),
+        ParamType::ClipIndex => index_below(state.num_scenes, "clip slot"),
+    }
+}
+
+// ── Param accessors (for validated params) ─────────────────────────────────
+
+pub fn number(params: &Params, name: &str) -> Result<f64, String> {
+    params
+        .get(name)
+        .and_then(Value::as_f64)
+        .ok_or_else(|| format!("missing number '{name}'"))
+}
+
+pub fn index(params: &Params, name: &str) -> Result<i32, String> {
+    params
+        .get(name)
+        .and_then(Value::as_u64)
+        .map(|i| i as i32)
+        .ok_or_else(|| format!("missing index '{name}'"))
+}
+
+pub fn boolean(params: &Params, name: &str) -> Result<bool, String> {
+    params
+        .get(name)
+        .and_then(Value::as_bool)
+        .ok_or_else(|| format!("missing flag '{name}'"))
+}
+
+pub fn string<'a>(params: &'a Params, name: &str) -> Result<&'a str, String> {
+    params
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("missing text '{name}'"))
+}
+
+// ── Registry ───────────────────────────────────────────────────────────────
+
+/// Every skill Maestro can run.
+pub struct SkillRegistry {
+    builtins: Vec<SkillDescriptor>,
+    macros: RwLock<Vec<macros::MacroSkill>>,
+}
+
+impl SkillRegistry {
+    /// Built-in skills only; call [`reload_macros`](Self::reload_macros)
+    /// to add the user's macros.
+    pub fn new() -> Self {
+        Self {
+            builtins: builtin::descriptors(),
+            macros: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Re-read the macro files.  Returns (and logs) the files that failed
+    /// to load; the others replace the previously loaded macros.
+    pub fn reload_macros(&self) -> Vec<String> {
+        let dir = macros::skills_dir();
+        let (loaded, errors) = macros::load_dir(&dir, &self.builtins);
+        for e in &errors {
+            log::warn!("Skipping macro skill {}", e);
+        }
+        log::info!("Loaded {} macro skill(s) from {}", loaded.len(), dir.display());
+        *self.macros.write().unwrap() = loaded;
+        errors
+    }
+
+    pub fn list(&self) -> Vec<SkillDescriptor> {
+        let macros = self.macros.read().unwrap();
+        let mut skills = self.builtins.clone();
+        skills.extend(macros.iter().map(|m| m.descriptor.clone()));
+        skills
+    }
+
+    pub fn get(&self, id: &str) -> Option<SkillDescriptor> {
+        self.builtins.iter().find(|s| s.id == id).cloned().or_else(|| {
+            let macros = self.macros.read().unwrap();
+            macros.iter().find(|m| m.descriptor.id == id).map(|m| m.descriptor.clone())
+        })
+    }
+
+    /// Validate `params` and run the skill.
+    pub async fn execute(
+        &self,
+        id: &str,
+        params: &Params,
+        ctx: &SkillContext<'_>,
+    ) -> Result<SkillResult, String> {
+        let skill = self.get(id).ok_or_else(|| format!("Unknown skill '{id}'"))?;
+        let params = validate(&skill, params, &ctx.state.get())?;
+        let user_macro = {
+            let macros = self.macros.read().unwrap();
+            macros.iter().find(|m| m.descriptor.id == id).cloned()
+        };
+        match user_macro {
+            Some(m) => m.execute(&params, ctx, &self.builtins).await,
+            None => builtin::execute(id, &params, ctx).await,
+        }
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn skill() -> SkillDescriptor {
+        SkillDescriptor {
+            id: "test.skill".into(),
+            name: "Test".into(),
+            description: String::new(),
+            category: SkillCategory::Utility,
+            params: vec![
+                SkillParam::new("track", ParamType::TrackIndex, ""),
+                SkillParam::new(
+                    "level",
+                    ParamType::Number {
+                        min: Some(0.0),
+                        max: Some(1.0),
+                    },
+                    "",
+                )
+                .optional(0.5),
+            ],
+            keyboard_shortcut: None,
+        }
+    }
+
+    fn params(v: Value) -> Params {
+        v.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn validation_fills_defaults_and_checks_types() {
+        let mut state = EngineState::default();
+        state.tracks.resize_with(2, Default::default);
+
+        let ok = validate(&skill(), &params(json!({ "track": 1 })), &state).unwrap();
+        assert_eq!(ok["level"], json!(0.5));
+
+        let err = |p: Value| validate(&skill(), &params(p), &state).unwrap_err();
+        assert!(err(json!({})).contains("requires parameter 'track'"));
+        assert!(err(json!({ "track": 2 })).contains("does not exist"));
+        assert!(err(json!({ "track": -1 })).contains("expected a track index"));
+        assert!(err(json!({ "track": 0, "level": 2 })).contains("outside 0..1"));
+        assert!(err(json!({ "track": 0, "pan": 0 })).contains("no parameter 'pan'"));
+    }
+
+    #[test]
+    fn descriptors_serialize_like_the_ts_types() {
+        let v = serde_json::to_value(skill()).unwrap();
+        assert_eq!(v["params"][0]["paramType"], json!({ "type": "trackIndex" }));
+        assert_eq!(v["params"][1]["defaultValue"], json!(0.5));
+        assert_eq!(v["category"], json!("Utility"));
+        assert_eq!(v["keyboardShortcut"], Value::Null);
+    }
+
+    #[test]
+    fn builtin_ids_are_unique() {
+        let skills = builtin::descriptors();
+        let mut ids: Vec<_> = skills.iter().map(|s| &s.id).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), skills.len());
+    }
+}
@@ -0,0 +1,301 @@
+//! Mixer snapshots for A/B comparisons.
+//!
+//! A [`Snapshot`] holds the mixer values of `EngineState` — track volume,
+//! pan, mute, solo and sends, return and master volume/pan — plus the custom
+//! params the engine reports values for.  Snapshots are saved as JSON in
+//! the open project's workspace folder, or `~/.config/maestro/snapshots/`
+//! when no workspace is open.  Recalling one applies only the values
+//! that differ, through the undo journal; [`morph_frame`] interpolates
+//! between two of them.
+//!
+//! Channels are matched by index, so a snapshot recalled after tracks were
+//! added or removed only touches the tracks both have.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::engine::{EngineState, ParamDef, TrackState};
+use crate::journal::Edit;
+
+/// Values closer than this count as equal when diffing.
+const EPSILON: f64 = 1e-4;
+
+pub fn snapshots_dir() -> PathBuf {
+    crate::config::config_dir().join("snapshots")
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelSnapshot {
+    /// For display; recall matches channels by index.
+    pub name: String,
+    pub volume: f64,
+    pub pan: f64,
+    /// Only recalled for tracks (engines expose no return/master mute).
+    #[serde(default)]
+    pub mute: bool,
+    #[serde(default)]
+    pub solo: bool,
+    #[serde(default)]
+    pub sends: Vec<f64>,
+}
+
+impl ChannelSnapshot {
+    fn capture(track: &TrackState) -> Self {
+        Self {
+            name: track.name.clone(),
+            volume: track.volume,
+            pan: track.panning,
+            mute: track.mute,
+            solo: track.solo,
+            sends: track.sends.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub name: String,
+    /// Unix time in seconds.
+    pub created: u64,
+    pub tracks: Vec<ChannelSnapshot>,
+    #[serde(default)]
+    pub returns: Vec<ChannelSnapshot>,
+    pub master: ChannelSnapshot,
+    /// Custom param values by id.
+    #[serde(default)]
+    pub params: BTreeMap<String, f32>,
+}
+
+impl Snapshot {
+    pub fn capture(name: &str, state: &EngineState, params: &[ParamDef]) -> Self {
+        Self {
+            name: name.to_string(),
+            created: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            tracks: state.tracks.iter().map(ChannelSnapshot::capture).collect(),
+            returns: state
+                .return_tracks
+                .iter()
+                .map(ChannelSnapshot::capture)
+                .collect(),
+            master: ChannelSnapshot::capture(&state.master),
+            params: params
+                .iter()
+                .filter_map(|p| Some((p.id.clone(), p.value?)))
+                .collect(),
+        }
+    }
+
+    /// The edits that turn the current mixer into this snapshot.
+    pub fn diff(&self, state: &EngineState, params: &[ParamDef]) -> Vec<Edit> {
+        let current = Snapshot::capture("", state, params);
+        let mut edits = mixer_edits(&current, self, 1.0);
+        edits.retain(|edit| differs(edit, &current));
+        edits
+    }
+
+    pub fn path(dir: &Path, name: &str) -> Result<PathBuf, String> {
+        if name.trim().is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+            return Err(format!("Invalid snapshot name '{name}'"));
+        }
+        Ok(dir.join(format!("{name}.json")))
+    }
+
+    pub fn load(dir: &Path, name: &str) -> Result<Self, String> {
+        let path = Self::path(dir, name)?;
+        let content =
+            std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<(), String> {
+        let path = Self::path(dir, &self.name)?;
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(&path, content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Every readable snapshot in `dir`, oldest first.
+    pub fn list(dir: &Path) -> Vec<Self> {
+        let mut snapshots: Vec<Self> = std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok().map(|e| e.path()))
+                    .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                    .filter_map(|p| std::fs::read_to_string(p).ok())
+                    .filter_map(|content| serde_json::from_str(&content).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        snapshots.sort_by(|a: &Self, b| (a.created, &a.name).cmp(&(b.created, &b.name)));
+        snapshots
+    }
+}
+
+/// The mixer `t` of the way (0–1) from `from` to `to`.  Levels and params
+/// that differ are interpolated; a track muted in only one of the two is unmuted while
+/// morphing, so it fades rather than cuts, and solos switch at the end.
+pub fn morph_frame(from: &Snapshot, to: &Snapshot, t: f64) -> Vec<Edit> {
+    mixer_edits(from, to, t.clamp(0.0, 1.0))
+}
+
+/// Drop the mutes and solos of a morph `frame` that `state` already has,
+/// so toggles are only sent when they flip.
+pub fn drop_held_toggles(frame: &mut Vec<Edit>, state: &EngineState) {
+    let track = |i: &i32| usize::try_from(*i).ok().and_then(|i| state.tracks.get(i));
+    frame.retain(|edit| match edit {
+        Edit::TrackMute(i, on) => track(i).map_or(true, |t| t.mute != *on),
+        Edit::TrackSolo(i, on) => track(i).map_or(true, |t| t.solo != *on),
+        _ => true,
+    });
+}
+
+fn mixer_edits(from: &Snapshot, to: &Snapshot, t: f64) -> Vec<Edit> {
+    // Levels the two snapshots share are left out, so a frame only carries
+    // what's moving.
+    let lerp = |a: f64, b: f64| ((a - b).abs() > EPSILON).then_some(a + (b - a) * t);
+    let done = t >= 1.0;
+    let mut edits = Vec::new();
+    for (i, (a, b)) in from.tracks.iter().zip(&to.tracks).enumerate() {
+        let i = i as i32;
+        edits.extend(lerp(a.volume, b.volume).map(|v| Edit::TrackVolume(i, v)));
+        edits.extend(lerp(a.pan, b.pan).map(|v| Edit::TrackPan(i, v)));
+        edits.push(Edit::TrackMute(
+            i,
+            if done { b.mute } else { a.mute && b.mute },
+        ));
+        edits.push(Edit::TrackSolo(i, if done { b.solo } else { a.solo }));
+        for (s, (x, y)) in a.sends.iter().zip(&b.sends).enumerate() {
+            edits.extend(lerp(*x, *y).map(|v| Edit::TrackSend(i, s as i32, v)));
+        }
+    }
+    for (i, (a, b)) in from.returns.iter().zip(&to.returns).enumerate() {
+        edits.extend(lerp(a.volume, b.volume).map(|v| Edit::ReturnVolume(i as i32, v)));
+        edits.extend(lerp(a.pan, b.pan).map(|v| Edit::ReturnPan(i as i32, v)));
+    }
+    edits.extend(lerp(from.master.volume, to.master.volume).map(Edit::MasterVolume));
+    edits.extend(lerp(from.master.pan, to.master.pan).map(Edit::MasterPan));
+    for (id, b) in &to.params {
+        if let Some(value) = from.params.get(id).and_then(|a| lerp(*a as f64, *b as f64)) {
+            edits.push(Edit::CustomParam(id.clone(), value as f32));
+        }
+    }
+    edits
+}
+
+/// Whether `edit` changes anything relative to `current`.
+fn differs(edit: &Edit, current: &Snapshot) -> bool {
+    let far = |a: f64, b: f64| (a - b).abs() > EPSILON;
+    let track = |i: &i32| &current.tracks[*i as usize];
+    match edit {
+        Edit::TrackVolume(i, v) => far(track(i).volume, *v),
+        Edit::TrackPan(i, v) => far(track(i).pan, *v),
+        Edit::TrackMute(i, on) => track(i).mute != *on,
+        Edit::TrackSolo(i, on) => track(i).solo != *on,
+        Edit::TrackSend(i, s, v) => far(track(i).sends[*s as usize], *v),
+        Edit::ReturnVolume(i, v) => far(current.returns[*i as usize].volume, *v),
+        Edit::ReturnPan(i, v) => far(current.returns[*i as usize].pan, *v),
+        Edit::MasterVolume(v) => far(current.master.volume, *v),
+        Edit::MasterPan(v) => far(current.master.pan, *v),
+        Edit::CustomParam(id, v) => current
+            .params
+            .get(id)
+            .map_or(true, |c| far(*c as f64, *v as f64)),
+        _ => true,
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> EngineState {
+        let track = |volume: f64| TrackState {
+            volume,
+            sends: vec![0.0],
+            ..Default::default()
+        };
+        EngineState {
+            tracks: vec![track(0.5), track(0.8)],
+            return_tracks: vec![track(0.7)],
+            ..Default::default()
+        }
+    }
+
+    fn param(value: f32) -> Vec<ParamDef> {
+        vec![ParamDef {
+            id: "reverb".into(),
+            label: "Reverb".into(),
+            min: 0.0,
+            max: 1.0,
+            value: Some(value),
+        }]
+    }
+
+    #[test]
+    fn recall_diffs_only_changed_values() {
+        let mut state = state();
+        let a = Snapshot::capture("a", &state, &param(0.2));
+        assert!(a.diff(&state, &param(0.2)).is_empty());
+
+        state.tracks[1].volume = 0.3;
+        state.tracks[0].mute = true;
+        state.tracks[0].sends[0] = 0.5;
+        let mut edits = a.diff(&state, &param(0.6));
+        edits.sort_by_key(|e| format!("{e:?}"));
+        assert_eq!(
+            edits,
+            [
+                Edit::CustomParam("reverb".into(), 0.2),
+                Edit::TrackMute(0, false),
+                Edit::TrackSend(0, 0, 0.0),
+                Edit::TrackVolume(1, 0.8),
+            ]
+        );
+
+        // Channels only one side has are left alone.
+        state.tracks.pop();
+        assert!(!a
+            .diff(&state, &param(0.2))
+            .contains(&Edit::TrackVolume(1, 0.8)));
+    }
+
+    #[test]
+    fn morphing_interpolates_levels_and_fades_mutes() {
+        let mut state = state();
+        let a = Snapshot::capture("a", &state, &param(0.0));
+        state.tracks[0].volume = 1.0;
+        state.tracks[1].mute = true;
+        let b = Snapshot::capture("b", &state, &param(1.0));
+
+        let half = morph_frame(&a, &b, 0.5);
+        assert!(half.contains(&Edit::TrackVolume(0, 0.75)));
+        assert!(half.contains(&Edit::CustomParam("reverb".into(), 0.5)));
+        assert!(half.contains(&Edit::TrackMute(1, false)));
+        assert!(morph_frame(&a, &b, 1.0).contains(&Edit::TrackMute(1, true)));
+        // Levels both snapshots share aren't re-sent every frame.
+        assert!(!half.contains(&Edit::TrackVolume(1, 0.8)));
+        assert!(!half.iter().any(|e| matches!(e, Edit::TrackSend(..) | Edit::MasterPan(_))));
+
+        let mut frame = half;
+        drop_held_toggles(&mut frame, &state);
+        assert!(frame.contains(&Edit::TrackMute(1, false)));
+        assert!(!frame.contains(&Edit::TrackMute(0, false)));
+        assert!(!frame.iter().any(|e| matches!(e, Edit::TrackSolo(..))));
+        assert!(frame.contains(&Edit::TrackVolume(0, 0.75)));
+    }
+
+    #[test]
+    fn snapshots_round_trip_through_json() {
+        let snapshot = Snapshot::capture("a", &state(), &param(0.4));
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(serde_json::from_str::<Snapshot>(&json).unwrap(), snapshot);
+        assert!(Snapshot::path(&snapshots_dir(), "../x").is_err());
+    }
+}
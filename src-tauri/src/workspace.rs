@@ -0,0 +1,432 @@
+//! Per-project Maestro state.
+//!
+//! A [`Workspace`] holds what Maestro knows about a DAW project that the
+//! DAW doesn't keep itself: custom param values, the selected tracks,
+//! recent sample searches and automation lanes.  It is saved to
+//! `~/.config/maestro/workspaces/<project>/workspace.json`, next to that
+//! project's mixer snapshots.
+//!
+//! Workspaces are keyed by the project name the engine reports (Bitwig's
+//! project, Ardour's session) or, for engines that don't report one (Live
+//! through AbletonOSC), an id the user opens with `open_workspace`.
+//! [`watch`] switches workspaces when the engine reports another project,
+//! opens [`DEFAULT_WORKSPACE`] when none is reported or opened by the user,
+//! and autosaves changes once they have settled for [`AUTOSAVE_DELAY`].
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::automation::{Automation, Lane};
+use crate::engine::{EngineAdapter, StateChange, StateManager};
+use crate::palette::Palette;
+use crate::sidecar::api::SearchResultItem;
+
+/// How long a workspace must go unchanged before it is saved.
+pub const AUTOSAVE_DELAY: Duration = Duration::from_secs(2);
+
+/// How often [`watch`] records param changes and checks for pending saves.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Opened by [`watch`] when the engine reports no project.
+pub const DEFAULT_WORKSPACE: &str = "Default";
+
+/// How long [`watch`] waits for the engine to report a project before
+/// opening [`DEFAULT_WORKSPACE`].
+const DEFAULT_WORKSPACE_DELAY: Duration = Duration::from_secs(5);
+
+/// How long [`watch`] waits before trying a workspace that failed to open
+/// again.
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Searches kept per workspace, newest first.
+const MAX_SEARCHES: usize = 20;
+
+pub fn workspaces_dir() -> PathBuf {
+    crate::config::config_dir().join("workspaces")
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Workspace {
+    pub project: String,
+    /// Custom param values by id.
+    #[serde(default)]
+    pub params: BTreeMap<String, f32>,
+    #[serde(default)]
+    pub selected_tracks: Vec<usize>,
+    /// Newest first.
+    #[serde(default)]
+    pub searches: Vec<RecentSearch>,
+    /// Automation lanes recorded from control moves.
+    #[serde(default)]
+    pub automation: Vec<Lane>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentSearch {
+    /// The query text, or the file a similarity search started from.
+    pub query: String,
+    pub results: Vec<SearchResultItem>,
+}
+
+impl Workspace {
+    fn new(project: &str) -> Self {
+        Self {
+            project: project.to_string(),
+            params: BTreeMap::new(),
+            selected_tracks: Vec::new(),
+            searches: Vec::new(),
+            automation: Vec::new(),
+        }
+    }
+
+    /// Remember a search, replacing an earlier one with the same query.
+    pub fn record_search(&mut self, query: &str, results: &[SearchResultItem]) {
+        self.searches.retain(|s| s.query != query);
+        self.searches.insert(
+            0,
+            RecentSearch {
+                query: query.to_string(),
+                results: results.to_vec(),
+            },
+        );
+        self.searches.truncate(MAX_SEARCHES);
+    }
+}
+
+/// A file-system-safe directory name for `project`.
+fn dir_name(project: &str) -> Result<String, String> {
+    let name: String = project
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    if name.is_empty() || name.starts_with('.') {
+        return Err(format!("Invalid project name '{project}'"));
+    }
+    Ok(name)
+}
+
+#[derive(Default)]
+struct Inner {
+    current: Option<Workspace>,
+    /// When the current workspace first changed since it was last saved.
+    dirty_since: Option<Instant>,
+    /// When it last changed.
+    changed: Option<Instant>,
+}
+
+/// The open workspace.  Clones share it.
+#[derive(Clone)]
+pub struct Workspaces {
+    root: PathBuf,
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Workspaces {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            inner: Arc::default(),
+        }
+    }
+
+    pub fn current(&self) -> Option<Workspace> {
+        self.inner.lock().unwrap().current.clone()
+    }
+
+    fn dir(&self, project: &str) -> Result<PathBuf, String> {
+        Ok(self.root.join(dir_name(project)?))
+    }
+
+    /// Where snapshots are kept: the open workspace's folder, or the global
+    /// [`crate::snapshot::snapshots_dir`] when none is open.
+    pub fn snapshots_dir(&self) -> PathBuf {
+        self.current()
+            .and_then(|w| self.dir(&w.project).ok())
+            .map(|dir| dir.join("snapshots"))
+            .unwrap_or_else(crate::snapshot::snapshots_dir)
+    }
+
+    /// Switch to the workspace of `project`, saving the one that was open.
+    /// Starts an empty workspace if `project` has none yet.
+    pub fn open(&self, project: &str) -> Result<Workspace, String> {
+        let path = self.dir(project)?.join("workspace.json");
+        if let Some(current) = self.current().filter(|w| w.project == project) {
+            return Ok(current);
+        }
+        self.save()?;
+        let workspace = match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))?
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Workspace::new(project),
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        };
+        let mut inner = self.inner.lock().unwrap();
+        *inner = Inner {
+            current: Some(workspace.clone()),
+            ..Default::default()
+        };
+        Ok(workspace)
+    }
+
+    /// Change the open workspace, if any.  Changes are saved by [`watch`].
+    pub fn update(&self, f: impl FnOnce(&mut Workspace)) {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(workspace) = inner.current.as_mut() else {
+            return;
+        };
+        let before = workspace.clone();
+        f(workspace);
+        if *workspace != before {
+            let now = Instant::now();
+            inner.dirty_since.get_or_insert(now);
+            inner.changed = Some(now);
+        }
+    }
+
+    /// Write the open workspace if it has unsaved changes.
+    pub fn save(&self) -> Result<(), String> {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(workspace) = inner.current.as_ref() else {
+            return Ok(());
+        };
+        if inner.dirty_since.is_none() {
+            return Ok(());
+        }
+        let dir = self.dir(&workspace.project)?;
+        let path = dir.join("workspace.json");
+        std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        let content = serde_json::to_string_pretty(workspace).map_err(|e| e.to_string())?;
+        std::fs::write(&path, content).map_err(|e| format!("{}: {}", path.display(), e))?;
+        inner.dirty_since = None;
+        inner.changed = None;
+        Ok(())
+    }
+
+    /// Save once changes have settled for [`AUTOSAVE_DELAY`] — or have kept
+    /// coming for ten times that, so a steady stream still gets saved.
+    fn autosave_at(&self, now: Instant) -> Result<(), String> {
+        let due = {
+            let inner = self.inner.lock().unwrap();
+            match (inner.dirty_since, inner.changed) {
+                (Some(first), Some(last)) => {
+                    now >= last + AUTOSAVE_DELAY || now >= first + AUTOSAVE_DELAY * 10
+                }
+                _ => false,
+            }
+        };
+        if due {
+            self.save()?;
+        }
+        Ok(())
+    }
+}
+
+/// Open the workspace of `project` and push its state back out: custom
+/// params the engine currently exposes are set to their saved values, its
+/// automation lanes are loaded and the saved searches refill the command
+/// palette.  Emits
+/// `workspace-loaded`.
+pub fn activate(app: &AppHandle, project: &str) -> Result<Workspace, String> {
+    let workspaces = app.state::<Workspaces>();
+    let automation = app.state::<Automation>();
+    // Lanes still being recorded belong to the workspace being left.
+    automation.store();
+    let workspace = workspaces.open(project)?;
+
+    let engine = app.state::<Arc<dyn EngineAdapter>>();
+    for param in engine.custom_params() {
+        let Some(&value) = workspace.params.get(&param.id) else {
+            continue;
+        };
+        if param.value == Some(value) {
+            continue;
+        }
+        if let Err(e) = engine.set_custom_param(&param.id, value) {
+            log::warn!("Restoring {} for '{}': {}", param.id, project, e);
+        }
+    }
+
+    automation.load(workspace.automation.clone());
+    // Loading may have brought in lanes recorded before it opened.
+    let workspace = workspaces.current().unwrap_or(workspace);
+
+    let palette = app.state::<Palette>();
+    palette.clear_searches();
+    for search in workspace.searches.iter().rev() {
+        palette.record_search(&search.query, &search.results);
+    }
+
+    log::info!("Opened workspace '{}'", project);
+    let _ = app.emit("workspace-loaded", &workspace);
+    Ok(workspace)
+}
+
+/// Follow the project the engine reports, record custom param changes in
+/// the open workspace, and autosave.  Runs for the life of the app.
+pub async fn watch(app: AppHandle) {
+    let state = app.state::<StateManager>().inner().clone();
+    let workspaces = app.state::<Workspaces>().inner().clone();
+    let mut changes = state.subscribe();
+    let mut tick = tokio::time::interval(WATCH_INTERVAL);
+    let fallback_at = Instant::now() + DEFAULT_WORKSPACE_DELAY;
+    // The workspace `watch` last opened, and when to try the wanted one
+    // again after it failed to open.
+    let mut opened: Option<String> = None;
+    let mut retry_at = Instant::now();
+    // Param values at the last check.  Only values that move are recorded,
+    // so stale values right after a restore don't overwrite saved ones.
+    let mut seen: Option<BTreeMap<String, f32>> = None;
+    loop {
+        tokio::select! {
+            change = changes.recv() => match change {
+                Ok(StateChange::Project(_)) | Err(RecvError::Lagged(_)) => {
+                    retry_at = Instant::now();
+                }
+                Err(RecvError::Closed) => return,
+                Ok(_) => continue,
+            },
+            _ = tick.tick() => {
+                let params: BTreeMap<String, f32> = app
+                    .state::<Arc<dyn EngineAdapter>>()
+                    .custom_params()
+                    .into_iter()
+                    .filter_map(|p| Some((p.id, p.value?)))
+                    .collect();
+                if let Some(seen) = &seen {
+                    workspaces.update(|w| {
+                        for (id, value) in &params {
+                            if seen.get(id) != Some(value) {
+                                w.params.insert(id.clone(), *value);
+                            }
+                        }
+                    });
+                }
+                seen = Some(params);
+
+                app.state::<Automation>().store();
+                if let Err(e) = workspaces.autosave_at(Instant::now()) {
+                    log::warn!("Saving workspace failed: {}", e);
+                }
+            }
+        }
+
+        let now = Instant::now();
+        let wanted = state.get().project.or_else(|| {
+            (now >= fallback_at && workspaces.current().is_none())
+                .then(|| DEFAULT_WORKSPACE.to_string())
+        });
+        let Some(project) = wanted.filter(|p| opened.as_ref() != Some(p)) else {
+            continue;
+        };
+        if now < retry_at {
+            continue;
+        }
+        match activate(&app, &project) {
+            Ok(_) => {
+                opened = Some(project);
+                seen = None;
+            }
+            Err(e) => {
+                log::warn!("Opening workspace '{}' failed: {}", project, e);
+                retry_at = now + RETRY_DELAY;
+            }
+        }
+    }
+}
+
+// ── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("maestro-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn result(path: &str) -> SearchResultItem {
+        SearchResultItem {
+            id: path.into(),
+            file_path: path.into(),
+            file_name: path.into(),
+            distance: 0.0,
+            duration_seconds: 1.0,
+            metadata: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn workspaces_round_trip_per_project() {
+        let root = root("workspaces");
+        let workspaces = Workspaces::new(root.clone());
+        workspaces.update(|w| w.selected_tracks = vec![9]);
+        assert_eq!(workspaces.current(), None);
+
+        assert_eq!(workspaces.open("Song: A").unwrap(), Workspace::new("Song: A"));
+        workspaces.update(|w| {
+            w.params.insert("reverb".into(), 0.3);
+            w.selected_tracks = vec![1, 2];
+            w.record_search("kick", &[result("kick.wav")]);
+        });
+        let a = workspaces.current().unwrap();
+
+        // Switching saves the open workspace; switching back loads it.
+        workspaces.open("B").unwrap();
+        assert!(root.join("Song_ A/workspace.json").exists());
+        assert_eq!(workspaces.snapshots_dir(), root.join("B/snapshots"));
+        assert_eq!(workspaces.open("Song: A").unwrap(), a);
+        assert_eq!(Workspaces::new(root.clone()).open("Song: A").unwrap(), a);
+
+        assert!(workspaces.open("../x").is_err());
+        assert!(workspaces.open("  ").is_err());
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn autosave_waits_for_changes_to_settle() {
+        let root = root("autosave");
+        let workspaces = Workspaces::new(root.clone());
+        workspaces.open("A").unwrap();
+        let path = root.join("A/workspace.json");
+        let start = Instant::now();
+
+        workspaces.update(|w| w.selected_tracks = vec![1]);
+        workspaces.autosave_at(start).unwrap();
+        assert!(!path.exists());
+        workspaces.autosave_at(start + AUTOSAVE_DELAY * 2).unwrap();
+        assert!(path.exists());
+
+        // Unchanged values don't count as a change.
+        std::fs::remove_file(&path).unwrap();
+        workspaces.update(|w| w.selected_tracks = vec![1]);
+        workspaces.autosave_at(start + AUTOSAVE_DELAY * 4).unwrap();
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn searches_are_deduplicated_and_capped() {
+        let mut workspace = Workspace::new("A");
+        for i in 0..MAX_SEARCHES + 5 {
+            workspace.record_search(&format!("q{i}"), &[]);
+        }
+        workspace.record_search("q10", &[result("x.wav")]);
+        assert_eq!(workspace.searches.len(), MAX_SEARCHES);
+        assert_eq!(workspace.searches[0].query, "q10");
+        assert_eq!(workspace.searches.iter().filter(|s| s.query == "q10").count(), 1);
+    }
+}
@@ -0,0 +1,21 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { AutomationStatus, AutomationTarget } from "../types/automation";
+
+export async function getAutomation() {
+  return invoke<AutomationStatus>("get_automation");
+}
+
+/** Record control moves made while the transport plays. */
+export async function setAutomationRecording(enabled: boolean) {
+  return invoke("set_automation_recording", { enabled });
+}
+
+/** Play recorded lanes back in sync with the transport. */
+export async function setAutomationPlayback(enabled: boolean) {
+  return invoke("set_automation_playback", { enabled });
+}
+
+/** Clear one lane, or every lane when no target is given. */
+export async function clearAutomation(target?: AutomationTarget) {
+  return invoke("clear_automation", { target: target ?? null });
+}
@@ -0,0 +1,9 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export async function fireClip(trackIndex: number, sceneIndex: number) {
+  return invoke("fire_clip", { trackIndex, sceneIndex });
+}
+
+export async function stopClip(trackIndex: number, sceneIndex: number) {
+  return invoke("stop_clip", { trackIndex, sceneIndex });
+}
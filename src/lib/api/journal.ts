@@ -0,0 +1,19 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/** Undo the last Maestro edit, or the engine's last action if there is none. */
+export async function undo() {
+  return invoke("undo");
+}
+
+export async function redo() {
+  return invoke("redo");
+}
+
+/** Group edits into one undo step, e.g. for the length of a slider drag. */
+export async function beginTransaction() {
+  return invoke("begin_transaction");
+}
+
+export async function commitTransaction() {
+  return invoke("commit_transaction");
+}
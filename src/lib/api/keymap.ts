@@ -0,0 +1,31 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { EffectiveKeymap, KeyPressResult } from "../types/keymap";
+
+const MODIFIERS = ["Control", "Alt", "Shift", "Meta"];
+
+/** The chord for a key event in keymap notation, or null for a bare modifier. */
+export function chordFromEvent(e: KeyboardEvent): string | null {
+  if (MODIFIERS.includes(e.key)) return null;
+  const key = e.key === " " ? "Space" : e.key.length === 1 ? e.key.toUpperCase() : e.key;
+  // Shifted symbols ("?", "+") already carry Shift in the key itself.
+  const isLetter = key.length === 1 && key.toLowerCase() !== key.toUpperCase();
+  const shift = e.shiftKey && (key.length > 1 || isLetter);
+  return [e.ctrlKey && "Ctrl", e.altKey && "Alt", shift && "Shift", e.metaKey && "Meta", key]
+    .filter(Boolean)
+    .join("+");
+}
+
+/** Feed one chord to the core keymap; runs the skill of a completed binding. */
+export async function pressKey(key: string) {
+  return invoke<KeyPressResult>("press_key", { key });
+}
+
+/** Re-read `~/.config/maestro/keymap.toml`; returns errors and clashes. */
+export async function reloadKeymap() {
+  return invoke<string[]>("reload_keymap");
+}
+
+/** The effective keymap; with `path`, also written there as TOML. */
+export async function exportKeymap(path?: string) {
+  return invoke<EffectiveKeymap>("export_keymap", { path: path ?? null });
+}
@@ -0,0 +1,42 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { MidiFile, Note, NoteRegion, NoteTransform } from "../types/engine";
+
+export async function getClipNotes(trackIndex: number, sceneIndex: number) {
+  return invoke<Note[]>("get_clip_notes", { trackIndex, sceneIndex });
+}
+
+export async function addNotes(trackIndex: number, sceneIndex: number, notes: Note[]) {
+  return invoke("add_notes", { trackIndex, sceneIndex, notes });
+}
+
+/** Remove the notes inside `region`, or every note of the clip. */
+export async function removeNotes(
+  trackIndex: number,
+  sceneIndex: number,
+  region: NoteRegion | null = null
+) {
+  return invoke("remove_notes", { trackIndex, sceneIndex, region });
+}
+
+/** Quantize, transpose or change velocities; returns the rewritten notes. */
+export async function transformClipNotes(
+  trackIndex: number,
+  sceneIndex: number,
+  transform: NoteTransform
+) {
+  return invoke<Note[]>("transform_clip_notes", { trackIndex, sceneIndex, transform });
+}
+
+/** Load a `.mid` file into a clip slot, creating the clip if needed. */
+export async function importClipMidi(
+  trackIndex: number,
+  sceneIndex: number,
+  path: string,
+  useFileTempo = false
+) {
+  return invoke<MidiFile>("import_clip_midi", { trackIndex, sceneIndex, path, useFileTempo });
+}
+
+export async function exportClipMidi(trackIndex: number, sceneIndex: number, path: string) {
+  return invoke("export_clip_midi", { trackIndex, sceneIndex, path });
+}
@@ -0,0 +1,12 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { PaletteItem } from "../types/palette";
+
+/** Best matches first; empty text lists frequently and recently used items. */
+export async function paletteQuery(text: string, limit?: number) {
+  return invoke<PaletteItem[]>("palette_query", { text, limit: limit ?? null });
+}
+
+/** Rank `id` higher in future queries. */
+export async function recordPaletteUse(id: string) {
+  return invoke("palette_record_use", { id });
+}
@@ -0,0 +1,17 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { AutomationTarget } from "../types/automation";
+import type { Ramp } from "../types/ramps";
+
+/** Resolves when the ramp ends: true if it got there, false if cancelled. */
+export async function startRamp(ramp: Ramp) {
+  return invoke<boolean>("start_ramp", { ramp });
+}
+
+/** Stop one target's ramp, or every ramp when no target is given. */
+export async function cancelRamp(target?: AutomationTarget) {
+  return invoke<number>("cancel_ramp", { target: target ?? null });
+}
+
+export async function listRamps() {
+  return invoke<AutomationTarget[]>("list_ramps");
+}
@@ -0,0 +1,27 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { SceneState } from "../types/engine";
+
+export async function getScenes(): Promise<SceneState[]> {
+  return invoke("get_scenes");
+}
+
+export async function fireScene(sceneIndex: number) {
+  return invoke("fire_scene", { sceneIndex });
+}
+
+export async function stopScene(sceneIndex: number) {
+  return invoke("stop_scene", { sceneIndex });
+}
+
+/** Insert an empty scene at `sceneIndex`; -1 appends. */
+export async function createScene(sceneIndex: number = -1) {
+  return invoke("create_scene", { sceneIndex });
+}
+
+export async function duplicateScene(sceneIndex: number) {
+  return invoke("duplicate_scene", { sceneIndex });
+}
+
+export async function deleteScene(sceneIndex: number) {
+  return invoke("delete_scene", { sceneIndex });
+}
@@ -0,0 +1,12 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ScriptOutput } from "../types/scripts";
+
+/** Scripts in `~/.config/maestro/scripts/`, without the `.rhai` extension. */
+export async function listScripts() {
+  return invoke<string[]>("list_scripts");
+}
+
+/** Rejects only when the script can't be read; script errors are in `error`. */
+export async function runScript(name: string) {
+  return invoke<ScriptOutput>("run_script", { name });
+}
@@ -0,0 +1,16 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { SkillDescriptor, SkillResult } from "../types/skills";
+
+export async function listSkills() {
+  return invoke<SkillDescriptor[]>("list_skills");
+}
+
+/** Failures (unknown skill, bad params, engine errors) resolve with `success: false`. */
+export async function executeSkill(id: string, params: Record<string, unknown> = {}) {
+  return invoke<SkillResult>("execute_skill", { id, params });
+}
+
+/** Re-read macro skills from the config directory; returns load errors. */
+export async function reloadSkills() {
+  return invoke<string[]>("reload_skills");
+}
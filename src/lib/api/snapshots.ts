@@ -0,0 +1,21 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { Snapshot } from "../types/snapshots";
+
+export async function listSnapshots() {
+  return invoke<Snapshot[]>("list_snapshots");
+}
+
+/** Save the current mixer, replacing any snapshot with the same name. */
+export async function saveSnapshot(name: string) {
+  return invoke<Snapshot>("save_snapshot", { name });
+}
+
+/** Apply a snapshot as one undo step; resolves with the number of values changed. */
+export async function recallSnapshot(name: string) {
+  return invoke<number>("recall_snapshot", { name });
+}
+
+/** Glide from one snapshot to another; interrupted by any other recall or morph. */
+export async function morphSnapshots(from: string, to: string, durationMs: number) {
+  return invoke("morph_snapshots", { from, to, durationMs });
+}
@@ -15,3 +15,52 @@ export async function setTrackSolo(trackIndex: number, solo: boolean) {
 export async function setTrackPan(trackIndex: number, pan: number) {
   return invoke("set_track_pan", { trackIndex, pan });
 }
+
+/** Send `sendIndex` feeds return track `sendIndex`; `value` is 0–1. */
+export async function setTrackSend(trackIndex: number, sendIndex: number, value: number) {
+  return invoke("set_track_send", { trackIndex, sendIndex, value });
+}
+
+export async function setReturnTrackVolume(returnIndex: number, volume: number) {
+  return invoke("set_return_track_volume", { returnIndex, volume });
+}
+
+export async function setReturnTrackPan(returnIndex: number, pan: number) {
+  return invoke("set_return_track_pan", { returnIndex, pan });
+}
+
+export async function setMasterVolume(volume: number) {
+  return invoke("set_master_volume", { volume });
+}
+
+export async function setMasterPan(pan: number) {
+  return invoke("set_master_pan", { pan });
+}
+
+export type TrackKind = "audio" | "midi" | "return";
+
+/** Insert a track at `trackIndex`; -1 appends. Return tracks always append. */
+export async function createTrack(kind: TrackKind, trackIndex: number = -1) {
+  return invoke("create_track", { kind, trackIndex });
+}
+
+export async function deleteTrack(trackIndex: number) {
+  return invoke("delete_track", { trackIndex });
+}
+
+export async function duplicateTrack(trackIndex: number) {
+  return invoke("duplicate_track", { trackIndex });
+}
+
+export async function renameTrack(trackIndex: number, name: string) {
+  return invoke("rename_track", { trackIndex, name });
+}
+
+/** `color` is 0xRRGGBB. */
+export async function setTrackColor(trackIndex: number, color: number) {
+  return invoke("set_track_color", { trackIndex, color });
+}
+
+export async function setTrackArm(trackIndex: number, arm: boolean) {
+  return invoke("set_track_arm", { trackIndex, arm });
+}
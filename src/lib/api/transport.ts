@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import type { EngineFullState } from "../types/engine";
 
 export async function play() {
   return invoke("play");
@@ -16,6 +17,15 @@ export async function setTempo(bpm: number) {
   return invoke("set_tempo", { bpm });
 }
 
+export async function setSongTime(beats: number) {
+  return invoke("set_song_time", { beats });
+}
+
 export async function toggleLoop() {
   return invoke("toggle_loop");
 }
+
+/** The complete engine state, with its version. */
+export async function getEngineState() {
+  return invoke<EngineFullState>("get_engine_state");
+}
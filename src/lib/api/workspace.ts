@@ -0,0 +1,15 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { Workspace } from "../types/workspace";
+
+export async function getWorkspace() {
+  return invoke<Workspace | null>("get_workspace");
+}
+
+/** Open (or start) the workspace for a project id and restore its state. */
+export async function openWorkspace(project: string) {
+  return invoke<Workspace>("open_workspace", { project });
+}
+
+export async function setSelectedTracks(tracks: number[]) {
+  return invoke("set_selected_tracks", { tracks });
+}
@@ -0,0 +1,4 @@
+import { writable } from "svelte/store";
+
+/** The last failure to show in the status bar, e.g. of a shortcut. */
+export const statusError = writable<string | null>(null);
@@ -1,10 +1,42 @@
 import { writable } from "svelte/store";
 import { listen } from "@tauri-apps/api/event";
-import type { TrackState } from "../types/engine";
+import type { ClipState, TrackState } from "../types/engine";
 
 export const tracks = writable<TrackState[]>([]);
+export const returnTracks = writable<TrackState[]>([]);
+export const masterTrack = writable<TrackState | null>(null);
 export const selectedTrackIndex = writable<number | null>(null);
 
 listen<TrackState[]>("tracks-updated", (event) => {
   tracks.set(event.payload);
 });
+
+// Frames that only move meters carry just the levels.
+listen<{ index: number; level: number }[]>("meters-updated", (event) => {
+  tracks.update((all) => {
+    for (const { index, level } of event.payload) {
+      if (all[index]) all[index].meterLevel = level;
+    }
+    return all;
+  });
+});
+
+listen<TrackState[]>("return-tracks-updated", (event) => {
+  returnTracks.set(event.payload);
+});
+
+listen<TrackState>("master-updated", (event) => {
+  masterTrack.set(event.payload);
+});
+
+// Clip launch state arrives one slot at a time.
+listen<ClipState>("clip-changed", (event) => {
+  const clip = event.payload;
+  tracks.update((all) => {
+    const track = all[clip.trackIndex];
+    if (track) {
+      track.clips[clip.sceneIndex] = clip;
+    }
+    return all;
+  });
+});
@@ -0,0 +1,23 @@
+import { get, writable } from "svelte/store";
+import { listen } from "@tauri-apps/api/event";
+import type { Workspace } from "../types/workspace";
+import { getWorkspace, setSelectedTracks } from "../api/workspace";
+import { selectedTrackIndex } from "./tracks";
+
+/** The open workspace, or null before one is opened. */
+export const workspace = writable<Workspace | null>(null);
+
+function loaded(w: Workspace | null) {
+  workspace.set(w);
+  if (w) selectedTrackIndex.set(w.selected_tracks[0] ?? null);
+}
+
+getWorkspace().then(loaded);
+
+listen<Workspace>("workspace-loaded", (event) => loaded(event.payload));
+
+// Remember the selection in the open workspace.
+selectedTrackIndex.subscribe((index) => {
+  if (!get(workspace)) return;
+  setSelectedTracks(index === null ? [] : [index]);
+});
@@ -0,0 +1,26 @@
+/** What an automation lane controls. */
+export type AutomationTarget =
+  | { kind: "track_volume"; track: number }
+  | { kind: "track_pan"; track: number }
+  | { kind: "track_mute"; track: number }
+  | { kind: "track_solo"; track: number }
+  | { kind: "track_send"; track: number; send: number }
+  | { kind: "custom_param"; id: string };
+
+export interface AutomationPoint {
+  /** Song position in beats. */
+  beat: number;
+  value: number;
+}
+
+export interface AutomationLane {
+  target: AutomationTarget;
+  /** Sorted by beat. */
+  points: AutomationPoint[];
+}
+
+export interface AutomationStatus {
+  recording: boolean;
+  playing: boolean;
+  lanes: AutomationLane[];
+}
@@ -25,6 +25,8 @@ export interface TrackState {
   color: number;
   meterLevel: number;
   clips: ClipState[];
+  /** Send levels (0–1), one per return track. */
+  sends: number[];
 }
 
 export interface ClipState {
@@ -37,7 +39,46 @@ export interface ClipState {
   isTriggered: boolean;
 }
 
+export interface SceneState {
+  index: number;
+  name: string;
+  color: number;
+}
+
+/** A MIDI note; `start` and `duration` are in beats from the clip start. */
+export interface Note {
+  pitch: number;
+  start: number;
+  duration: number;
+  velocity: number;
+  mute: boolean;
+}
+
+export interface NoteRegion {
+  start_pitch: number;
+  pitch_span: number;
+  start_time: number;
+  time_span: number;
+}
+
+export type NoteTransform =
+  | { kind: "quantize"; grid: number; strength?: number }
+  | { kind: "transpose"; semitones: number }
+  | { kind: "scale_velocity"; factor: number }
+  | { kind: "humanize_velocity"; amount: number; seed?: number };
+
+/** A parsed Standard MIDI File, as returned by `importClipMidi`. */
+export interface MidiFile {
+  format: number;
+  notes: Note[];
+  tempo: number | null;
+  time_signature: { numerator: number; denominator: number } | null;
+}
+
+/** Returned by `getEngineState`. */
 export interface EngineFullState {
+  /** Number of changes made to the state so far. */
+  version: number;
   isPlaying: boolean;
   tempo: number;
   currentTime: number;
@@ -47,4 +88,44 @@ export interface EngineFullState {
   numTracks: number;
   numScenes: number;
   tracks: TrackState[];
+  scenes: SceneState[];
+  returnTracks: TrackState[];
+  master: TrackState;
+  /** Open project name, for engines that report one. */
+  project: string | null;
+}
+
+export type TrackField =
+  | { field: "name"; value: string }
+  | { field: "volume" | "pan" | "meter"; value: number }
+  | { field: "mute" | "solo" | "arm"; value: boolean }
+  | { field: "color"; value: number }
+  /** Send index and level. */
+  | { field: "send"; value: [number, number] };
+
+/**
+ * One value that changed.  The `state-changed` event carries a batch of
+ * these, at most one per value since the previous batch.
+ */
+export type StateChange =
+  | { kind: "playing" | "loop"; value: boolean }
+  | { kind: "tempo" | "song_time"; value: number }
+  | { kind: "project"; value: string }
+  /** Tracks, scenes or return tracks were added or removed; the new count. */
+  | { kind: "track_list" | "scene_list" | "return_track_list"; value: number }
+  | { kind: "track"; value: { index: number; field: TrackField } }
+  | { kind: "clip"; value: ClipState }
+  | { kind: "scene"; value: SceneState }
+  | { kind: "return_track" | "master"; value: TrackState };
+
+/** Payload of the `state-changed` event. */
+export interface StateBatch {
+  /** State version once these changes are applied. */
+  version: number;
+  /**
+   * Version of the previous batch, or null if changes were dropped in
+   * between.  If it isn't the version you last saw, reread the state.
+   */
+  since: number | null;
+  changes: StateChange[];
 }
@@ -0,0 +1,26 @@
+import type { SkillResult } from "./skills";
+
+export interface Binding {
+  /** Normalized chords separated by spaces, e.g. `"\\ T"` or `"Ctrl+Z"`. */
+  keys: string;
+  skill: string;
+  params: Record<string, unknown>;
+  source: "default" | "user";
+}
+
+export interface KeyConflict {
+  kept: Binding;
+  dropped: Binding;
+}
+
+export interface EffectiveKeymap {
+  leader: string;
+  bindings: Binding[];
+  conflicts: KeyConflict[];
+  errors: string[];
+}
+
+export type KeyPressResult =
+  | { status: "run"; binding: Binding; result: SkillResult | null }
+  | { status: "pending"; keys: string; result: null }
+  | { status: "unbound"; keys: string; result: null };
@@ -0,0 +1,19 @@
+export type PaletteAction =
+  | { kind: "runSkill"; skill: string; params: Record<string, unknown> }
+  | { kind: "selectTrack"; track: number }
+  | { kind: "fireClip"; track: number; scene: number }
+  | { kind: "fireScene"; scene: number }
+  | { kind: "runScript"; name: string }
+  | { kind: "insertSample"; path: string };
+
+export interface PaletteItem {
+  /** Pass to `recordPaletteUse` when the item is run. */
+  id: string;
+  title: string;
+  subtitle: string;
+  shortcut: string | null;
+  action: PaletteAction;
+  score: number;
+  /** Character positions in `title` that matched the query. */
+  matches: number[];
+}
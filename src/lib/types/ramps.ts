@@ -0,0 +1,17 @@
+import type { AutomationTarget } from "./automation";
+
+export type RampCurve = "linear" | "exponential" | "s_curve";
+
+export interface Ramp {
+  /** A track volume, pan or send, or a custom param; not mute or solo. */
+  target: AutomationTarget;
+  /**
+   * Start value; defaults to the current one. Engines that don't report
+   * values (MIDI, Zrythm, Ardour's custom params) need it.
+   */
+  from?: number;
+  to: number;
+  durationMs: number;
+  /** Defaults to linear. */
+  curve?: RampCurve;
+}
@@ -0,0 +1,8 @@
+export interface ScriptOutput {
+  /** Lines the script printed. */
+  output: string[];
+  /** Value of the script's last expression. */
+  value: unknown;
+  /** Compile error, thrown error or exceeded limit. */
+  error: string | null;
+}
@@ -18,6 +18,7 @@ export type SkillCategory =
   | "Transport"
   | "Track"
   | "Clip"
+  | "Param"
   | "Search"
   | "Utility";
 
@@ -33,4 +34,6 @@ export interface SkillDescriptor {
 export interface SkillResult {
   success: boolean;
   message: string;
+  /** Skill output, e.g. search results. */
+  data?: unknown;
 }
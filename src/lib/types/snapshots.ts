@@ -0,0 +1,19 @@
+export interface ChannelSnapshot {
+  name: string;
+  volume: number;
+  pan: number;
+  mute: boolean;
+  solo: boolean;
+  sends: number[];
+}
+
+export interface Snapshot {
+  name: string;
+  /** Unix time in seconds. */
+  created: number;
+  tracks: ChannelSnapshot[];
+  returns: ChannelSnapshot[];
+  master: ChannelSnapshot;
+  /** Custom param values by id. */
+  params: Record<string, number>;
+}
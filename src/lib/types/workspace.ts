@@ -0,0 +1,18 @@
+import type { AutomationLane } from "./automation";
+import type { SearchResultItem } from "./search";
+
+export interface RecentSearch {
+  /** Query text, or the file a similarity search started from. */
+  query: string;
+  results: SearchResultItem[];
+}
+
+export interface Workspace {
+  project: string;
+  /** Custom param values by id. */
+  params: Record<string, number>;
+  selected_tracks: number[];
+  /** Newest first. */
+  searches: RecentSearch[];
+  automation: AutomationLane[];
+}